@@ -104,7 +104,7 @@ struct Args {
     pub network_magic: u64,
     /// A list of points to use when trying to decide a startpoint; defaults to
     /// origin
-    #[arg(short, long, value_parser = parse_point)]
+    #[arg(short, long)]
     pub point: Vec<Point>,
     /// Download only the first block found that matches this criteria
     #[arg(long)]
@@ -127,16 +127,6 @@ impl Args {
     }
 }
 
-pub fn parse_point(s: &str) -> Result<Point, Box<dyn std::error::Error + Send + Sync + 'static>> {
-    if s == "origin" {
-        return std::result::Result::Ok(Point::Origin);
-    }
-    let parts: Vec<_> = s.split('/').collect();
-    let slot = parts[0].parse()?;
-    let hash = hex::decode(parts[1])?;
-    std::result::Result::Ok(Point::Specific(slot, hash))
-}
-
 fn save_file<P: AsRef<Path>>(filename: P, bytes: &[u8]) -> Result<()> {
     std::fs::write(filename, bytes).context("couldn't write file")
 }
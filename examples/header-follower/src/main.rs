@@ -0,0 +1,90 @@
+use anyhow::*;
+use clap::Parser;
+use pallas::{
+    ledger::traverse::{MultiEraBlock, MultiEraHeader},
+    network::{
+        facades::PeerClient,
+        miniprotocols::{chainsync::NextResponse, Point},
+    },
+};
+
+// An arbitrary predicate to decide whether to fetch the body of the block or
+// not; fill in with your own purpose built logic
+fn header_matches(header: &MultiEraHeader, issuer: Option<&[u8]>) -> bool {
+    // As an example, we fetch the blocks minted by a particular issuer, or all of
+    // them if none was specified
+    match issuer {
+        Some(issuer) => header.issuer_vkey() == Some(issuer),
+        None => true,
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+
+    let issuer = args
+        .issuer
+        .as_deref()
+        .map(hex::decode)
+        .transpose()
+        .context("invalid issuer key")?;
+
+    let mut peer = PeerClient::connect(&args.relay, args.network_magic).await?;
+
+    // Find an intersection point using the points on the command line, or
+    // start from the origin if there aren't any
+    let mut headers = peer.follow_headers(args.point).await?;
+
+    loop {
+        match headers.next_header().await? {
+            NextResponse::RollForward(content, _) => {
+                // Decode the header from the chain-sync wrapper; this is much
+                // cheaper than decoding the whole block
+                let header = content.decode()?;
+
+                // Epoch boundary blocks don't hold any transactions
+                if matches!(header, MultiEraHeader::EpochBoundary(_))
+                    || !header_matches(&header, issuer.as_deref())
+                {
+                    continue;
+                }
+
+                let body = headers.fetch_block_of(&header).await?;
+                let block = MultiEraBlock::decode(&body)?;
+
+                println!(
+                    "block {} at slot {} ({}) has {} txs",
+                    block.number(),
+                    block.slot(),
+                    block.hash(),
+                    block.tx_count()
+                );
+            }
+            NextResponse::RollBackward(point, _) => println!("rollback to {point:?}"),
+            // Once we've caught up, the next header arrives whenever the peer
+            // adopts a new block
+            NextResponse::Await => println!("tip of chain reached"),
+        }
+    }
+}
+
+/// A small utility to follow the Cardano blockchain by headers, downloading
+/// only the blocks it needs
+#[derive(Parser)]
+struct Args {
+    /// The address of the relay node to connect to
+    #[arg(short, long, default_value = "backbone.cardano.iog.io:3001")]
+    pub relay: String,
+    /// The network magic used to handshake with that node; defaults to mainnet
+    #[arg(short, long, env("CARDANO_NETWORK_MAGIC"), default_value_t = 764824073)]
+    pub network_magic: u64,
+    /// A list of points to use when trying to decide a startpoint; defaults to
+    /// origin
+    #[arg(short, long)]
+    pub point: Vec<Point>,
+    /// Only fetch the blocks minted by the pool with this (hex encoded) issuer
+    /// key
+    #[arg(short, long)]
+    pub issuer: Option<String>,
+}
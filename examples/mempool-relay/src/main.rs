@@ -0,0 +1,190 @@
+use std::collections::{HashSet, VecDeque};
+
+use anyhow::{bail, Context as _};
+use clap::Parser;
+use pallas::{
+    ledger::traverse::MultiEraTx,
+    network::{
+        facades::{NodeClient, PeerClient},
+        miniprotocols::{
+            txsubmission::{self, EraTxBody, EraTxId, Request, TxIdAndSize},
+            PRE_PRODUCTION_MAGIC,
+        },
+    },
+};
+use tokio::sync::mpsc;
+use tracing::{debug, info};
+
+/// Max number of txs waiting to be announced to the peer. Once full, the
+/// mempool monitor stops pulling txs until the peer catches up.
+const RELAY_QUEUE_SIZE: usize = 100;
+
+#[derive(Parser)]
+struct Args {
+    /// Path of the unix socket of the local node
+    #[arg(long)]
+    socket: String,
+
+    /// Address of the peer receiving the txs, as host:port
+    #[arg(long)]
+    peer: String,
+
+    /// Network magic of both the node and the peer
+    #[arg(long, default_value_t = PRE_PRODUCTION_MAGIC)]
+    magic: u64,
+}
+
+#[derive(Debug, Clone)]
+struct MempoolTx {
+    id: EraTxId,
+    body: EraTxBody,
+}
+
+/// Pulls the txs of each new snapshot of the local mempool and queues the
+/// ones that weren't present in the previous snapshot
+async fn monitor_mempool(
+    mut node: NodeClient,
+    queue: mpsc::Sender<MempoolTx>,
+) -> anyhow::Result<()> {
+    let monitor = node.monitor();
+    let mut known = HashSet::new();
+
+    loop {
+        // after the first snapshot, this waits for the mempool to change
+        let slot = monitor.acquire().await?;
+        debug!(slot, "acquired mempool snapshot");
+
+        let mut current = HashSet::new();
+
+        while let Some((era, body)) = monitor.query_next_tx().await? {
+            let cbor = body.0.to_vec();
+
+            let hash = MultiEraTx::decode(&cbor)
+                .context("decoding mempool tx")?
+                .hash();
+
+            current.insert(hash);
+
+            if known.contains(&hash) {
+                continue;
+            }
+
+            info!(%hash, "new tx in mempool");
+
+            let tx = MempoolTx {
+                id: EraTxId(era as u16, hash.to_vec()),
+                body: EraTxBody(era as u16, cbor),
+            };
+
+            queue.send(tx).await.context("relay is gone")?;
+        }
+
+        known = current;
+    }
+}
+
+/// Moves queued txs into the batch, without waiting, up to `limit` txs
+fn take_queued(queue: &mut mpsc::Receiver<MempoolTx>, batch: &mut Vec<MempoolTx>, limit: usize) {
+    while batch.len() < limit {
+        match queue.try_recv() {
+            Ok(tx) => batch.push(tx),
+            Err(_) => break,
+        }
+    }
+}
+
+/// Offers the queued txs to the peer, following the requests of its
+/// tx-submission server
+async fn relay_txs(
+    mut peer: PeerClient,
+    mut queue: mpsc::Receiver<MempoolTx>,
+) -> anyhow::Result<()> {
+    let client = peer.txsubmission();
+    client.send_init().await?;
+
+    // txs announced to the peer and not acknowledged yet, oldest first
+    let mut unacked: VecDeque<MempoolTx> = VecDeque::new();
+
+    loop {
+        let (ack, req, blocking) = match client.next_request().await? {
+            Request::TxIds(ack, req) => (ack, req, true),
+            Request::TxIdsNonBlocking(ack, req) => (ack, req, false),
+            Request::Txs(ids) => {
+                let bodies: Vec<_> = ids
+                    .iter()
+                    .filter_map(|id| unacked.iter().find(|tx| &tx.id == id))
+                    .map(|tx| tx.body.clone())
+                    .collect();
+
+                info!(requested = ids.len(), sent = bodies.len(), "replying txs");
+                client.reply_txs(bodies).await?;
+                continue;
+            }
+        };
+
+        if ack as usize > unacked.len() {
+            bail!(
+                "peer acknowledged {ack} txs but only {} are pending",
+                unacked.len()
+            );
+        }
+
+        unacked.drain(..ack as usize);
+
+        let mut batch = vec![];
+
+        // a blocking request must be answered with at least one tx, so we
+        // wait until the mempool gives us something to relay
+        if blocking {
+            match queue.recv().await {
+                Some(tx) => batch.push(tx),
+                None => {
+                    client.send_done().await?;
+                    return Ok(());
+                }
+            }
+        }
+
+        take_queued(&mut queue, &mut batch, req as usize);
+
+        let ids = batch
+            .iter()
+            .map(|tx| {
+                TxIdAndSize(
+                    tx.id.clone(),
+                    tx.body.1.len() as txsubmission::TxSizeInBytes,
+                )
+            })
+            .collect();
+
+        debug!(ack, req, announced = batch.len(), "replying tx ids");
+        client.reply_tx_ids(ids).await?;
+
+        unacked.extend(batch);
+    }
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing::subscriber::set_global_default(
+        tracing_subscriber::FmtSubscriber::builder()
+            .with_max_level(tracing::Level::INFO)
+            .finish(),
+    )?;
+
+    let args = Args::parse();
+
+    let node = NodeClient::connect(&args.socket, args.magic)
+        .await
+        .context("connecting to local node")?;
+
+    let peer = PeerClient::connect(&args.peer, args.magic)
+        .await
+        .context("connecting to peer")?;
+
+    let (sender, receiver) = mpsc::channel(RELAY_QUEUE_SIZE);
+
+    tokio::try_join!(monitor_mempool(node, sender), relay_txs(peer, receiver))?;
+
+    Ok(())
+}
@@ -135,16 +135,12 @@ async fn do_chainsync(client: &mut NodeClient) {
         hex::decode("18e6eeaa592c42113280ba47a0829355e6bed1c9ce67cce4be502d6031d0679a").unwrap(),
     )];
 
-    let (point, _) = client
-        .chainsync()
-        .find_intersect(known_points)
-        .await
-        .unwrap();
+    let mut blocks = client.follow_blocks(known_points).await.unwrap();
 
-    info!("intersected point is {:?}", point);
+    info!("intersected point is {:?}", blocks.intersection());
 
     loop {
-        let next = client.chainsync().request_or_await_next().await.unwrap();
+        let next = blocks.next_block().await.unwrap();
         match next {
             chainsync::NextResponse::RollForward(h, _) => {
                 let block_number = MultiEraBlock::decode(&h).unwrap().number();
@@ -67,33 +67,24 @@ async fn do_chainsync(
         match next {
             chainsync::NextResponse::RollForward(h, _) => {
                 tracing::trace!("rolling forward, header size: {}", h.cbor.len());
-                let point = match h.byron_prefix {
-                    None => {
-                        let multi_era_header = MultiEraHeader::decode(h.variant, None, &h.cbor)?;
-                        let slot = multi_era_header.slot();
-                        let hash = multi_era_header.hash().to_vec();
-                        let number = multi_era_header.number();
-                        match &multi_era_header {
-                            MultiEraHeader::EpochBoundary(_) => {
-                                tracing::info!("epoch boundary");
-                                None
-                            }
-                            MultiEraHeader::ShelleyCompatible(_)
-                            | MultiEraHeader::BabbageCompatible(_) => {
-                                if next_log.elapsed().as_secs() > 1 {
-                                    tracing::info!("chainsync block header: {}", number);
-                                    next_log = Instant::now();
-                                }
-                                Some(Point::Specific(slot, hash))
-                            }
-                            MultiEraHeader::Byron(_) => {
-                                tracing::info!("ignoring byron header");
-                                None
-                            }
+                let multi_era_header = h.decode()?;
+                let slot = multi_era_header.slot();
+                let hash = multi_era_header.hash().to_vec();
+                let number = multi_era_header.number();
+                let point = match &multi_era_header {
+                    MultiEraHeader::EpochBoundary(_) => {
+                        tracing::info!("epoch boundary");
+                        None
+                    }
+                    MultiEraHeader::ShelleyCompatible(_) | MultiEraHeader::BabbageCompatible(_) => {
+                        if next_log.elapsed().as_secs() > 1 {
+                            tracing::info!("chainsync block header: {}", number);
+                            next_log = Instant::now();
                         }
+                        Some(Point::Specific(slot, hash))
                     }
-                    Some(_) => {
-                        tracing::info!("skipping byron block");
+                    MultiEraHeader::Byron(_) => {
+                        tracing::info!("ignoring byron header");
                         None
                     }
                 };
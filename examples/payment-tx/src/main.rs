@@ -0,0 +1,236 @@
+use anyhow::{bail, Context as _};
+use clap::Parser;
+use pallas::{
+    crypto::hash::Hasher,
+    ledger::addresses::{
+        Address, Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+    },
+    network::{
+        facades::NodeClient,
+        miniprotocols::{
+            localstate::queries_v16::{self, TransactionOutput, UTxO},
+            localtxsubmission::{EraTx, Response},
+            MAINNET_MAGIC, PRE_PRODUCTION_MAGIC,
+        },
+    },
+    txbuilder::{BuildConway, BuiltTransaction, Input, Output, StagingTransaction},
+    wallet::keystore::hd::Bip32PrivateKey,
+};
+
+/// Minimum amount of lovelace left for the change output
+const MIN_CHANGE: u64 = 2_000_000;
+
+/// Max attempts at converging into a fee that covers the tx size
+const MAX_FEE_ROUNDS: usize = 5;
+
+#[derive(Parser)]
+struct Args {
+    /// Path of the unix socket of the local node
+    #[arg(long)]
+    socket: String,
+
+    /// Network magic of the node
+    #[arg(long, default_value_t = PRE_PRODUCTION_MAGIC)]
+    magic: u64,
+
+    /// BIP-39 mnemonic of the paying wallet
+    #[arg(long, env = "MNEMONIC", hide_env_values = true)]
+    mnemonic: String,
+
+    /// Address receiving the payment, in bech32
+    #[arg(long)]
+    to: String,
+
+    /// Amount of lovelace to send
+    #[arg(long)]
+    amount: u64,
+
+    /// Print the signed tx instead of submitting it
+    #[arg(long)]
+    dry_run: bool,
+}
+
+const fn harden(index: u32) -> u32 {
+    index | 0x80_00_00_00
+}
+
+struct Wallet {
+    payment: Bip32PrivateKey,
+    address: Address,
+}
+
+impl Wallet {
+    /// Derives the first address of the first account of a mnemonic
+    fn from_mnemonic(mnemonic: String, network: Network) -> anyhow::Result<Self> {
+        let root = Bip32PrivateKey::from_bip39_mnenomic(mnemonic, "".into())?;
+
+        let account = root
+            .derive(harden(1852))
+            .derive(harden(1815))
+            .derive(harden(0));
+
+        let payment = account.derive(0).derive(0);
+        let stake = account.derive(2).derive(0);
+
+        let key_hash = |key: &Bip32PrivateKey| {
+            Hasher::<224>::hash(key.to_public().to_ed25519_pubkey().as_ref())
+        };
+
+        let address = ShelleyAddress::new(
+            network,
+            ShelleyPaymentPart::key_hash(key_hash(&payment)),
+            ShelleyDelegationPart::key_hash(key_hash(&stake)),
+        );
+
+        Ok(Self {
+            payment,
+            address: address.into(),
+        })
+    }
+
+    fn sign(&self, tx: BuiltTransaction) -> anyhow::Result<BuiltTransaction> {
+        Ok(tx.sign(self.payment.to_ed25519_private_key())?)
+    }
+}
+
+/// Picks the largest UTxOs until their lovelace covers the target amount
+fn select_inputs(
+    mut utxos: Vec<(UTxO, TransactionOutput)>,
+    target: u64,
+) -> anyhow::Result<Vec<(UTxO, TransactionOutput)>> {
+    utxos.sort_by_key(|(_, output)| std::cmp::Reverse(output.amount().coin()));
+
+    let mut selected = vec![];
+    let mut total = 0;
+
+    for utxo in utxos {
+        if total >= target {
+            break;
+        }
+
+        total += utxo.1.amount().coin();
+        selected.push(utxo);
+    }
+
+    if total < target {
+        bail!("not enough funds, need {target} lovelace but only have {total}");
+    }
+
+    Ok(selected)
+}
+
+/// Builds the signed payment tx paying the given fee
+fn build_tx(
+    wallet: &Wallet,
+    inputs: &[(UTxO, TransactionOutput)],
+    to: &Address,
+    amount: u64,
+    fee: u64,
+) -> anyhow::Result<BuiltTransaction> {
+    let total: u64 = inputs.iter().map(|(_, x)| x.amount().coin()).sum();
+
+    let mut change = Output::new(wallet.address.clone(), total - amount - fee);
+
+    let assets = inputs.iter().filter_map(|(_, x)| x.amount().assets());
+
+    for policies in assets {
+        for (policy, names) in policies.iter() {
+            for (name, quantity) in names.iter() {
+                change = change.add_asset(*policy, name.to_vec(), quantity.into())?;
+            }
+        }
+    }
+
+    let mut staging = StagingTransaction::new()
+        .output(Output::new(to.clone(), amount))
+        .output(change)
+        .fee(fee);
+
+    for (utxo, _) in inputs {
+        staging = staging.input(Input::new(utxo.transaction_id, (&utxo.index).into()));
+    }
+
+    wallet.sign(staging.build_conway_raw()?)
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let network = match args.magic {
+        MAINNET_MAGIC => Network::Mainnet,
+        _ => Network::Testnet,
+    };
+
+    let wallet = Wallet::from_mnemonic(args.mnemonic, network)?;
+    let to = Address::from_bech32(&args.to).context("invalid target address")?;
+
+    println!("paying from {}", wallet.address);
+
+    let mut client = NodeClient::connect(&args.socket, args.magic)
+        .await
+        .context("can't connect to node")?;
+
+    let query = client.statequery();
+    query.acquire(None).await?;
+
+    let era = queries_v16::get_current_era(query).await?;
+
+    let utxos = queries_v16::get_utxo_by_address(query, era, vec![wallet.address.to_vec().into()])
+        .await?
+        .utxo
+        .to_vec();
+
+    let pparams = queries_v16::get_current_pparams(query, era)
+        .await?
+        .pop()
+        .context("node returned no protocol params")?;
+
+    query.send_release().await?;
+
+    let (Some(fee_a), Some(fee_b)) = (pparams.minfee_a, pparams.minfee_b) else {
+        bail!("protocol params are missing the fee coefficients");
+    };
+
+    // the final fee is unknown until the tx is signed, so the selection
+    // reserves some room for it on top of the minimum change
+    let inputs = select_inputs(utxos, args.amount + MIN_CHANGE + fee_b as u64 * 2)?;
+
+    let mut fee = fee_b as u64;
+
+    let tx = 'converge: {
+        for _ in 0..MAX_FEE_ROUNDS {
+            let tx = build_tx(&wallet, &inputs, &to, args.amount, fee)?;
+            let required = fee_a as u64 * tx.tx_bytes.0.len() as u64 + fee_b as u64;
+
+            if required <= fee {
+                break 'converge tx;
+            }
+
+            fee = required;
+        }
+
+        bail!("fee didn't converge after {MAX_FEE_ROUNDS} rounds");
+    };
+
+    println!(
+        "tx {} pays a fee of {fee} lovelace",
+        hex::encode(tx.tx_hash.0)
+    );
+
+    if args.dry_run {
+        println!("{}", hex::encode(&tx.tx_bytes.0));
+        return Ok(());
+    }
+
+    match client
+        .submission()
+        .submit_tx(EraTx(era, tx.tx_bytes.0))
+        .await?
+    {
+        Response::Accepted => println!("tx accepted by the node"),
+        Response::Rejected(reason) => bail!("tx rejected: {}", hex::encode(reason.0)),
+    }
+
+    Ok(())
+}
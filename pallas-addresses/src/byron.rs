@@ -217,7 +217,6 @@ pub struct AddressPayload {
     pub addrtype: AddrType,
 }
 
-use cryptoxide::hashing::sha3_256;
 impl AddressPayload {
     pub fn hash_address_id(
         addrtype: &AddrType,
@@ -225,9 +224,9 @@ impl AddressPayload {
         attributes: &AddrAttrs,
     ) -> Hash<28> {
         let parts = (addrtype, spending_data, attributes);
-        let buf = minicbor::to_vec(parts).unwrap();
+        let digest = pallas_crypto::hash::Sha3Hasher::hash_cbor(&parts);
 
-        pallas_crypto::hash::Hasher::<224>::hash(&sha3_256(&buf))
+        pallas_crypto::hash::Hasher::<224>::hash(digest.as_ref())
     }
 
     pub fn new(addrtype: AddrType, spending_data: SpendingData, attributes: AddrAttrs) -> Self {
@@ -238,6 +237,18 @@ impl AddressPayload {
         }
     }
 
+    /// Build the payload of a public key address
+    ///
+    /// `xpub` is the extended public key (the ed25519 public key followed by
+    /// its chain code) that controls the address. `attributes` usually holds
+    /// the network tag for non-mainnet addresses and, for legacy (Daedalus)
+    /// wallets, the encrypted derivation path.
+    pub fn new_pubkey(xpub: &[u8; 64], attributes: AddrAttrs) -> Self {
+        let spending_data = SpendingData::PubKey(ByteVec::from(Vec::from(xpub.as_slice())));
+
+        Self::new(AddrType::PubKey, spending_data, attributes)
+    }
+
     // bootstrap era + no hdpayload address
     pub fn new_redeem(
         pubkey: pallas_crypto::key::ed25519::PublicKey,
@@ -295,8 +306,30 @@ impl ByronAddress {
         ByronAddress::new(&payload, c)
     }
 
+    /// Build a public key address from its extended public key and attributes
+    ///
+    /// See [`AddressPayload::new_pubkey`] for the meaning of each argument.
+    pub fn from_pubkey(xpub: &[u8; 64], attributes: AddrAttrs) -> Self {
+        AddressPayload::new_pubkey(xpub, attributes).into()
+    }
+
+    /// Decode an address from its CBOR bytes, validating its checksum
     pub fn from_bytes(value: &[u8]) -> Result<Self, Error> {
-        pallas_codec::minicbor::decode(value).map_err(Error::InvalidByronCbor)
+        let addr: Self = pallas_codec::minicbor::decode(value).map_err(Error::InvalidByronCbor)?;
+        addr.validate_crc()?;
+
+        Ok(addr)
+    }
+
+    /// Check that the CRC matches the payload of the address
+    pub fn validate_crc(&self) -> Result<(), Error> {
+        let expected = CRC.checksum(&self.payload.0);
+
+        if expected != self.crc {
+            return Err(Error::InvalidByronCrc(expected, self.crc));
+        }
+
+        Ok(())
     }
 
     // Tries to decode an address from its hex representation
@@ -367,4 +400,56 @@ mod tests {
             assert_eq!(crc2, addr.crc);
         }
     }
+
+    #[test]
+    fn bad_crc_is_rejected() {
+        for vector in TEST_VECTORS {
+            let addr = ByronAddress::from_base58(vector).unwrap();
+            let corrupted = ByronAddress::new(addr.payload.as_ref(), addr.crc ^ 1);
+
+            assert!(matches!(
+                ByronAddress::from_base58(&corrupted.to_base58()),
+                Err(Error::InvalidByronCrc(expected, found)) if expected == addr.crc && found == addr.crc ^ 1
+            ));
+
+            assert!(matches!(
+                crate::Address::from_bytes(&corrupted.to_vec()),
+                Err(Error::InvalidByronCrc(..))
+            ));
+
+            assert!(matches!(
+                corrupted.to_base58().parse::<crate::Address>(),
+                Err(Error::InvalidByronCrc(..))
+            ));
+        }
+    }
+
+    #[test]
+    fn pubkey_address_from_parts() {
+        let xpub = [7u8; 64];
+        let network_tag =
+            AddrAttrProperty::NetworkTag(ByteVec::from(vec![0x1a, 0x2d, 0x96, 0x4a, 0x09]));
+
+        for attributes in [vec![], vec![network_tag]] {
+            let attributes = AddrAttrs::from(attributes);
+            let addr = ByronAddress::from_pubkey(&xpub, attributes.clone());
+
+            assert_eq!(CRC.checksum(addr.payload.as_ref()), addr.crc);
+
+            let roundtrip = ByronAddress::from_base58(&addr.to_base58()).unwrap();
+            assert_eq!(roundtrip, addr);
+
+            let payload = addr.decode().unwrap();
+            assert_eq!(payload.addrtype, AddrType::PubKey);
+            assert_eq!(payload.attributes, attributes);
+
+            let spending_data = SpendingData::PubKey(ByteVec::from(xpub.to_vec()));
+            let root =
+                AddressPayload::hash_address_id(&AddrType::PubKey, &spending_data, &attributes);
+            assert_eq!(payload.root, root);
+        }
+
+        let mainnet = ByronAddress::from_pubkey(&xpub, vec![].into());
+        assert!(mainnet.to_base58().starts_with("Ae2"));
+    }
 }
@@ -44,6 +44,9 @@ pub enum Error {
     #[error("invalid CBOR for Byron address {0}")]
     InvalidByronCbor(pallas_codec::minicbor::decode::Error),
 
+    #[error("invalid CRC for Byron address, expected {0:08x} but found {1:08x}")]
+    InvalidByronCrc(u32, u32),
+
     #[error("unkown hrp for network {0:08b}")]
     UnknownNetworkHrp(u8),
 
@@ -396,7 +399,7 @@ parse_shelley_fn!(parse_type_7, script_hash);
 // type 8 (1000) are Byron addresses
 fn parse_type_8(header: u8, payload: &[u8]) -> Result<Address, Error> {
     let vec = [&[header], payload].concat();
-    let inner = pallas_codec::minicbor::decode(&vec).map_err(Error::InvalidByronCbor)?;
+    let inner = ByronAddress::from_bytes(&vec)?;
     Ok(Address::Byron(inner))
 }
 
@@ -708,8 +711,12 @@ impl FromStr for Address {
             return Ok(x);
         }
 
-        if let Ok(x) = ByronAddress::from_base58(s) {
-            return Ok(x.into());
+        match ByronAddress::from_base58(s) {
+            Ok(x) => return Ok(x.into()),
+            // a well-formed Byron address with a bad checksum is most likely a typo,
+            // report it as such instead of trying other formats
+            Err(e @ Error::InvalidByronCrc(..)) => return Err(e),
+            Err(_) => (),
         }
 
         if let Ok(x) = Address::from_hex(s) {
@@ -923,4 +930,35 @@ mod tests {
         let addr = Address::from_hex("015bad085057ac10ecc7060f7ac41edd6f63068d8963ef7d86ca58669e5ecf2d283418a60be5a848a2380eb721000da1e0bbf39733134beca4cb57afb0b35fc89c63061c9914e055001a518c7516");
         assert!(matches!(addr, Ok(Address::Shelley(_))));
     }
+
+    #[test]
+    fn test_vector_corpus() {
+        let corpus = include_str!("../../test_data/addresses.csv");
+
+        let vectors = corpus
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+
+        for vector in vectors {
+            let [text, bytes, typeid, network] = vector.split(',').collect::<Vec<_>>()[..] else {
+                panic!("malformed test vector {vector}");
+            };
+
+            let addr = Address::from_str(text).unwrap();
+            assert_eq!(addr.to_string(), text, "{text}");
+            assert_eq!(addr.to_hex(), bytes, "{text}");
+            assert_eq!(Address::from_hex(bytes).unwrap(), addr, "{text}");
+            assert_eq!(addr.typeid(), typeid.parse::<u8>().unwrap(), "{text}");
+
+            let expected = match network {
+                "mainnet" => Some(Network::Mainnet),
+                "testnet" => Some(Network::Testnet),
+                "none" => None,
+                x => panic!("unknown network {x}"),
+            };
+
+            assert_eq!(addr.network(), expected, "{text}");
+        }
+    }
 }
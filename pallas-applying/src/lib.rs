@@ -4,6 +4,8 @@ pub mod alonzo;
 pub mod babbage;
 pub mod byron;
 pub mod conway;
+#[cfg(feature = "network")]
+pub mod localstate;
 pub mod shelley_ma;
 pub mod utils;
 
@@ -11,9 +13,14 @@ use alonzo::validate_alonzo_tx;
 use babbage::validate_babbage_tx;
 use byron::validate_byron_tx;
 use conway::validate_conway_tx;
-use pallas_primitives::alonzo::TransactionIndex;
-use pallas_traverse::{Era, MultiEraTx};
+use pallas_codec::utils::CborWrap;
+use pallas_primitives::{
+    alonzo::{TransactionIndex, TransactionInput},
+    byron::TxIn,
+};
+use pallas_traverse::{Era, MultiEraInput, MultiEraTx};
 use shelley_ma::validate_shelley_ma_tx;
+use std::borrow::Cow;
 
 pub use utils::{
     CertState, Environment, MultiEraProtocolParameters, UTxOs,
@@ -25,20 +32,69 @@ pub use utils::{
 };
 
 /// Ledger sequence rule: LEDGERS
+///
+/// Txs are validated in the order of the slice, which must be the order in
+/// which they appear in the block. Each one is checked against the UTxO set
+/// and cert state left by the previous ones: its consumed inputs are removed
+/// and its produced outputs are added before moving on to the next tx, so a
+/// tx can spend outputs created earlier in the block but no input can be
+/// spent twice. The given UTxO set is left untouched, the cert state is only
+/// updated if every tx is valid.
+///
+/// Txs flagged as invalid (Alonzo onwards) only consume their collateral and
+/// produce their collateral return, so their certificates never reach the
+/// cert state. Since the cert state is only tracked for Shelley-MA txs, which
+/// can't be flagged as invalid, both behave the same here.
 pub fn validate_txs(
     metxs: &[MultiEraTx],
     env: &Environment,
     utxos: &UTxOs,
     cert_state: &mut CertState,
 ) -> ValidationResult {
+    let produced: Vec<_> = metxs.iter().map(|x| x.produces()).collect();
+
+    let mut delta_utxos: UTxOs = utxos
+        .iter()
+        .map(|(input, output)| (input.clone(), output.reborrow()))
+        .collect();
+
     let mut delta_state: CertState = cert_state.clone();
-    for (txix, metx) in metxs.iter().enumerate() {
-        validate_tx(metx, txix.try_into().unwrap(), env, utxos, &mut delta_state)?;
+
+    for ((txix, metx), produced) in metxs.iter().enumerate().zip(produced.iter()) {
+        validate_tx(
+            metx,
+            txix.try_into().unwrap(),
+            env,
+            &delta_utxos,
+            &mut delta_state,
+        )?;
+
+        for input in metx.consumes() {
+            delta_utxos.remove(&input);
+        }
+
+        for (index, output) in produced {
+            delta_utxos.insert(produced_input(metx, *index), output.reborrow());
+        }
     }
+
     *cert_state = delta_state;
     Ok(())
 }
 
+/// The input pointing to an output produced by a tx
+fn produced_input(metx: &MultiEraTx, index: usize) -> MultiEraInput<'static> {
+    match metx {
+        MultiEraTx::Byron(_) => MultiEraInput::Byron(Box::new(Cow::Owned(TxIn::Variant0(
+            CborWrap((metx.hash(), index as u32)),
+        )))),
+        _ => MultiEraInput::AlonzoCompatible(Box::new(Cow::Owned(TransactionInput {
+            transaction_id: metx.hash(),
+            index: index as u64,
+        }))),
+    }
+}
+
 /// Ledger inference rule: LEDGER
 pub fn validate_tx(
     metx: &MultiEraTx,
@@ -101,3 +157,47 @@ pub fn validate_tx(
         (_, None) => Err(EnvMissingAccountState),
     }
 }
+
+/// Source of UTxOs that can only be queried asynchronously (eg: a database or
+/// a remote node)
+#[cfg(feature = "async")]
+pub trait UTxOResolver<'b> {
+    /// Fetch the outputs referenced by the given inputs
+    ///
+    /// Inputs that can't be found should be left out of the returned set;
+    /// validation will then report them as missing from the UTxO set.
+    ///
+    /// The returned future must be `Send` so that validation can be spawned
+    /// onto a multi-threaded runtime.
+    fn resolve(
+        &self,
+        inputs: &[pallas_traverse::MultiEraInput<'_>],
+    ) -> impl std::future::Future<Output = UTxOs<'b>> + Send;
+}
+
+/// Ledger inference rule: LEDGER, resolving the UTxOs through an async source
+///
+/// Builds the UTxO set by asking the resolver for every input the tx depends
+/// on (regular, collateral and reference inputs) and then runs the same
+/// (sync) validation as [`validate_tx`].
+#[cfg(feature = "async")]
+#[allow(clippy::manual_async_fn)]
+pub fn validate_tx_with_resolver<'a, 't, 'b, R: UTxOResolver<'b> + Sync>(
+    metx: &'a MultiEraTx<'t>,
+    txix: TransactionIndex,
+    env: &'a Environment,
+    resolver: &'a R,
+    cert_state: &'a mut CertState,
+) -> impl std::future::Future<Output = ValidationResult> + Send + use<'a, 't, 'b, R> {
+    // spelled out instead of `async fn` so that callers get the `Send` bound
+    // without the compiler having to infer it from the resolver's future
+    async move {
+        let mut inputs = metx.inputs();
+        inputs.extend(metx.collateral());
+        inputs.extend(metx.reference_inputs());
+
+        let utxos = resolver.resolve(&inputs).await;
+
+        validate_tx(metx, txix, env, &utxos, cert_state)
+    }
+}
@@ -0,0 +1,285 @@
+//! Builds the validation environment from the state of a local node
+//!
+//! The local-state-query mini-protocol exposes the protocol params using the
+//! `queries_v16` types. This module maps those into the era-specific params
+//! expected by the validation rules and assembles an [`Environment`] using the
+//! genesis config and ledger tip reported by the node.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDate};
+use pallas_network::miniprotocols::localstate::{
+    queries_v16::{self, Era, GenesisConfig, ProtocolParam, SystemStart},
+    Client, ClientError,
+};
+use pallas_primitives::{
+    alonzo::{ExUnitPrices, ExUnits, Nonce, NonceVariant, RationalNumber},
+    babbage::CostModels as BabbageCostModels,
+    conway::{CostModels as ConwayCostModels, DRepVotingThresholds, PoolVotingThresholds},
+};
+use thiserror::Error;
+
+use crate::utils::{BabbageProtParams, ConwayProtParams, Environment, MultiEraProtocolParameters};
+
+/// Era index used by the hard-fork combinator for Babbage
+pub const BABBAGE_ERA: Era = 5;
+
+/// Era index used by the hard-fork combinator for Conway
+pub const CONWAY_ERA: Era = 6;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("protocol params for era {0} are not supported")]
+    UnsupportedEra(Era),
+
+    #[error("protocol param {0} is missing")]
+    MissingParam(&'static str),
+
+    #[error("invalid system start")]
+    InvalidSystemStart,
+
+    #[error("node returned no {0}")]
+    EmptyResponse(&'static str),
+
+    #[error("state query failed")]
+    Query(#[from] ClientError),
+}
+
+fn required<T>(value: Option<T>, name: &'static str) -> Result<T, Error> {
+    value.ok_or(Error::MissingParam(name))
+}
+
+fn rational(x: queries_v16::RationalNumber) -> RationalNumber {
+    RationalNumber {
+        numerator: x.numerator,
+        denominator: x.denominator,
+    }
+}
+
+fn ex_units(x: queries_v16::ExUnits) -> ExUnits {
+    ExUnits {
+        mem: x.mem as u64,
+        steps: x.steps,
+    }
+}
+
+fn ex_unit_prices(x: queries_v16::ExUnitPrices) -> ExUnitPrices {
+    ExUnitPrices {
+        mem_price: rational(x.mem_price),
+        step_price: rational(x.step_price),
+    }
+}
+
+fn pool_voting_thresholds(x: queries_v16::PoolVotingThresholds) -> PoolVotingThresholds {
+    PoolVotingThresholds {
+        motion_no_confidence: rational(x.motion_no_confidence),
+        committee_normal: rational(x.committee_normal),
+        committee_no_confidence: rational(x.committee_no_confidence),
+        hard_fork_initiation: rational(x.hard_fork_initiation),
+        security_voting_threshold: rational(x.security_voting_threshold),
+    }
+}
+
+fn drep_voting_thresholds(x: queries_v16::DRepVotingThresholds) -> DRepVotingThresholds {
+    DRepVotingThresholds {
+        motion_no_confidence: rational(x.motion_no_confidence),
+        committee_normal: rational(x.committee_normal),
+        committee_no_confidence: rational(x.committee_no_confidence),
+        update_constitution: rational(x.update_constitution),
+        hard_fork_initiation: rational(x.hard_fork_initiation),
+        pp_network_group: rational(x.pp_network_group),
+        pp_economic_group: rational(x.pp_economic_group),
+        pp_technical_group: rational(x.pp_technical_group),
+        pp_governance_group: rational(x.pp_governance_group),
+        treasury_withdrawal: rational(x.treasury_withdrawal),
+    }
+}
+
+/// Converts the system start reported by the node into a UTC timestamp
+pub fn system_start(start: &SystemStart) -> Result<DateTime<FixedOffset>, Error> {
+    let date = NaiveDate::from_yo_opt(start.year as i32, start.day_of_year)
+        .ok_or(Error::InvalidSystemStart)?;
+
+    let time = date.and_hms_opt(0, 0, 0).ok_or(Error::InvalidSystemStart)?
+        + Duration::nanoseconds((start.picoseconds_of_day / 1000) as i64);
+
+    Ok(time.and_utc().fixed_offset())
+}
+
+/// Maps the params returned by `GetCurrentPParams` into the ones used for
+/// validation
+///
+/// Timing values come from the genesis config, since they aren't part of the
+/// protocol params. Only Babbage and Conway params are supported, as earlier
+/// eras use a different layout on the wire.
+pub fn protocol_params(
+    era: Era,
+    params: ProtocolParam,
+    genesis: &GenesisConfig,
+) -> Result<MultiEraProtocolParameters, Error> {
+    if era != BABBAGE_ERA && era != CONWAY_ERA {
+        return Err(Error::UnsupportedEra(era));
+    }
+
+    let system_start = system_start(&genesis.system_start)?;
+    let epoch_length = genesis.epoch_length as u64;
+    let slot_length = genesis.slot_length as u64;
+
+    let minfee_a = required(params.minfee_a, "minfee_a")?;
+    let minfee_b = required(params.minfee_b, "minfee_b")?;
+    let max_block_body_size = required(params.max_block_body_size, "max_block_body_size")?;
+    let max_transaction_size = required(params.max_transaction_size, "max_transaction_size")?;
+    let max_block_header_size = required(params.max_block_header_size, "max_block_header_size")?;
+    let key_deposit = required(params.key_deposit, "key_deposit")?.as_u64();
+    let pool_deposit = required(params.pool_deposit, "pool_deposit")?.as_u64();
+    let maximum_epoch = required(params.maximum_epoch, "maximum_epoch")?;
+    let desired_number_of_stake_pools = required(
+        params.desired_number_of_stake_pools,
+        "desired_number_of_stake_pools",
+    )?;
+    let pool_pledge_influence = rational(required(
+        params.pool_pledge_influence,
+        "pool_pledge_influence",
+    )?);
+    let expansion_rate = rational(required(params.expansion_rate, "expansion_rate")?);
+    let treasury_growth_rate = rational(required(
+        params.treasury_growth_rate,
+        "treasury_growth_rate",
+    )?);
+    let protocol_version = required(params.protocol_version, "protocol_version")?;
+    let min_pool_cost = required(params.min_pool_cost, "min_pool_cost")?.as_u64();
+    let ada_per_utxo_byte = required(params.ada_per_utxo_byte, "ada_per_utxo_byte")?.as_u64();
+    let cost_models = required(
+        params.cost_models_for_script_languages,
+        "cost_models_for_script_languages",
+    )?;
+    let execution_costs = ex_unit_prices(required(params.execution_costs, "execution_costs")?);
+    let max_tx_ex_units = ex_units(required(params.max_tx_ex_units, "max_tx_ex_units")?);
+    let max_block_ex_units = ex_units(required(params.max_block_ex_units, "max_block_ex_units")?);
+    let max_value_size = required(params.max_value_size, "max_value_size")?;
+    let collateral_percentage = required(params.collateral_percentage, "collateral_percentage")?;
+    let max_collateral_inputs = required(params.max_collateral_inputs, "max_collateral_inputs")?;
+
+    if era == BABBAGE_ERA {
+        return Ok(MultiEraProtocolParameters::Babbage(BabbageProtParams {
+            system_start,
+            epoch_length,
+            slot_length,
+            minfee_a,
+            minfee_b,
+            max_block_body_size,
+            max_transaction_size,
+            max_block_header_size,
+            key_deposit,
+            pool_deposit,
+            desired_number_of_stake_pools,
+            protocol_version,
+            min_pool_cost,
+            ada_per_utxo_byte,
+            cost_models_for_script_languages: BabbageCostModels {
+                plutus_v1: cost_models.plutus_v1,
+                plutus_v2: cost_models.plutus_v2,
+            },
+            execution_costs,
+            max_tx_ex_units,
+            max_block_ex_units,
+            max_value_size,
+            collateral_percentage,
+            max_collateral_inputs,
+            expansion_rate,
+            treasury_growth_rate,
+            maximum_epoch,
+            pool_pledge_influence,
+            // both were removed from the params in Babbage
+            decentralization_constant: RationalNumber {
+                numerator: 0,
+                denominator: 1,
+            },
+            extra_entropy: Nonce {
+                variant: NonceVariant::NeutralNonce,
+                hash: None,
+            },
+        }));
+    }
+
+    Ok(MultiEraProtocolParameters::Conway(ConwayProtParams {
+        system_start,
+        epoch_length,
+        slot_length,
+        minfee_a,
+        minfee_b,
+        max_block_body_size,
+        max_transaction_size,
+        max_block_header_size,
+        key_deposit,
+        pool_deposit,
+        desired_number_of_stake_pools,
+        protocol_version,
+        min_pool_cost,
+        ada_per_utxo_byte,
+        cost_models_for_script_languages: ConwayCostModels {
+            plutus_v1: cost_models.plutus_v1,
+            plutus_v2: cost_models.plutus_v2,
+            plutus_v3: cost_models.plutus_v3,
+        },
+        execution_costs,
+        max_tx_ex_units,
+        max_block_ex_units,
+        max_value_size,
+        collateral_percentage,
+        max_collateral_inputs,
+        expansion_rate,
+        treasury_growth_rate,
+        maximum_epoch,
+        pool_pledge_influence,
+        pool_voting_thresholds: required(params.pool_voting_thresholds, "pool_voting_thresholds")
+            .map(pool_voting_thresholds)?,
+        drep_voting_thresholds: required(params.drep_voting_thresholds, "drep_voting_thresholds")
+            .map(drep_voting_thresholds)?,
+        min_committee_size: required(params.min_committee_size, "min_committee_size")?,
+        committee_term_limit: required(params.committee_term_limit, "committee_term_limit")?,
+        governance_action_validity_period: required(
+            params.governance_action_validity_period,
+            "governance_action_validity_period",
+        )?,
+        governance_action_deposit: required(
+            params.governance_action_deposit,
+            "governance_action_deposit",
+        )?
+        .as_u64(),
+        drep_deposit: required(params.drep_deposit, "drep_deposit")?.as_u64(),
+        drep_inactivity_period: required(params.drep_inactivity_period, "drep_inactivity_period")?,
+        minfee_refscript_cost_per_byte: rational(required(
+            params.minfee_refscript_cost_per_byte,
+            "minfee_refscript_cost_per_byte",
+        )?),
+    }))
+}
+
+/// Queries the node for everything needed to validate txs against its
+/// current ledger state
+///
+/// The client must have already acquired the state to query. The block slot
+/// of the environment is the one of the ledger tip. The account state is left
+/// empty, which is fine for the eras supported by [`protocol_params`].
+pub async fn fetch_environment(client: &mut Client) -> Result<Environment, Error> {
+    let era = queries_v16::get_current_era(client).await?;
+
+    let params = queries_v16::get_current_pparams(client, era)
+        .await?
+        .pop()
+        .ok_or(Error::EmptyResponse("protocol params"))?;
+
+    let genesis = queries_v16::get_genesis_config(client, era)
+        .await?
+        .pop()
+        .ok_or(Error::EmptyResponse("genesis config"))?;
+
+    let tip = queries_v16::get_chain_point(client).await?;
+
+    Ok(Environment {
+        prot_params: protocol_params(era, params, &genesis)?,
+        prot_magic: genesis.network_magic,
+        block_slot: tip.slot_or_default(),
+        network_id: genesis.network_id as u8,
+        acnt: None,
+    })
+}
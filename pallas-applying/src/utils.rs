@@ -691,10 +691,7 @@ pub fn conway_get_val_size_in_words(val: &ConwayValue) -> u64 {
 }
 
 pub fn compute_native_script_hash(script: &NativeScript) -> PolicyId {
-    let mut payload = Vec::new();
-    let _ = encode(script, &mut payload);
-    payload.insert(0, 0);
-    pallas_crypto::hash::Hasher::<224>::hash(&payload)
+    pallas_crypto::hash::Hasher::<224>::hash_tagged_cbor(script, 0)
 }
 
 #[deprecated(since = "0.31.0", note = "use `compute_plutus_v1_script_hash` instead")]
@@ -703,21 +700,15 @@ pub fn compute_plutus_script_hash(script: &PlutusScript<1>) -> PolicyId {
 }
 
 pub fn compute_plutus_v1_script_hash(script: &PlutusScript<1>) -> PolicyId {
-    let mut payload: Vec<u8> = Vec::from(script.as_ref());
-    payload.insert(0, 1);
-    pallas_crypto::hash::Hasher::<224>::hash(&payload)
+    pallas_crypto::hash::Hasher::<224>::hash_tagged(script.as_ref(), script.language().script_tag())
 }
 
 pub fn compute_plutus_v2_script_hash(script: &PlutusScript<2>) -> PolicyId {
-    let mut payload: Vec<u8> = Vec::from(script.as_ref());
-    payload.insert(0, 2);
-    pallas_crypto::hash::Hasher::<224>::hash(&payload)
+    pallas_crypto::hash::Hasher::<224>::hash_tagged(script.as_ref(), script.language().script_tag())
 }
 
 pub fn compute_plutus_v3_script_hash(script: &PlutusScript<3>) -> PolicyId {
-    let mut payload: Vec<u8> = Vec::from(script.as_ref());
-    payload.insert(0, 3);
-    pallas_crypto::hash::Hasher::<224>::hash(&payload)
+    pallas_crypto::hash::Hasher::<224>::hash_tagged(script.as_ref(), script.language().script_tag())
 }
 
 pub type CertificateIndex = u32;
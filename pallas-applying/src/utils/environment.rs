@@ -9,6 +9,8 @@ use pallas_primitives::{
     conway::{CostModels as ConwayCostModels, Epoch},
 };
 
+use super::ValidationError;
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -111,6 +113,125 @@ impl MultiEraProtocolParameters {
             }
         }
     }
+
+    /// Fee coefficient charged per byte of the tx
+    pub fn min_fee_a(&self) -> u64 {
+        match self {
+            MultiEraProtocolParameters::Byron(ByronProtParams { multiplier, .. }) => *multiplier,
+            MultiEraProtocolParameters::Shelley(ShelleyProtParams { minfee_a, .. }) => {
+                *minfee_a as u64
+            }
+            MultiEraProtocolParameters::Alonzo(AlonzoProtParams { minfee_a, .. }) => {
+                *minfee_a as u64
+            }
+            MultiEraProtocolParameters::Babbage(BabbageProtParams { minfee_a, .. }) => {
+                *minfee_a as u64
+            }
+            MultiEraProtocolParameters::Conway(ConwayProtParams { minfee_a, .. }) => {
+                *minfee_a as u64
+            }
+        }
+    }
+
+    /// Constant fee charged to every tx
+    pub fn min_fee_b(&self) -> u64 {
+        match self {
+            MultiEraProtocolParameters::Byron(ByronProtParams { summand, .. }) => *summand,
+            MultiEraProtocolParameters::Shelley(ShelleyProtParams { minfee_b, .. }) => {
+                *minfee_b as u64
+            }
+            MultiEraProtocolParameters::Alonzo(AlonzoProtParams { minfee_b, .. }) => {
+                *minfee_b as u64
+            }
+            MultiEraProtocolParameters::Babbage(BabbageProtParams { minfee_b, .. }) => {
+                *minfee_b as u64
+            }
+            MultiEraProtocolParameters::Conway(ConwayProtParams { minfee_b, .. }) => {
+                *minfee_b as u64
+            }
+        }
+    }
+
+    pub fn max_tx_size(&self) -> u64 {
+        match self {
+            MultiEraProtocolParameters::Byron(ByronProtParams { max_tx_size, .. }) => *max_tx_size,
+            MultiEraProtocolParameters::Shelley(ShelleyProtParams {
+                max_transaction_size,
+                ..
+            }) => *max_transaction_size as u64,
+            MultiEraProtocolParameters::Alonzo(AlonzoProtParams {
+                max_transaction_size,
+                ..
+            }) => *max_transaction_size as u64,
+            MultiEraProtocolParameters::Babbage(BabbageProtParams {
+                max_transaction_size,
+                ..
+            }) => *max_transaction_size as u64,
+            MultiEraProtocolParameters::Conway(ConwayProtParams {
+                max_transaction_size,
+                ..
+            }) => *max_transaction_size as u64,
+        }
+    }
+
+    /// Deposit for registering a stake key, which Byron doesn't have
+    pub fn key_deposit(&self) -> Option<Coin> {
+        match self {
+            MultiEraProtocolParameters::Byron(_) => None,
+            MultiEraProtocolParameters::Shelley(ShelleyProtParams { key_deposit, .. }) => {
+                Some(*key_deposit)
+            }
+            MultiEraProtocolParameters::Alonzo(AlonzoProtParams { key_deposit, .. }) => {
+                Some(*key_deposit)
+            }
+            MultiEraProtocolParameters::Babbage(BabbageProtParams { key_deposit, .. }) => {
+                Some(*key_deposit)
+            }
+            MultiEraProtocolParameters::Conway(ConwayProtParams { key_deposit, .. }) => {
+                Some(*key_deposit)
+            }
+        }
+    }
+
+    /// Deposit for registering a stake pool, which Byron doesn't have
+    pub fn pool_deposit(&self) -> Option<Coin> {
+        match self {
+            MultiEraProtocolParameters::Byron(_) => None,
+            MultiEraProtocolParameters::Shelley(ShelleyProtParams { pool_deposit, .. }) => {
+                Some(*pool_deposit)
+            }
+            MultiEraProtocolParameters::Alonzo(AlonzoProtParams { pool_deposit, .. }) => {
+                Some(*pool_deposit)
+            }
+            MultiEraProtocolParameters::Babbage(BabbageProtParams { pool_deposit, .. }) => {
+                Some(*pool_deposit)
+            }
+            MultiEraProtocolParameters::Conway(ConwayProtParams { pool_deposit, .. }) => {
+                Some(*pool_deposit)
+            }
+        }
+    }
+
+    /// Min lovelace required per byte of a tx output, which Byron and
+    /// Shelley-MA don't have
+    ///
+    /// Alonzo params hold the coins per UTxO _word_ instead (in the field
+    /// named `ada_per_utxo_byte`), which is converted to bytes the same way
+    /// the ledger does when translating them into Babbage.
+    pub fn coins_per_utxo_byte(&self) -> Option<Coin> {
+        match self {
+            MultiEraProtocolParameters::Byron(_) | MultiEraProtocolParameters::Shelley(_) => None,
+            MultiEraProtocolParameters::Alonzo(AlonzoProtParams {
+                ada_per_utxo_byte, ..
+            }) => Some(*ada_per_utxo_byte / 8),
+            MultiEraProtocolParameters::Babbage(BabbageProtParams {
+                ada_per_utxo_byte, ..
+            }) => Some(*ada_per_utxo_byte),
+            MultiEraProtocolParameters::Conway(ConwayProtParams {
+                ada_per_utxo_byte, ..
+            }) => Some(*ada_per_utxo_byte),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -293,4 +414,82 @@ impl Environment {
     pub fn acnt(&self) -> &Option<AccountState> {
         &self.acnt
     }
+
+    pub fn builder() -> EnvironmentBuilder {
+        EnvironmentBuilder::default()
+    }
+}
+
+/// Assembles an [Environment], checking that its values are consistent
+///
+/// Byron params must come without account state, while Shelley-MA params
+/// require it. The later eras don't use it, so it's optional for them.
+#[derive(Debug, Default)]
+pub struct EnvironmentBuilder {
+    prot_params: Option<MultiEraProtocolParameters>,
+    prot_magic: Option<u32>,
+    block_slot: Option<u64>,
+    network_id: Option<u8>,
+    acnt: Option<AccountState>,
+}
+
+impl EnvironmentBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn prot_params(mut self, prot_params: MultiEraProtocolParameters) -> Self {
+        self.prot_params = Some(prot_params);
+        self
+    }
+
+    pub fn prot_magic(mut self, prot_magic: u32) -> Self {
+        self.prot_magic = Some(prot_magic);
+        self
+    }
+
+    pub fn block_slot(mut self, block_slot: u64) -> Self {
+        self.block_slot = Some(block_slot);
+        self
+    }
+
+    pub fn network_id(mut self, network_id: u8) -> Self {
+        self.network_id = Some(network_id);
+        self
+    }
+
+    pub fn acnt(mut self, acnt: AccountState) -> Self {
+        self.acnt = Some(acnt);
+        self
+    }
+
+    pub fn build(self) -> Result<Environment, ValidationError> {
+        let prot_params = self
+            .prot_params
+            .ok_or(ValidationError::EnvMissingField("prot_params"))?;
+
+        match (&prot_params, &self.acnt) {
+            (MultiEraProtocolParameters::Byron(_), Some(_)) => {
+                return Err(ValidationError::PParamsByronDoesntNeedAccountState)
+            }
+            (MultiEraProtocolParameters::Shelley(_), None) => {
+                return Err(ValidationError::EnvMissingAccountState)
+            }
+            _ => (),
+        }
+
+        Ok(Environment {
+            prot_params,
+            prot_magic: self
+                .prot_magic
+                .ok_or(ValidationError::EnvMissingField("prot_magic"))?,
+            block_slot: self
+                .block_slot
+                .ok_or(ValidationError::EnvMissingField("block_slot"))?,
+            network_id: self
+                .network_id
+                .ok_or(ValidationError::EnvMissingField("network_id"))?,
+            acnt: self.acnt,
+        })
+    }
 }
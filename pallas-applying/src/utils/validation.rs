@@ -13,6 +13,9 @@ pub enum ValidationError {
     #[error("missing account state")]
     EnvMissingAccountState,
 
+    #[error("missing {0} in environment")]
+    EnvMissingField(&'static str),
+
     #[error("unknown protocol parameters")]
     UnknownProtParams,
 
@@ -2367,6 +2367,118 @@ mod babbage_tests {
         }
     }
 
+    #[cfg(feature = "async")]
+    struct MapResolver<'b>(UTxOs<'b>);
+
+    #[cfg(feature = "async")]
+    impl<'b> pallas_applying::UTxOResolver<'b> for MapResolver<'b> {
+        async fn resolve(&self, inputs: &[MultiEraInput<'_>]) -> UTxOs<'b> {
+            self.0
+                .iter()
+                .filter(|(k, _)| inputs.iter().any(|i| i.output_ref() == k.output_ref()))
+                .map(|(k, v)| (k.clone(), v.clone()))
+                .collect()
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    // Transaction hash:
+    // b17d685c42e714238c1fb3abcd40e5c6291ebbb420c9c69b641209607bd00c7d
+    async fn successful_mainnet_tx_with_resolver() {
+        use pallas_applying::validate_tx_with_resolver;
+
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/babbage3.tx"));
+        let mtx: MintedTx = babbage_minted_tx_from_cbor(&cbor_bytes);
+        let metx: MultiEraTx = MultiEraTx::from_babbage(&mtx);
+        let tx_outs_info: &[(
+            String,
+            Value,
+            Option<MintedDatumOption>,
+            Option<CborWrap<MintedScriptRef>>,
+        )] = &[(
+            String::from("011be1f490912af2fc39f8e3637a2bade2ecbebefe63e8bfef10989cd6f593309a155b0ebb45ff830747e61f98e5b77feaf7529ce9df351382"),
+            Value::Coin(103324335),
+            None,
+            None,
+        )];
+        let utxos: UTxOs = mk_utxo_for_babbage_tx(&mtx.transaction_body, tx_outs_info);
+        let acnt = AccountState {
+            treasury: 261_254_564_000_000,
+            reserves: 0,
+        };
+
+        let env: Environment = Environment {
+            prot_params: MultiEraProtocolParameters::Babbage(mk_mainnet_params_epoch_365()),
+            prot_magic: 764824073,
+            block_slot: 72316896,
+            network_id: 1,
+            acnt: Some(acnt),
+        };
+
+        let mut cert_state: CertState = CertState::default();
+        let resolver = MapResolver(utxos);
+        match validate_tx_with_resolver(&metx, 0, &env, &resolver, &mut cert_state).await {
+            Ok(()) => (),
+            Err(err) => assert!(false, "Unexpected error ({:?})", err),
+        }
+
+        let empty = MapResolver(UTxOs::new());
+        match validate_tx_with_resolver(&metx, 0, &env, &empty, &mut cert_state).await {
+            Ok(()) => assert!(false, "All inputs should be missing from the UTxO set"),
+            Err(err) => match err {
+                PostAlonzo(PostAlonzoError::InputNotInUTxO) => (),
+                _ => assert!(false, "Unexpected error ({:?})", err),
+            },
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    // Same tx as above, validated from a spawned task to make sure the
+    // resolver-based validation can be moved across threads
+    async fn spawned_validation_with_resolver() {
+        use pallas_applying::validate_tx_with_resolver;
+
+        let handle = tokio::spawn(async move {
+            let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/babbage3.tx"));
+            let mtx: MintedTx = babbage_minted_tx_from_cbor(&cbor_bytes);
+            let metx: MultiEraTx = MultiEraTx::from_babbage(&mtx);
+            let tx_outs_info: &[(
+                String,
+                Value,
+                Option<MintedDatumOption>,
+                Option<CborWrap<MintedScriptRef>>,
+            )] = &[(
+                String::from("011be1f490912af2fc39f8e3637a2bade2ecbebefe63e8bfef10989cd6f593309a155b0ebb45ff830747e61f98e5b77feaf7529ce9df351382"),
+                Value::Coin(103324335),
+                None,
+                None,
+            )];
+            let utxos: UTxOs = mk_utxo_for_babbage_tx(&mtx.transaction_body, tx_outs_info);
+            let acnt = AccountState {
+                treasury: 261_254_564_000_000,
+                reserves: 0,
+            };
+
+            let env: Environment = Environment {
+                prot_params: MultiEraProtocolParameters::Babbage(mk_mainnet_params_epoch_365()),
+                prot_magic: 764824073,
+                block_slot: 72316896,
+                network_id: 1,
+                acnt: Some(acnt),
+            };
+
+            let mut cert_state: CertState = CertState::default();
+            let resolver = MapResolver(utxos);
+            validate_tx_with_resolver(&metx, 0, &env, &resolver, &mut cert_state).await
+        });
+
+        if let Err(err) = handle.await.unwrap() {
+            panic!("Unexpected error ({:?})", err)
+        }
+    }
+
     fn mk_mainnet_params_epoch_365() -> BabbageProtParams {
         BabbageProtParams {
             system_start: chrono::DateTime::parse_from_rfc3339("2017-09-23T21:44:51Z").unwrap(),
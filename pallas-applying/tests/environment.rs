@@ -0,0 +1,306 @@
+use pallas_applying::utils::{
+    AccountState, AlonzoProtParams, BabbageProtParams, ByronProtParams, ConwayProtParams,
+    Environment, MultiEraProtocolParameters, ShelleyProtParams, ValidationError,
+};
+use pallas_codec::utils::KeyValuePairs;
+use pallas_primitives::{
+    alonzo::{ExUnitPrices, ExUnits, Nonce, NonceVariant, RationalNumber},
+    babbage, conway,
+};
+
+fn byron_params() -> MultiEraProtocolParameters {
+    MultiEraProtocolParameters::Byron(ByronProtParams {
+        block_version: (1, 0, 0),
+        start_time: 1506203091,
+        script_version: 0,
+        slot_duration: 20000,
+        max_block_size: 2000000,
+        max_header_size: 2000000,
+        max_tx_size: 4096,
+        max_proposal_size: 700,
+        mpc_thd: 20000000000000,
+        heavy_del_thd: 300000000000,
+        update_vote_thd: 1000000000000,
+        update_proposal_thd: 100000000000000,
+        update_implicit: 10000,
+        soft_fork_rule: (900000000000000, 600000000000000, 50000000000000),
+        summand: 155381,
+        multiplier: 44,
+        unlock_stake_epoch: 18446744073709551615,
+    })
+}
+
+#[test]
+fn builder_sets_all_fields() {
+    let env = Environment::builder()
+        .prot_params(byron_params())
+        .prot_magic(764824073)
+        .block_slot(6341)
+        .network_id(1)
+        .build()
+        .unwrap();
+
+    assert_eq!(*env.prot_magic(), 764824073);
+    assert_eq!(*env.block_slot(), 6341);
+    assert_eq!(*env.network_id(), 1);
+    assert!(env.acnt().is_none());
+
+    assert_eq!(env.prot_params().min_fee_a(), 44);
+    assert_eq!(env.prot_params().min_fee_b(), 155381);
+    assert_eq!(env.prot_params().max_tx_size(), 4096);
+    assert_eq!(env.prot_params().key_deposit(), None);
+}
+
+#[test]
+fn builder_rejects_inconsistent_environment() {
+    let result = Environment::builder()
+        .prot_params(byron_params())
+        .prot_magic(764824073)
+        .block_slot(6341)
+        .network_id(1)
+        .acnt(AccountState::default())
+        .build();
+
+    assert!(matches!(
+        result,
+        Err(ValidationError::PParamsByronDoesntNeedAccountState)
+    ));
+
+    let result = Environment::builder()
+        .prot_params(byron_params())
+        .prot_magic(764824073)
+        .network_id(1)
+        .build();
+
+    assert!(matches!(
+        result,
+        Err(ValidationError::EnvMissingField("block_slot"))
+    ));
+}
+
+fn system_start() -> chrono::DateTime<chrono::FixedOffset> {
+    chrono::DateTime::parse_from_rfc3339("2017-09-23T21:44:51Z").unwrap()
+}
+
+fn ratio(numerator: u64, denominator: u64) -> RationalNumber {
+    RationalNumber {
+        numerator,
+        denominator,
+    }
+}
+
+fn neutral_nonce() -> Nonce {
+    Nonce {
+        variant: NonceVariant::NeutralNonce,
+        hash: None,
+    }
+}
+
+fn prices() -> ExUnitPrices {
+    ExUnitPrices {
+        mem_price: ratio(577, 10000),
+        step_price: ratio(721, 10000000),
+    }
+}
+
+fn shelley_params() -> MultiEraProtocolParameters {
+    MultiEraProtocolParameters::Shelley(ShelleyProtParams {
+        system_start: system_start(),
+        epoch_length: 432000,
+        slot_length: 1,
+        minfee_a: 44,
+        minfee_b: 155381,
+        max_block_body_size: 65536,
+        max_transaction_size: 16384,
+        max_block_header_size: 1100,
+        key_deposit: 2_000_000,
+        pool_deposit: 500_000_000,
+        desired_number_of_stake_pools: 500,
+        protocol_version: (4, 0),
+        min_utxo_value: 1_000_000,
+        min_pool_cost: 340_000_000,
+        expansion_rate: ratio(3, 1000),
+        treasury_growth_rate: ratio(2, 10),
+        maximum_epoch: 18,
+        pool_pledge_influence: ratio(3, 10),
+        decentralization_constant: ratio(0, 1),
+        extra_entropy: neutral_nonce(),
+    })
+}
+
+fn alonzo_params() -> MultiEraProtocolParameters {
+    MultiEraProtocolParameters::Alonzo(AlonzoProtParams {
+        system_start: system_start(),
+        epoch_length: 432000,
+        slot_length: 1,
+        minfee_a: 44,
+        minfee_b: 155381,
+        max_block_body_size: 65536,
+        max_transaction_size: 16384,
+        max_block_header_size: 1100,
+        key_deposit: 2_000_000,
+        pool_deposit: 500_000_000,
+        desired_number_of_stake_pools: 500,
+        protocol_version: (6, 0),
+        min_pool_cost: 340_000_000,
+        ada_per_utxo_byte: 34482,
+        cost_models_for_script_languages: KeyValuePairs::from(vec![]),
+        execution_costs: prices(),
+        max_tx_ex_units: ExUnits {
+            mem: 10_000_000,
+            steps: 10_000_000_000,
+        },
+        max_block_ex_units: ExUnits {
+            mem: 50_000_000,
+            steps: 40_000_000_000,
+        },
+        max_value_size: 5000,
+        collateral_percentage: 150,
+        max_collateral_inputs: 3,
+        expansion_rate: ratio(3, 1000),
+        treasury_growth_rate: ratio(2, 10),
+        maximum_epoch: 18,
+        pool_pledge_influence: ratio(3, 10),
+        decentralization_constant: ratio(0, 1),
+        extra_entropy: neutral_nonce(),
+    })
+}
+
+fn babbage_params() -> MultiEraProtocolParameters {
+    MultiEraProtocolParameters::Babbage(BabbageProtParams {
+        system_start: system_start(),
+        epoch_length: 432000,
+        slot_length: 1,
+        minfee_a: 44,
+        minfee_b: 155381,
+        max_block_body_size: 90112,
+        max_transaction_size: 16384,
+        max_block_header_size: 1100,
+        key_deposit: 2_000_000,
+        pool_deposit: 500_000_000,
+        desired_number_of_stake_pools: 500,
+        protocol_version: (8, 0),
+        min_pool_cost: 170_000_000,
+        ada_per_utxo_byte: 4310,
+        cost_models_for_script_languages: babbage::CostModels {
+            plutus_v1: None,
+            plutus_v2: None,
+        },
+        execution_costs: prices(),
+        max_tx_ex_units: ExUnits {
+            mem: 14_000_000,
+            steps: 10_000_000_000,
+        },
+        max_block_ex_units: ExUnits {
+            mem: 62_000_000,
+            steps: 20_000_000_000,
+        },
+        max_value_size: 5000,
+        collateral_percentage: 150,
+        max_collateral_inputs: 3,
+        expansion_rate: ratio(3, 1000),
+        treasury_growth_rate: ratio(2, 10),
+        maximum_epoch: 18,
+        pool_pledge_influence: ratio(3, 10),
+        decentralization_constant: ratio(0, 1),
+        extra_entropy: neutral_nonce(),
+    })
+}
+
+fn conway_params() -> MultiEraProtocolParameters {
+    MultiEraProtocolParameters::Conway(ConwayProtParams {
+        system_start: system_start(),
+        epoch_length: 432000,
+        slot_length: 1,
+        minfee_a: 44,
+        minfee_b: 155381,
+        max_block_body_size: 90112,
+        max_transaction_size: 16384,
+        max_block_header_size: 1100,
+        key_deposit: 2_000_000,
+        pool_deposit: 500_000_000,
+        desired_number_of_stake_pools: 500,
+        protocol_version: (9, 0),
+        min_pool_cost: 170_000_000,
+        ada_per_utxo_byte: 4310,
+        cost_models_for_script_languages: conway::CostModels {
+            plutus_v1: None,
+            plutus_v2: None,
+            plutus_v3: None,
+        },
+        execution_costs: prices(),
+        max_tx_ex_units: ExUnits {
+            mem: 14_000_000,
+            steps: 10_000_000_000,
+        },
+        max_block_ex_units: ExUnits {
+            mem: 62_000_000,
+            steps: 20_000_000_000,
+        },
+        max_value_size: 5000,
+        collateral_percentage: 150,
+        max_collateral_inputs: 3,
+        expansion_rate: ratio(3, 1000),
+        treasury_growth_rate: ratio(2, 10),
+        maximum_epoch: 18,
+        pool_pledge_influence: ratio(3, 10),
+        pool_voting_thresholds: conway::PoolVotingThresholds {
+            motion_no_confidence: ratio(51, 100),
+            committee_normal: ratio(51, 100),
+            committee_no_confidence: ratio(51, 100),
+            hard_fork_initiation: ratio(51, 100),
+            security_voting_threshold: ratio(51, 100),
+        },
+        drep_voting_thresholds: conway::DRepVotingThresholds {
+            motion_no_confidence: ratio(67, 100),
+            committee_normal: ratio(67, 100),
+            committee_no_confidence: ratio(3, 5),
+            update_constitution: ratio(3, 4),
+            hard_fork_initiation: ratio(3, 5),
+            pp_network_group: ratio(67, 100),
+            pp_economic_group: ratio(67, 100),
+            pp_technical_group: ratio(67, 100),
+            pp_governance_group: ratio(3, 4),
+            treasury_withdrawal: ratio(67, 100),
+        },
+        min_committee_size: 7,
+        committee_term_limit: 146,
+        governance_action_validity_period: 6,
+        governance_action_deposit: 100_000_000_000,
+        drep_deposit: 500_000_000,
+        drep_inactivity_period: 20,
+        minfee_refscript_cost_per_byte: ratio(15, 1),
+    })
+}
+
+#[test]
+fn params_are_exposed_for_every_era() {
+    for (params, version) in [
+        (shelley_params(), 4),
+        (alonzo_params(), 6),
+        (babbage_params(), 8),
+        (conway_params(), 9),
+    ] {
+        assert_eq!(params.system_start(), system_start());
+        assert_eq!(params.protocol_version(), version);
+        assert_eq!(params.epoch_length(), 432000);
+        assert_eq!(params.slot_length(), 1);
+        assert_eq!(params.min_fee_a(), 44);
+        assert_eq!(params.min_fee_b(), 155381);
+        assert_eq!(params.max_tx_size(), 16384);
+        assert_eq!(params.key_deposit(), Some(2_000_000));
+        assert_eq!(params.pool_deposit(), Some(500_000_000));
+    }
+}
+
+#[test]
+fn coins_per_utxo_byte_is_derived_per_era() {
+    assert_eq!(byron_params().coins_per_utxo_byte(), None);
+    assert_eq!(shelley_params().coins_per_utxo_byte(), None);
+
+    // 34482 per word is what mainnet switched to 4310 per byte from
+    assert_eq!(alonzo_params().coins_per_utxo_byte(), Some(4310));
+
+    assert_eq!(babbage_params().coins_per_utxo_byte(), Some(4310));
+    assert_eq!(conway_params().coins_per_utxo_byte(), Some(4310));
+}
@@ -0,0 +1,133 @@
+#![cfg(feature = "network")]
+
+use pallas_applying::{
+    localstate::{protocol_params, system_start, Error, BABBAGE_ERA, CONWAY_ERA},
+    MultiEraProtocolParameters,
+};
+use pallas_codec::utils::AnyUInt;
+use pallas_network::miniprotocols::localstate::queries_v16::{
+    CostMdls, ExUnitPrices, ExUnits, Fraction, GenesisConfig, ProtocolParam, RationalNumber,
+    SystemStart,
+};
+
+fn rational(numerator: u64, denominator: u64) -> RationalNumber {
+    RationalNumber {
+        numerator,
+        denominator,
+    }
+}
+
+fn mainnet_genesis() -> GenesisConfig {
+    GenesisConfig {
+        // 2017-09-23T21:44:51Z
+        system_start: SystemStart {
+            year: 2017,
+            day_of_year: 266,
+            picoseconds_of_day: 78_291_000_000_000_000,
+        },
+        network_magic: 764824073,
+        network_id: 1,
+        active_slots_coefficient: Fraction { num: 1, den: 20 },
+        security_param: 2160,
+        epoch_length: 432000,
+        slots_per_kes_period: 129600,
+        max_kes_evolutions: 62,
+        slot_length: 1,
+        update_quorum: 5,
+        max_lovelace_supply: AnyUInt::U64(45_000_000_000_000_000),
+    }
+}
+
+fn babbage_params() -> ProtocolParam {
+    ProtocolParam {
+        minfee_a: Some(44),
+        minfee_b: Some(155381),
+        max_block_body_size: Some(90112),
+        max_transaction_size: Some(16384),
+        max_block_header_size: Some(1100),
+        key_deposit: Some(AnyUInt::U32(2_000_000)),
+        pool_deposit: Some(AnyUInt::U32(500_000_000)),
+        maximum_epoch: Some(18),
+        desired_number_of_stake_pools: Some(500),
+        pool_pledge_influence: Some(rational(3, 10)),
+        expansion_rate: Some(rational(3, 1000)),
+        treasury_growth_rate: Some(rational(1, 5)),
+        protocol_version: Some((8, 0)),
+        min_pool_cost: Some(AnyUInt::U32(170_000_000)),
+        ada_per_utxo_byte: Some(AnyUInt::U16(4310)),
+        cost_models_for_script_languages: Some(CostMdls {
+            plutus_v1: Some(vec![1, 2]),
+            plutus_v2: Some(vec![3]),
+            plutus_v3: None,
+        }),
+        execution_costs: Some(ExUnitPrices {
+            mem_price: rational(577, 10000),
+            step_price: rational(721, 10000000),
+        }),
+        max_tx_ex_units: Some(ExUnits {
+            mem: 14_000_000,
+            steps: 10_000_000_000,
+        }),
+        max_block_ex_units: Some(ExUnits {
+            mem: 62_000_000,
+            steps: 20_000_000_000,
+        }),
+        max_value_size: Some(5000),
+        collateral_percentage: Some(150),
+        max_collateral_inputs: Some(3),
+        pool_voting_thresholds: None,
+        drep_voting_thresholds: None,
+        min_committee_size: None,
+        committee_term_limit: None,
+        governance_action_validity_period: None,
+        governance_action_deposit: None,
+        drep_deposit: None,
+        drep_inactivity_period: None,
+        minfee_refscript_cost_per_byte: None,
+    }
+}
+
+#[test]
+fn system_start_is_converted_to_utc() {
+    let start = system_start(&mainnet_genesis().system_start).unwrap();
+
+    assert_eq!(start.to_rfc3339(), "2017-09-23T21:44:51+00:00");
+}
+
+#[test]
+fn babbage_params_are_mapped() {
+    let params = protocol_params(BABBAGE_ERA, babbage_params(), &mainnet_genesis()).unwrap();
+
+    let MultiEraProtocolParameters::Babbage(params) = params else {
+        panic!("expected babbage params");
+    };
+
+    assert_eq!(params.epoch_length, 432000);
+    assert_eq!(params.slot_length, 1);
+    assert_eq!(params.key_deposit, 2_000_000);
+    assert_eq!(params.ada_per_utxo_byte, 4310);
+    assert_eq!(params.protocol_version, (8, 0));
+    assert_eq!(params.max_tx_ex_units.mem, 14_000_000);
+    assert_eq!(params.execution_costs.mem_price.numerator, 577);
+    assert_eq!(
+        params.cost_models_for_script_languages.plutus_v2,
+        Some(vec![3])
+    );
+}
+
+#[test]
+fn conway_params_require_governance_fields() {
+    let result = protocol_params(CONWAY_ERA, babbage_params(), &mainnet_genesis());
+
+    assert!(matches!(
+        result,
+        Err(Error::MissingParam("pool_voting_thresholds"))
+    ));
+}
+
+#[test]
+fn earlier_eras_are_unsupported() {
+    let result = protocol_params(4, babbage_params(), &mainnet_genesis());
+
+    assert!(matches!(result, Err(Error::UnsupportedEra(4))));
+}
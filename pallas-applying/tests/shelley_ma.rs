@@ -22,7 +22,7 @@ use pallas_primitives::alonzo::{
     Certificate, MintedTx, MintedWitnessSet, Nonce, NonceVariant, PoolKeyhash, PoolMetadata,
     RationalNumber, Relay, StakeCredential, TransactionBody, TransactionOutput, VKeyWitness, Value,
 };
-use pallas_traverse::{Era, MultiEraTx};
+use pallas_traverse::{Era, MultiEraBlock, MultiEraTx};
 use std::str::FromStr;
 
 #[cfg(test)]
@@ -287,6 +287,151 @@ mod shelley_ma_tests {
         }
     }
 
+    #[test]
+    // The pool registration of mary2.tx and the delegation to that pool of
+    // mary3.tx, applied as if they were in the same block.
+    fn block_txs_thread_cert_state_in_order() {
+        let mary2_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/mary2.tx"));
+        let mary2: MintedTx = minted_tx_from_cbor(&mary2_bytes);
+        let mary3_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/mary3.tx"));
+        let mary3: MintedTx = minted_tx_from_cbor(&mary3_bytes);
+
+        let mut utxos: UTxOs = mk_utxo_for_alonzo_compatible_tx(
+            &mary2.transaction_body,
+            &[(
+                String::from("018e8f7a7073b8a95a4c1f1cf412b1042fca4945b89eb11754b3481b29fb2b631db76384f64dd94b47f97fc8c2a206764c17a1de7da2f70e83"),
+                Value::Coin(1_507_817_955),
+                None,
+            )],
+        );
+        utxos.extend(mk_utxo_for_alonzo_compatible_tx(
+            &mary3.transaction_body,
+            &[(String::from(MARY3_UTXO), Value::Coin(627_760_000), None)],
+        ));
+
+        let mut cert_state: CertState = CertState::default();
+        let hash =
+            Hash::from_str("FB2B631DB76384F64DD94B47F97FC8C2A206764C17A1DE7DA2F70E83").unwrap();
+        cert_state
+            .dstate
+            .rewards
+            .insert(StakeCredential::AddrKeyhash(hash), 0);
+
+        // a slot before the TTL of both txs
+        let mut env: Environment = mary3_env();
+        env.block_slot = 26_000_000;
+
+        // delegating before the pool is registered fails, leaving the cert
+        // state untouched
+        let txs = [
+            MultiEraTx::from_alonzo_compatible(&mary3, Era::Mary),
+            MultiEraTx::from_alonzo_compatible(&mary2, Era::Mary),
+        ];
+        match validate_txs(&txs, &env, &utxos, &mut cert_state) {
+            Ok(()) => panic!("Pool is not registered"),
+            Err(err) => match err {
+                ShelleyMA(ShelleyMAError::PoolNotRegistered) => (),
+                _ => panic!("Unexpected error ({:?})", err),
+            },
+        }
+        assert!(cert_state.pstate.pool_params.is_empty());
+
+        let txs = [
+            MultiEraTx::from_alonzo_compatible(&mary2, Era::Mary),
+            MultiEraTx::from_alonzo_compatible(&mary3, Era::Mary),
+        ];
+        match validate_txs(&txs, &env, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => panic!("Unexpected error ({:?})", err),
+        }
+        assert!(cert_state
+            .pstate
+            .pool_params
+            .contains_key(&mary2_pool_operator()));
+    }
+
+    // Utxos spent by shelley1.tx
+    fn shelley1_utxos<'a>(mtx: &'a MintedTx) -> UTxOs<'a> {
+        mk_utxo_for_alonzo_compatible_tx(
+            &mtx.transaction_body,
+            &[(
+                String::from("0129bb156d52d014bb444a14138cbee36044c6faed37d0c2d49d2358315c465cbf8c5536970e8a29bb7adcda0d663b20007d481813694c64ef"),
+                Value::Coin(2332267427205),
+                None,
+            )],
+        )
+    }
+
+    #[test]
+    // shelley1.tx followed by the first tx of shelley1.block, which spends
+    // its first output, as if both were in the same block.
+    fn block_txs_spend_outputs_of_previous_txs() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/shelley1.tx"));
+        let mtx: MintedTx = minted_tx_from_cbor(&cbor_bytes);
+        let block_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/shelley1.block"));
+        let block = MultiEraBlock::decode(&block_bytes).unwrap();
+        let utxos: UTxOs = shelley1_utxos(&mtx);
+        let env: Environment = hardcoded_environment_values!();
+
+        let spender = block.txs().remove(0);
+        let txs = [
+            MultiEraTx::from_alonzo_compatible(&mtx, Era::Shelley),
+            spender,
+        ];
+
+        // the spender delegates to a pool, which must be registered already
+        let (cred, pool) = match txs[1].certs()[0].as_alonzo() {
+            Some(Certificate::StakeDelegation(cred, pool)) => (cred.clone(), *pool),
+            cert => panic!("Unexpected certificate ({:?})", cert),
+        };
+        let mut cert_state: CertState = CertState::default();
+        cert_state.dstate.rewards.insert(cred.clone(), 0);
+        cert_state
+            .pstate
+            .pool_params
+            .insert(pool, mary2_pool_param());
+        let initial_state = cert_state.clone();
+
+        match validate_txs(&txs, &env, &utxos, &mut cert_state) {
+            Ok(()) => (),
+            Err(err) => panic!("Unexpected error ({:?})", err),
+        }
+        assert_eq!(cert_state.dstate.delegations.get(&cred), Some(&pool));
+
+        // the given utxo set is left untouched
+        assert_eq!(utxos.len(), 1);
+
+        let txs = [txs[1].clone(), txs[0].clone()];
+        let mut cert_state: CertState = initial_state;
+        match validate_txs(&txs, &env, &utxos, &mut cert_state) {
+            Ok(()) => panic!("Output is spent before being created"),
+            Err(err) => match err {
+                ShelleyMA(ShelleyMAError::InputNotInUTxO) => (),
+                _ => panic!("Unexpected error ({:?})", err),
+            },
+        }
+        assert!(cert_state.dstate.delegations.is_empty());
+    }
+
+    #[test]
+    // shelley1.tx twice in the same block
+    fn block_txs_cant_double_spend() {
+        let cbor_bytes: Vec<u8> = cbor_to_bytes(include_str!("../../test_data/shelley1.tx"));
+        let mtx: MintedTx = minted_tx_from_cbor(&cbor_bytes);
+        let utxos: UTxOs = shelley1_utxos(&mtx);
+        let env: Environment = hardcoded_environment_values!();
+
+        let metx: MultiEraTx = MultiEraTx::from_alonzo_compatible(&mtx, Era::Shelley);
+        let mut cert_state: CertState = CertState::default();
+        match validate_txs(&[metx.clone(), metx], &env, &utxos, &mut cert_state) {
+            Ok(()) => panic!("Input is spent twice"),
+            Err(err) => match err {
+                ShelleyMA(ShelleyMAError::InputNotInUTxO) => (),
+                _ => panic!("Unexpected error ({:?})", err),
+            },
+        }
+    }
+
     fn mary2_pool_operator() -> PoolKeyhash {
         Hash::from_str("59EBE72AE96462018FBE04633100F90B3066688D85F00F3BD254707F").unwrap()
     }
@@ -3,6 +3,8 @@ mod encode;
 pub mod filler;
 pub mod zigzag;
 
+use thiserror::Error;
+
 pub mod en {
     pub use super::encode::*;
 }
@@ -45,3 +47,31 @@ where
 
     Ok(value)
 }
+
+#[derive(Error, Debug)]
+pub enum RoundTripError {
+    #[error(transparent)]
+    Decode(#[from] de::Error),
+    #[error(transparent)]
+    Encode(#[from] en::Error),
+    #[error("Encoding the decoded value doesn't reproduce the original bytes")]
+    Mismatch,
+}
+
+/// Decodes a value, checking that it encodes back to the exact same bytes
+///
+/// Hashes are computed over the original bytes, so this rejects inputs with
+/// trailing data or a non-canonical encoding that would otherwise decode
+/// fine.
+pub fn validate<'b, T>(bytes: &'b [u8]) -> Result<T, RoundTripError>
+where
+    T: de::Decode<'b> + en::Encode,
+{
+    let value: T = decode(bytes)?;
+
+    if encode(&value)? != bytes {
+        return Err(RoundTripError::Mismatch);
+    }
+
+    Ok(value)
+}
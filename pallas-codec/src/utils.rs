@@ -9,6 +9,13 @@ use std::{collections::HashMap, fmt, hash::Hash as StdHash, ops::Deref};
 
 static TAG_SET: u64 = 258;
 
+/// Error raised when building a non-empty collection out of no elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum NonEmptyError {
+    #[error("collection must contain at least one element")]
+    Empty,
+}
+
 /// Utility for skipping parts of the CBOR payload, use only for debugging
 #[derive(Debug, PartialEq, PartialOrd, Eq, Ord)]
 pub struct SkipCbor<const N: usize> {}
@@ -240,6 +247,11 @@ where
             Some(NonEmptyKeyValuePairs::Def(x))
         }
     }
+
+    /// Build from a list of pairs, failing if the list is empty
+    pub fn new(x: Vec<(K, V)>) -> Result<Self, NonEmptyError> {
+        Self::from_vec(x).ok_or(NonEmptyError::Empty)
+    }
 }
 
 impl<K, V> From<NonEmptyKeyValuePairs<K, V>> for Vec<(K, V)>
@@ -697,6 +709,24 @@ where
     }
 }
 
+/// Consume the optional 258 tag that precedes a set
+///
+/// Legacy (tag-less) encodings are accepted, but any tag other than 258 is
+/// rejected so that a re-encode (which always emits the tag) can't silently
+/// change the meaning of the data.
+fn decode_set_tag(d: &mut minicbor::Decoder<'_>) -> Result<(), minicbor::decode::Error> {
+    // decode optional set tag (this will be required in era following Conway)
+    if d.datatype()? == Type::Tag {
+        let found_tag = d.tag()?;
+
+        if found_tag != Tag::new(TAG_SET) {
+            return Err(Error::message(format!("Unrecognised tag: {found_tag:?}")));
+        }
+    }
+
+    Ok(())
+}
+
 /// Set
 ///
 /// Optional 258 tag (until era after Conway, at which point is it required)
@@ -745,14 +775,7 @@ where
     T: Decode<'b, C>,
 {
     fn decode(d: &mut minicbor::Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
-        // decode optional set tag (this will be required in era following Conway)
-        if d.datatype()? == Type::Tag {
-            let found_tag = d.tag()?;
-
-            if found_tag != Tag::new(TAG_SET) {
-                return Err(Error::message(format!("Unrecognised tag: {found_tag:?}")));
-            }
-        }
+        decode_set_tag(d)?;
 
         Ok(Self(d.decode_with(ctx)?))
     }
@@ -793,6 +816,11 @@ impl<T> NonEmptySet<T> {
             Some(Self(x))
         }
     }
+
+    /// Build from a list of items, failing if the list is empty
+    pub fn new(x: Vec<T>) -> Result<Self, NonEmptyError> {
+        Self::from_vec(x).ok_or(NonEmptyError::Empty)
+    }
 }
 
 impl<T> Deref for NonEmptySet<T> {
@@ -836,14 +864,7 @@ where
     T: Decode<'b, C>,
 {
     fn decode(d: &mut minicbor::Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
-        // decode optional set tag (this will be required in era following Conway)
-        if d.datatype()? == Type::Tag {
-            let found_tag = d.tag()?;
-
-            if found_tag != Tag::new(TAG_SET) {
-                return Err(Error::message(format!("Unrecognised tag: {found_tag:?}")));
-            }
-        }
+        decode_set_tag(d)?;
 
         let inner: Vec<T> = d.decode_with(ctx)?;
 
@@ -979,6 +1000,16 @@ impl From<&AnyUInt> for u64 {
     }
 }
 
+impl AnyUInt {
+    /// The value as a plain integer, regardless of its encoded width
+    ///
+    /// Decoding rejects any value that doesn't fit in a `u64` (such as CBOR
+    /// bignums), so this conversion never overflows.
+    pub fn as_u64(&self) -> u64 {
+        u64::from(self)
+    }
+}
+
 /// Introduced in Conway
 /// positive_coin = 1 .. 18446744073709551615
 #[derive(Debug, PartialEq, Copy, Clone, PartialOrd, Eq, Ord, Hash, Serialize, Deserialize)]
@@ -1157,6 +1188,82 @@ impl<C, T> minicbor::Encode<C> for KeepRaw<'_, T> {
     }
 }
 
+/// Skips decoding of a struct, keeping only its original CBOR
+///
+/// Useful for large structures that a consumer might not need (eg: witness
+/// sets when indexing outputs). The CBOR is still walked to find where the
+/// item ends, but nothing is allocated. The inner value can be decoded on
+/// demand.
+///
+/// # Examples
+///
+/// ```
+/// use pallas_codec::utils::OnlyRaw;
+///
+/// let a = (123u16, (456u16, 789u16), 123u16);
+/// let data = minicbor::to_vec(a).unwrap();
+///
+/// let (_, skipped, _): (u16, OnlyRaw<(u16, u16)>, u16) = minicbor::decode(&data).unwrap();
+/// assert_eq!(skipped.decode_inner().unwrap(), (456u16, 789u16));
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct OnlyRaw<'b, T> {
+    raw: &'b [u8],
+    inner: std::marker::PhantomData<T>,
+}
+
+impl<T> Clone for OnlyRaw<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            raw: self.raw,
+            inner: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<'b, T> OnlyRaw<'b, T> {
+    pub fn raw_cbor(&self) -> &'b [u8] {
+        self.raw
+    }
+
+    /// Decode the skipped struct from its original CBOR
+    pub fn decode_inner(&self) -> Result<T, minicbor::decode::Error>
+    where
+        T: minicbor::Decode<'b, ()>,
+    {
+        minicbor::decode(self.raw)
+    }
+}
+
+impl<'b, T, C> minicbor::Decode<'b, C> for OnlyRaw<'b, T> {
+    fn decode(
+        d: &mut minicbor::Decoder<'b>,
+        _ctx: &mut C,
+    ) -> Result<Self, minicbor::decode::Error> {
+        let all = d.input();
+        let start = d.position();
+        d.skip()?;
+        let end = d.position();
+
+        Ok(Self {
+            raw: &all[start..end],
+            inner: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<C, T> minicbor::Encode<C> for OnlyRaw<'_, T> {
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        _ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.writer_mut()
+            .write_all(self.raw_cbor())
+            .map_err(minicbor::encode::Error::write)
+    }
+}
+
 /// Struct to hold arbitrary CBOR to be processed independently
 ///
 /// # Examples
@@ -1263,6 +1370,15 @@ where
             Nullable::Undefined => Nullable::Undefined,
         }
     }
+
+    /// Borrows the inner value, treating both `Null` and `Undefined` as
+    /// `None`
+    pub fn as_option(&self) -> Option<&T> {
+        match self {
+            Nullable::Some(x) => Some(x),
+            Nullable::Null | Nullable::Undefined => None,
+        }
+    }
 }
 
 impl<'b, C, T> minicbor::Decode<'b, C> for Nullable<T>
@@ -0,0 +1,27 @@
+use pallas_codec::{minicbor, utils::AnyUInt};
+
+#[test]
+fn any_uint_converts_regardless_of_width() {
+    for (cbor, value) in [
+        ("17", 23),
+        ("1818", 24),
+        ("190100", 256),
+        ("1a000f4240", 1_000_000),
+        ("1bffffffffffffffff", u64::MAX),
+    ] {
+        let bytes = hex::decode(cbor).unwrap();
+        let x: AnyUInt = minicbor::decode(&bytes).unwrap();
+
+        assert_eq!(x.as_u64(), value);
+        assert_eq!(u64::from(x), value);
+        assert_eq!(minicbor::to_vec(x).unwrap(), bytes);
+    }
+}
+
+#[test]
+fn any_uint_rejects_values_beyond_u64() {
+    // bignum (tag 2) holding 2^64
+    let bytes = hex::decode("c249010000000000000000").unwrap();
+
+    assert!(minicbor::decode::<AnyUInt>(&bytes).is_err());
+}
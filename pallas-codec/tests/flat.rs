@@ -1,5 +1,5 @@
 use pallas_codec::flat::filler::Filler;
-use pallas_codec::flat::{decode, encode};
+use pallas_codec::flat::{decode, encode, validate, RoundTripError};
 use proptest::prelude::*;
 
 prop_compose! {
@@ -39,6 +39,25 @@ mod bigint {
     }
 }
 
+#[test]
+fn validate_rejects_trailing_bytes() {
+    let mut bytes = encode(&"hello".to_string()).unwrap();
+
+    let value: String = validate(&bytes).unwrap();
+    assert_eq!(value, "hello");
+
+    bytes.push(0xff);
+
+    assert!(matches!(
+        validate::<String>(&bytes),
+        Err(RoundTripError::Mismatch)
+    ));
+    assert!(matches!(
+        validate::<String>(&bytes[..3]),
+        Err(RoundTripError::Decode(_))
+    ));
+}
+
 #[test]
 fn encode_bool() {
     let bytes = encode(&true).unwrap();
@@ -0,0 +1,35 @@
+use pallas_codec::utils::{KeyValuePairs, NonEmptyError, NonEmptyKeyValuePairs, NonEmptySet};
+
+#[test]
+fn non_empty_set_rejects_empty() {
+    assert_eq!(NonEmptySet::<u8>::new(vec![]), Err(NonEmptyError::Empty));
+    assert_eq!(NonEmptySet::<u8>::try_from(vec![]), Err(vec![]));
+
+    let set = NonEmptySet::new(vec![3u8, 1, 2]).unwrap();
+    assert_eq!(set.first(), Some(&3));
+    assert_eq!(set, NonEmptySet::try_from(vec![3u8, 1, 2]).unwrap());
+}
+
+#[test]
+fn non_empty_pairs_rejects_empty() {
+    let empty: Vec<(u8, u8)> = vec![];
+
+    assert_eq!(
+        NonEmptyKeyValuePairs::new(empty.clone()),
+        Err(NonEmptyError::Empty)
+    );
+    assert!(NonEmptyKeyValuePairs::try_from(KeyValuePairs::Indef(empty)).is_err());
+
+    let pairs = NonEmptyKeyValuePairs::new(vec![(1u8, 10u8), (0, 20)]).unwrap();
+    assert_eq!(pairs.first(), Some(&(1, 10)));
+}
+
+#[test]
+fn decoded_empty_collections_have_no_first() {
+    // decoding is lenient and accepts empty collections
+    let set: NonEmptySet<u8> = pallas_codec::minicbor::decode(&[0x80]).unwrap();
+    assert_eq!(set.first(), None);
+
+    let pairs: NonEmptyKeyValuePairs<u8, u8> = pallas_codec::minicbor::decode(&[0xa0]).unwrap();
+    assert_eq!(pairs.first(), None);
+}
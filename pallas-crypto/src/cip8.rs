@@ -0,0 +1,393 @@
+//! Verification of CIP-8 message signatures
+//!
+//! Wallets sign arbitrary data (eg: through the CIP-30 `signData` endpoint)
+//! producing a COSE_Sign1 structure together with the COSE_Key of the signer.
+//! This module parses both and verifies the Ed25519 signature over the
+//! reconstructed `Sig_structure`, checking that the key matches the address
+//! committed in the protected headers.
+
+use pallas_codec::minicbor::{self, data::Type, Decoder};
+use thiserror::Error;
+
+use crate::{
+    hash::Hasher,
+    key::ed25519::{PublicKey, Signature},
+};
+
+/// COSE algorithm id of EdDSA
+const ALG_EDDSA: i64 = -8;
+
+/// COSE key type id of octet key pairs
+const KTY_OKP: i64 = 1;
+
+/// COSE curve id of Ed25519
+const CRV_ED25519: i64 = 6;
+
+/// CBOR tag of a COSE_Sign1 structure
+const COSE_SIGN1_TAG: u64 = 18;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("invalid CBOR structure: {0}")]
+    InvalidCbor(#[from] minicbor::decode::Error),
+
+    #[error("unsupported algorithm {0}, expecting EdDSA")]
+    UnsupportedAlgorithm(i64),
+
+    #[error("invalid COSE key: {0}")]
+    InvalidKey(&'static str),
+
+    #[error("invalid signature size {0}")]
+    InvalidSignatureSize(usize),
+
+    #[error("signed message has no payload")]
+    MissingPayload,
+
+    #[error("protected headers have no address")]
+    MissingAddress,
+
+    #[error("address can't be checked against a key")]
+    UnsupportedAddress,
+
+    #[error("key doesn't match the signing address")]
+    AddressMismatch,
+
+    #[error("signature doesn't match the key and payload")]
+    InvalidSignature,
+}
+
+/// Decodes a COSE header label, which can be an int or a text
+fn decode_label(d: &mut Decoder) -> Result<Option<i64>, Error> {
+    match d.datatype()? {
+        Type::String => {
+            d.str()?;
+            Ok(None)
+        }
+        _ => Ok(Some(d.i64()?)),
+    }
+}
+
+/// A COSE_Sign1 structure, as produced by CIP-8 compliant signers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoseSign1 {
+    /// Serialized protected headers, exactly as signed
+    protected: Vec<u8>,
+    payload: Option<Vec<u8>>,
+    signature: Vec<u8>,
+}
+
+impl CoseSign1 {
+    /// Creates an unsigned structure for a payload, committing to the
+    /// address of the signer in the protected headers
+    pub fn new(address: &[u8], payload: &[u8]) -> Self {
+        let mut e = minicbor::Encoder::new(vec![]);
+
+        e.map(2)
+            .and_then(|e| e.i64(1))
+            .and_then(|e| e.i64(ALG_EDDSA))
+            .and_then(|e| e.str("address"))
+            .and_then(|e| e.bytes(address))
+            .expect("infallible");
+
+        Self {
+            protected: e.into_writer(),
+            payload: Some(payload.to_vec()),
+            signature: vec![],
+        }
+    }
+
+    /// Sets the signature over the `Sig_structure` of this message
+    pub fn set_signature(&mut self, signature: &Signature) {
+        self.signature = signature.as_ref().to_vec();
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let mut d = Decoder::new(bytes);
+
+        if d.datatype()? == Type::Tag {
+            let tag = d.tag()?;
+
+            if tag.as_u64() != COSE_SIGN1_TAG {
+                return Err(
+                    minicbor::decode::Error::message("unexpected tag for COSE_Sign1").into(),
+                );
+            }
+        }
+
+        d.array()?;
+
+        let protected = d.bytes()?.to_vec();
+
+        // unprotected headers are not covered by the signature
+        d.skip()?;
+
+        let payload = match d.datatype()? {
+            Type::Null => {
+                d.null()?;
+                None
+            }
+            _ => Some(d.bytes()?.to_vec()),
+        };
+
+        let signature = d.bytes()?.to_vec();
+
+        Ok(Self {
+            protected,
+            payload,
+            signature,
+        })
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut e = minicbor::Encoder::new(vec![]);
+
+        e.array(4)
+            .and_then(|e| e.bytes(&self.protected))
+            .and_then(|e| e.map(1))
+            .and_then(|e| e.str("hashed"))
+            .and_then(|e| e.bool(false))
+            .expect("infallible");
+
+        match &self.payload {
+            Some(x) => e.bytes(x),
+            None => e.null(),
+        }
+        .and_then(|e| e.bytes(&self.signature))
+        .expect("infallible");
+
+        e.into_writer()
+    }
+
+    /// The signed payload, `None` if the payload is detached
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.payload.as_deref()
+    }
+
+    /// The algorithm declared in the protected headers
+    pub fn algorithm(&self) -> Result<Option<i64>, Error> {
+        let mut d = Decoder::new(&self.protected);
+        let entries = d.map()?.unwrap_or_default();
+
+        for _ in 0..entries {
+            match decode_label(&mut d)? {
+                Some(1) => return Ok(Some(d.i64()?)),
+                _ => d.skip()?,
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The address of the signer, as committed in the protected headers
+    pub fn address(&self) -> Result<Vec<u8>, Error> {
+        let mut d = Decoder::new(&self.protected);
+        let entries = d.map()?.unwrap_or_default();
+
+        for _ in 0..entries {
+            let is_address = d.datatype()? == Type::String && d.probe().str()? == "address";
+
+            d.skip()?;
+
+            match is_address {
+                true => return Ok(d.bytes()?.to_vec()),
+                false => d.skip()?,
+            }
+        }
+
+        Err(Error::MissingAddress)
+    }
+
+    /// Builds the `Sig_structure` that was signed for a given payload
+    pub fn sig_structure(&self, payload: &[u8]) -> Vec<u8> {
+        let mut e = minicbor::Encoder::new(vec![]);
+
+        e.array(4)
+            .and_then(|e| e.str("Signature1"))
+            .and_then(|e| e.bytes(&self.protected))
+            .and_then(|e| e.bytes(&[]))
+            .and_then(|e| e.bytes(payload))
+            .expect("infallible");
+
+        e.into_writer()
+    }
+
+    /// Verifies the signature over the embedded payload
+    ///
+    /// Besides the signature itself, this checks that the algorithm is EdDSA
+    /// and that the key hashes to the credential of the address in the
+    /// protected headers.
+    pub fn verify(&self, key: &PublicKey) -> Result<(), Error> {
+        let payload = self.payload().ok_or(Error::MissingPayload)?;
+        self.verify_detached(key, payload)
+    }
+
+    /// Verifies the signature over a payload that is not embedded in the
+    /// structure
+    pub fn verify_detached(&self, key: &PublicKey, payload: &[u8]) -> Result<(), Error> {
+        match self.algorithm()? {
+            Some(ALG_EDDSA) => (),
+            Some(x) => return Err(Error::UnsupportedAlgorithm(x)),
+            None => return Err(Error::InvalidKey("missing algorithm")),
+        }
+
+        let address = self.address()?;
+        let credential = address_key_hash(&address).ok_or(Error::UnsupportedAddress)?;
+
+        if Hasher::<224>::hash(key.as_ref()).as_ref() != credential {
+            return Err(Error::AddressMismatch);
+        }
+
+        let signature = Signature::try_from(self.signature.as_slice())
+            .map_err(|_| Error::InvalidSignatureSize(self.signature.len()))?;
+
+        if !key.verify(self.sig_structure(payload), &signature) {
+            return Err(Error::InvalidSignature);
+        }
+
+        Ok(())
+    }
+}
+
+/// The key hash of the credential an address commits to, for addresses
+/// controlled by a key
+///
+/// That's the payment key for base, pointer and enterprise addresses and the
+/// stake key for reward addresses. Script-controlled and Byron addresses are
+/// not supported.
+fn address_key_hash(address: &[u8]) -> Option<&[u8]> {
+    let header = *address.first()?;
+
+    match header >> 4 {
+        0b0000 | 0b0010 | 0b0100 | 0b0110 | 0b1110 => address.get(1..29),
+        _ => None,
+    }
+}
+
+/// Encodes an Ed25519 public key as a COSE_Key structure
+pub fn public_key_to_cose_key(key: &PublicKey) -> Vec<u8> {
+    let mut e = minicbor::Encoder::new(vec![]);
+
+    e.map(4)
+        .and_then(|e| e.i64(1))
+        .and_then(|e| e.i64(KTY_OKP))
+        .and_then(|e| e.i64(3))
+        .and_then(|e| e.i64(ALG_EDDSA))
+        .and_then(|e| e.i64(-1))
+        .and_then(|e| e.i64(CRV_ED25519))
+        .and_then(|e| e.i64(-2))
+        .and_then(|e| e.bytes(key.as_ref()))
+        .expect("infallible");
+
+    e.into_writer()
+}
+
+/// Extracts an Ed25519 public key from a COSE_Key structure
+pub fn public_key_from_cose_key(bytes: &[u8]) -> Result<PublicKey, Error> {
+    let mut d = Decoder::new(bytes);
+    let entries = d.map()?.unwrap_or_default();
+
+    let mut key = None;
+
+    for _ in 0..entries {
+        match decode_label(&mut d)? {
+            Some(1) => {
+                if d.i64()? != KTY_OKP {
+                    return Err(Error::InvalidKey("key type is not OKP"));
+                }
+            }
+            Some(3) => match d.i64()? {
+                ALG_EDDSA => (),
+                x => return Err(Error::UnsupportedAlgorithm(x)),
+            },
+            Some(-1) => {
+                if d.i64()? != CRV_ED25519 {
+                    return Err(Error::InvalidKey("curve is not Ed25519"));
+                }
+            }
+            Some(-2) => key = Some(d.bytes()?),
+            _ => d.skip()?,
+        }
+    }
+
+    let key = key.ok_or(Error::InvalidKey("missing public key"))?;
+
+    PublicKey::try_from(key).map_err(|_| Error::InvalidKey("invalid public key size"))
+}
+
+/// Verifies a CIP-30 `DataSignature`, given as the CBOR of its COSE_Sign1
+/// and COSE_Key parts
+///
+/// Returns the signed payload and the address of the signer.
+pub fn verify_data_signature(signature: &[u8], key: &[u8]) -> Result<(Vec<u8>, Vec<u8>), Error> {
+    let sign1 = CoseSign1::from_bytes(signature)?;
+    let key = public_key_from_cose_key(key)?;
+
+    sign1.verify(&key)?;
+
+    let payload = sign1.payload().ok_or(Error::MissingPayload)?.to_vec();
+
+    Ok((payload, sign1.address()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::OsRng;
+
+    use super::*;
+    use crate::key::ed25519::SecretKey;
+
+    fn enterprise_address(key: &PublicKey) -> Vec<u8> {
+        [&[0b0110_0001], Hasher::<224>::hash(key.as_ref()).as_ref()].concat()
+    }
+
+    fn sign(secret: &SecretKey, address: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut message = CoseSign1::new(address, payload);
+        message.set_signature(&secret.sign(message.sig_structure(payload)));
+        message.to_bytes()
+    }
+
+    #[test]
+    fn valid_signature_is_verified() {
+        let secret = SecretKey::new(OsRng);
+        let key = secret.public_key();
+        let address = enterprise_address(&key);
+
+        let signed = sign(&secret, &address, b"hello world");
+
+        let (payload, signer) =
+            verify_data_signature(&signed, &public_key_to_cose_key(&key)).unwrap();
+        assert_eq!(payload, b"hello world");
+        assert_eq!(signer, address);
+    }
+
+    #[test]
+    fn tampered_signatures_are_rejected() {
+        let secret = SecretKey::new(OsRng);
+        let key = secret.public_key();
+        let address = enterprise_address(&key);
+
+        let signed = CoseSign1::from_bytes(&sign(&secret, &address, b"hello world")).unwrap();
+
+        assert!(matches!(
+            signed.verify_detached(&key, b"hello there"),
+            Err(Error::InvalidSignature)
+        ));
+
+        let other = SecretKey::new(OsRng).public_key();
+        assert!(matches!(signed.verify(&other), Err(Error::AddressMismatch)));
+
+        // signed by the right key, but for someone else's address
+        let forged = sign(&secret, &enterprise_address(&other), b"hello world");
+        let forged = CoseSign1::from_bytes(&forged).unwrap();
+        assert!(matches!(forged.verify(&key), Err(Error::AddressMismatch)));
+
+        // script addresses can't be checked against a key
+        let mut script_address = address.clone();
+        script_address[0] = 0b0111_0001;
+        let script = CoseSign1::from_bytes(&sign(&secret, &script_address, b"hi")).unwrap();
+        assert!(matches!(
+            script.verify(&key),
+            Err(Error::UnsupportedAddress)
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use crate::hash::Hash;
-use cryptoxide::blake2b::Blake2b;
+use cryptoxide::{blake2b::Blake2b, sha3::Sha3_256};
 use pallas_codec::minicbor;
 
 /// handy method to create a hash of given `SIZE` bit size.
@@ -48,6 +48,23 @@ impl<const BITS: usize> Hasher<BITS> {
         use cryptoxide::digest::Digest as _;
         self.0.input(bytes);
     }
+
+    /// update the [`Hasher`] with the given inputs, allowing to chain calls
+    ///
+    /// ```
+    /// # use pallas_crypto::hash::Hasher;
+    ///
+    /// let mut hasher = Hasher::<224>::new();
+    /// hasher.update(&[0x61]).update(b"payment").update(b"delegation");
+    ///
+    /// let digest = hasher.finalize();
+    /// # assert_eq!(digest, Hasher::<224>::hash(b"\x61paymentdelegation"));
+    /// ```
+    #[inline]
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        self.input(bytes);
+        self
+    }
 }
 
 macro_rules! common_hasher {
@@ -117,6 +134,99 @@ macro_rules! common_hasher {
 common_hasher!(160);
 common_hasher!(224);
 common_hasher!(256);
+common_hasher!(512);
+
+/// handy method to create a `SHA3-256` hash, as used by Byron addresses
+///
+/// It exposes the same API as [`Hasher`], yielding a 32 bytes digest.
+///
+/// ```
+/// # use pallas_crypto::hash::Sha3Hasher;
+///
+/// let digest = Sha3Hasher::hash(b"My transaction");
+/// # assert_eq!(
+/// #   "8d78c47d5040747b5e84e93e98d9e0a4d06a5b653a80903a67a3479c432425ce",
+/// #   hex::encode(digest)
+/// # );
+/// ```
+pub struct Sha3Hasher(Sha3_256);
+
+impl Sha3Hasher {
+    /// create a new [`Sha3Hasher`]
+    #[inline]
+    pub fn new() -> Self {
+        Self(Sha3_256::new())
+    }
+
+    /// update the [`Sha3Hasher`] with the given inputs
+    #[inline]
+    pub fn input(&mut self, bytes: &[u8]) {
+        use cryptoxide::digest::Digest as _;
+        self.0.input(bytes);
+    }
+
+    /// update the [`Sha3Hasher`] with the given inputs, allowing to chain
+    /// calls
+    #[inline]
+    pub fn update(&mut self, bytes: &[u8]) -> &mut Self {
+        self.input(bytes);
+        self
+    }
+
+    /// convenient function to directly generate the hash of the given bytes
+    #[inline]
+    pub fn hash(bytes: &[u8]) -> Hash<32> {
+        let mut hasher = Self::new();
+        hasher.input(bytes);
+        hasher.finalize()
+    }
+
+    /// convenient function to directly generate the hash
+    /// of the given [minicbor::Encode] data object
+    #[inline]
+    pub fn hash_cbor(data: &impl minicbor::Encode<()>) -> Hash<32> {
+        let mut hasher = Self::new();
+        let () = minicbor::encode(data, &mut hasher).expect("Infallible");
+        hasher.finalize()
+    }
+
+    /// consume the [`Sha3Hasher`] and returns the computed digest
+    pub fn finalize(mut self) -> Hash<32> {
+        use cryptoxide::digest::Digest as _;
+        let mut hash = [0; 32];
+        self.0.result(&mut hash);
+        Hash::new(hash)
+    }
+}
+
+impl Default for Sha3Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl std::io::Write for Sha3Hasher {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.input(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl minicbor::encode::Write for &mut Sha3Hasher {
+    type Error = std::convert::Infallible;
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        self.input(buf);
+        Ok(())
+    }
+}
 
 /*
 TODO: somehow the `minicbor::Write` does not allow to implement this
@@ -134,6 +244,21 @@ impl<const BITS: usize> Write for Hasher<BITS> {
 }
 */
 
+/// allows to stream data into the [`Hasher`] (eg: using [`std::io::copy`])
+/// without buffering it first
+impl<const BITS: usize> std::io::Write for Hasher<BITS> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.input(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 impl<const BITS: usize> minicbor::encode::Write for &mut Hasher<BITS> {
     type Error = std::convert::Infallible;
 
@@ -143,3 +268,51 @@ impl<const BITS: usize> minicbor::encode::Write for &mut Hasher<BITS> {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let data: Vec<u8> = (0..10_000u32).map(|x| x as u8).collect();
+
+        let mut hasher = Hasher::<256>::new();
+
+        for chunk in data.chunks(333) {
+            hasher.update(chunk);
+        }
+
+        assert_eq!(hasher.finalize(), Hasher::<256>::hash(&data));
+
+        let mut hasher = Hasher::<224>::new();
+        std::io::copy(&mut data.as_slice(), &mut hasher).unwrap();
+
+        assert_eq!(hasher.finalize(), Hasher::<224>::hash(&data));
+    }
+
+    #[test]
+    fn known_digests() {
+        let vectors: [(&[u8], &str, &str); 2] = [
+            (
+                b"",
+                "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce",
+                "a7ffc6f8bf1ed76651c14756a061d662f580ff4de43b49fa82d80a4b80f8434a",
+            ),
+            (
+                b"abc",
+                "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923",
+                "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+            ),
+        ];
+
+        for (input, blake2b_512, sha3_256) in vectors {
+            assert_eq!(Hasher::<512>::hash(input).to_string(), blake2b_512);
+            assert_eq!(Sha3Hasher::hash(input).to_string(), sha3_256);
+
+            let mut hasher = Sha3Hasher::new();
+            hasher.update(input);
+            assert_eq!(hasher.finalize().to_string(), sha3_256);
+        }
+    }
+}
@@ -7,9 +7,9 @@
 //!   encoding for everything by the cardano protocol
 //! * [`struct@Hash`] a conveniently strongly typed byte array
 //!
-//! The algorithm exposed here is `Blake2b`. We currently support two
-//! digest size for the algorithm: 224 bits and 256 bits. They are the
-//! only two required to use with the Cardano protocol
+//! The algorithm exposed here is `Blake2b`. We currently support digests
+//! of 160, 224, 256 and 512 bits. [`Sha3Hasher`] provides `SHA3-256`,
+//! which is required to derive Byron addresses
 //!
 //! # Example
 //!
@@ -31,4 +31,7 @@ mod hash;
 mod hasher;
 mod serde;
 
-pub use self::{hash::Hash, hasher::Hasher};
+pub use self::{
+    hash::Hash,
+    hasher::{Hasher, Sha3Hasher},
+};
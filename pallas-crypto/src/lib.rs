@@ -1,5 +1,6 @@
 extern crate core;
 
+pub mod cip8;
 pub mod hash;
 pub mod key;
 pub mod memsec;
@@ -1,7 +1,7 @@
 use std::{
     fs::File,
-    io::{BufReader, Read, Seek},
-    path::Path,
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
 };
 
 use immutable::secondary;
@@ -12,8 +12,23 @@ use crate::storage::immutable;
 pub type SecondaryIndex = super::secondary::Reader;
 pub type SecondaryEntry = super::secondary::Entry;
 
+type ChunkData = Box<dyn Read + Send + Sync>;
+
+/// Extension of compressed chunk files
+#[cfg(feature = "zstd")]
+const ZSTD_EXTENSION: &str = "chunk.zst";
+
+/// Magic number at the start of every zstd frame
+#[cfg(feature = "zstd")]
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
 pub struct Reader {
-    inner: BufReader<File>,
+    inner: ChunkData,
+    // chunk data might be decompressed on the fly, which can't be seeked, so
+    // we track the offset of the reader ourselves
+    position: u64,
+    // check each block against the checksum of its secondary index entry
+    verify: bool,
     index: SecondaryIndex,
     current: Option<Result<SecondaryEntry, secondary::Error>>,
     next: Option<Result<SecondaryEntry, secondary::Error>>,
@@ -27,40 +42,65 @@ pub enum Error {
     CannotReadBlock(std::io::Error),
     #[error(transparent)]
     SecondaryIndexError(secondary::Error),
+    #[error("Block checksum mismatch at offset {0}, expected {1:#010x}, found {2:#010x}")]
+    ChecksumMismatch(u64, u32, u32),
 }
 
-impl Reader {
-    fn open(mut index: SecondaryIndex, chunks: File) -> Self {
-        let inner = BufReader::new(chunks);
+const CRC32: crc::Crc<u32> = crc::Crc::<u32>::new(&crc::CRC_32_ISO_HDLC);
 
+impl Reader {
+    fn open(mut index: SecondaryIndex, chunks: ChunkData) -> Self {
         let current = index.next();
         let next = index.next();
 
         Self {
-            inner,
+            inner: chunks,
+            position: 0,
+            verify: false,
             index,
             current,
             next,
         }
     }
 
-    fn read_middle_block(file: &mut BufReader<File>, next_offset: u64) -> Result<Vec<u8>, Error> {
-        let start = file.stream_position().map_err(Error::CannotReadBlock)?;
+    fn read_middle_block(&mut self, next_offset: u64) -> Result<Vec<u8>, Error> {
+        let start = self.position;
         let delta = next_offset - start;
         trace!(start, delta, "reading chunk middle block");
 
         let mut buf = vec![0u8; delta as usize];
-        file.read_exact(&mut buf).map_err(Error::CannotReadBlock)?;
+        self.inner
+            .read_exact(&mut buf)
+            .map_err(Error::CannotReadBlock)?;
+        self.position += delta;
 
         Ok(buf)
     }
 
-    fn read_last_block(file: &mut BufReader<File>) -> Result<Vec<u8>, Error> {
-        let start = file.stream_position().map_err(Error::CannotReadBlock)?;
+    fn check(&self, entry: &SecondaryEntry, block: Vec<u8>) -> Result<Vec<u8>, Error> {
+        let found = CRC32.checksum(&block);
+
+        if found != entry.checksum {
+            return Err(Error::ChecksumMismatch(
+                entry.block_offset,
+                entry.checksum,
+                found,
+            ));
+        }
+
+        Ok(block)
+    }
+
+    fn read_last_block(&mut self) -> Result<Vec<u8>, Error> {
+        let start = self.position;
         trace!(start, "reading chunk last block");
 
         let mut buf = vec![];
-        file.read_to_end(&mut buf).map_err(Error::CannotReadBlock)?;
+        let read = self
+            .inner
+            .read_to_end(&mut buf)
+            .map_err(Error::CannotReadBlock)?;
+        self.position += read as u64;
 
         Ok(buf)
     }
@@ -78,16 +118,26 @@ impl Iterator for Reader {
 
                 Some(Err(Error::SecondaryIndexError(next)))
             }
-            (Some(_), Some(Ok(next))) => {
-                let block = Self::read_middle_block(&mut self.inner, next.block_offset);
+            (Some(current), Some(Ok(next))) => {
+                let block = self.read_middle_block(next.block_offset);
+
+                let block = match (self.verify, current) {
+                    (true, Ok(current)) => block.and_then(|x| self.check(&current, x)),
+                    _ => block,
+                };
 
                 self.current = Some(Ok(next));
                 self.next = self.index.next();
 
                 Some(block)
             }
-            (Some(_), None) => {
-                let block = Self::read_last_block(&mut self.inner);
+            (Some(current), None) => {
+                let block = self.read_last_block();
+
+                let block = match (self.verify, current) {
+                    (true, Ok(current)) => block.and_then(|x| self.check(&current, x)),
+                    _ => block,
+                };
 
                 self.current = None;
                 self.next = None;
@@ -98,14 +148,76 @@ impl Iterator for Reader {
     }
 }
 
+/// Returns the name of the chunk stored at the given path, if it's a chunk
+/// file
+///
+/// With the `zstd` feature, compressed chunks (`.chunk.zst`) are recognized
+/// too.
+pub fn chunk_name(path: &Path) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+
+    #[cfg(feature = "zstd")]
+    if let Some(name) = file_name.strip_suffix(ZSTD_EXTENSION) {
+        return name.strip_suffix('.').map(str::to_owned);
+    }
+
+    file_name.strip_suffix(".chunk").map(str::to_owned)
+}
+
+#[cfg(feature = "zstd")]
+fn open_chunk(dir: &Path, name: &str) -> Result<ChunkData, Error> {
+    let plain: PathBuf = dir.join(name).with_extension("chunk");
+    let compressed: PathBuf = dir.join(name).with_extension(ZSTD_EXTENSION);
+
+    let path = match plain.exists() || !compressed.exists() {
+        true => plain,
+        false => compressed,
+    };
+
+    use std::io::BufRead as _;
+
+    let mut file = BufReader::new(File::open(path).map_err(Error::CannotOpenChunkFile)?);
+
+    // compressed chunks are detected by content, regardless of their name
+    let is_compressed = file
+        .fill_buf()
+        .map_err(Error::CannotOpenChunkFile)?
+        .starts_with(&ZSTD_MAGIC);
+
+    match is_compressed {
+        true => {
+            let decoder = zstd::Decoder::with_buffer(file).map_err(Error::CannotOpenChunkFile)?;
+            Ok(Box::new(decoder))
+        }
+        false => Ok(Box::new(file)),
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn open_chunk(dir: &Path, name: &str) -> Result<ChunkData, Error> {
+    let path: PathBuf = dir.join(name).with_extension("chunk");
+    let file = File::open(path).map_err(Error::CannotOpenChunkFile)?;
+
+    Ok(Box::new(BufReader::new(file)))
+}
+
 pub fn read_blocks(dir: &Path, name: &str) -> Result<Reader, Error> {
     let secondary = secondary::read_entries(dir, name).map_err(Error::SecondaryIndexError)?;
+    let chunk = open_chunk(dir, name)?;
 
-    let chunk = dir.join(name).with_extension("chunk");
-    let chunk = std::fs::File::open(chunk).map_err(Error::CannotOpenChunkFile)?;
     Ok(Reader::open(secondary, chunk))
 }
 
+/// Same as `read_blocks`, but each block is checked against the CRC32 stored
+/// in the secondary index, yielding `Error::ChecksumMismatch` for corrupt
+/// blocks
+pub fn read_blocks_verified(dir: &Path, name: &str) -> Result<Reader, Error> {
+    let mut reader = read_blocks(dir, name)?;
+    reader.verify = true;
+
+    Ok(reader)
+}
+
 #[cfg(test)]
 mod tests {
     use std::path::Path;
@@ -119,4 +231,91 @@ mod tests {
             pallas_traverse::MultiEraBlock::decode(&block).unwrap();
         }
     }
+
+    #[test]
+    fn corrupt_blocks_fail_verification() {
+        let blocks: Vec<_> = super::read_blocks_verified(Path::new("../test_data"), "01285")
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        let dir = std::env::temp_dir().join(format!("pallas-crc-chunk-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for ext in ["primary", "secondary"] {
+            let name = format!("01285.{ext}");
+            std::fs::copy(Path::new("../test_data").join(&name), dir.join(&name)).unwrap();
+        }
+
+        // flip a bit in the middle of the second block
+        let mut chunk = std::fs::read("../test_data/01285.chunk").unwrap();
+        chunk[blocks[0].len() + blocks[1].len() / 2] ^= 0x01;
+        std::fs::write(dir.join("01285.chunk"), chunk).unwrap();
+
+        let unverified = super::read_blocks(&dir, "01285").unwrap();
+        assert!(unverified.into_iter().all(|x| x.is_ok()));
+
+        let verified: Vec<_> = super::read_blocks_verified(&dir, "01285")
+            .unwrap()
+            .collect();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(verified.len(), blocks.len());
+        assert!(verified[0].is_ok());
+        assert!(matches!(
+            verified[1],
+            Err(super::Error::ChecksumMismatch(offset, _, _)) if offset == blocks[0].len() as u64
+        ));
+        assert!(verified[2..].iter().all(|x| x.is_ok()));
+    }
+
+    #[test]
+    fn chunk_names_are_recognized() {
+        assert_eq!(
+            super::chunk_name(Path::new("db/immutable/01285.chunk")),
+            Some("01285".into())
+        );
+
+        assert_eq!(
+            super::chunk_name(Path::new("db/immutable/01285.primary")),
+            None
+        );
+
+        #[cfg(feature = "zstd")]
+        assert_eq!(
+            super::chunk_name(Path::new("db/immutable/01285.chunk.zst")),
+            Some("01285".into())
+        );
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn it_can_decode_compressed_chunks() {
+        let dir = std::env::temp_dir().join(format!("pallas-zstd-chunk-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        for ext in ["primary", "secondary"] {
+            let name = format!("01285.{ext}");
+            std::fs::copy(Path::new("../test_data").join(&name), dir.join(&name)).unwrap();
+        }
+
+        let chunk = std::fs::read("../test_data/01285.chunk").unwrap();
+        let compressed = zstd::encode_all(chunk.as_slice(), 3).unwrap();
+        std::fs::write(dir.join("01285.chunk.zst"), compressed).unwrap();
+
+        let expected: Vec<_> = super::read_blocks(Path::new("../test_data"), "01285")
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        let blocks: Vec<_> = super::read_blocks(&dir, "01285")
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(blocks, expected);
+    }
 }
@@ -1,10 +1,9 @@
 use std::{
     cmp::Ordering,
-    ffi::OsStr,
     path::{Path, PathBuf},
 };
 
-use pallas_traverse::MultiEraBlock;
+use pallas_traverse::{time::Epoch, wellknown::GenesisValues, MultiEraBlock};
 use tap::Tap;
 use tracing::debug;
 
@@ -125,17 +124,12 @@ fn build_stack_of_chunk_names(dir: &Path) -> Result<ChunkNameSack, Error> {
     let mut chunks = std::fs::read_dir(dir)
         .map_err(Error::CannotReadDir)?
         .map_while(|e| e.ok())
-        .filter(|e| {
-            e.path()
-                .extension()
-                .map(|e| e.to_string_lossy() == "chunk")
-                .unwrap_or_default()
-        })
-        .filter_map(|e| e.path().file_stem().map(OsStr::to_owned))
-        .map(|s| s.to_string_lossy().to_string())
+        .filter_map(|e| chunk::chunk_name(&e.path()))
         .collect::<Vec<_>>();
 
     chunks.sort();
+    // a chunk might be available both compressed and uncompressed
+    chunks.dedup();
     // According to this docs https://mithril.network/doc/glossary/#immutable-file-number,
     // the last chunk files are not really immutable.
     // So to preserve only immutable data the last chunk files are omitted.
@@ -175,6 +169,113 @@ pub fn read_blocks(dir: &Path) -> Result<impl Iterator<Item = FallibleBlock>, Er
     Ok(iter)
 }
 
+/// Groups a stream of blocks into consecutive runs that belong to the same
+/// epoch
+///
+/// Blocks are buffered until the first block of the next epoch shows up, so
+/// at most one epoch worth of blocks is held in memory at any time. Read and
+/// decode errors are yielded as soon as they're found without discarding the
+/// epoch being accumulated.
+pub struct EpochBlocks<I> {
+    inner: I,
+    genesis: GenesisValues,
+    current: Option<(Epoch, Vec<Block>)>,
+}
+
+impl<I> EpochBlocks<I> {
+    fn epoch_of(&self, block: &[u8]) -> Result<Epoch, Error> {
+        let block = MultiEraBlock::decode(block).map_err(Error::CannotDecodeBlock)?;
+        let (epoch, _) = block.epoch(&self.genesis);
+
+        Ok(epoch)
+    }
+}
+
+impl<I> Iterator for EpochBlocks<I>
+where
+    I: Iterator<Item = FallibleBlock>,
+{
+    type Item = Result<(Epoch, Vec<Block>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let block = match self.inner.next() {
+                Some(Ok(x)) => x,
+                Some(Err(err)) => return Some(Err(Error::ChunkReadError(err))),
+                None => return self.current.take().map(Ok),
+            };
+
+            let epoch = match self.epoch_of(&block) {
+                Ok(x) => x,
+                Err(err) => return Some(Err(err)),
+            };
+
+            match &mut self.current {
+                Some((current, blocks)) if *current == epoch => blocks.push(block),
+                _ => {
+                    if let Some(done) = self.current.replace((epoch, vec![block])) {
+                        return Some(Ok(done));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Adaptors available on any iterator of blocks read from the immutable db
+pub trait BlockIterator: Iterator<Item = FallibleBlock> + Sized {
+    /// Groups the blocks by the epoch they belong to, using the slot to epoch
+    /// conversion of the given network
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use pallas_hardano::storage::immutable::{read_blocks, BlockIterator};
+    /// use pallas_traverse::wellknown::GenesisValues;
+    ///
+    /// let blocks = read_blocks(Path::new("/path/to/immutable")).unwrap();
+    ///
+    /// for group in blocks.by_epoch(&GenesisValues::mainnet()) {
+    ///     let (epoch, blocks) = group.unwrap();
+    ///     println!("epoch {epoch} has {} blocks", blocks.len());
+    /// }
+    /// ```
+    fn by_epoch(self, genesis: &GenesisValues) -> EpochBlocks<Self> {
+        EpochBlocks {
+            inner: self,
+            genesis: genesis.clone(),
+            current: None,
+        }
+    }
+}
+
+impl<I> BlockIterator for I where I: Iterator<Item = FallibleBlock> {}
+
+/// Same as `read_blocks`, but each block is checked against the checksum
+/// stored in the secondary index of its chunk
+///
+/// Corrupt blocks are yielded as `chunk::Error::ChecksumMismatch` errors, and
+/// chunks that can't be opened are reported instead of ending the iteration,
+/// which makes it suitable for integrity checks of stored snapshots.
+pub fn read_blocks_verified(dir: &Path) -> Result<impl Iterator<Item = FallibleBlock>, Error> {
+    let names = build_stack_of_chunk_names(dir)?;
+    let dir = dir.to_owned();
+
+    let iter =
+        names
+            .into_iter()
+            .rev()
+            .flat_map(move |name| -> Box<dyn Iterator<Item = FallibleBlock>> {
+                match chunk::read_blocks_verified(&dir, &name) {
+                    Ok(reader) => Box::new(reader),
+                    Err(err) => Box::new(std::iter::once(Err(err))),
+                }
+            });
+
+    Ok(iter)
+}
+
 /// Returns an iterator over the chain from the given point if the specific
 /// block is found, otherwise returns an error.
 ///
@@ -439,6 +540,35 @@ mod tests {
         assert_eq!(count, 1777);
     }
 
+    #[test]
+    fn blocks_are_grouped_by_epoch() {
+        use super::{read_blocks, BlockIterator};
+        use pallas_traverse::wellknown::GenesisValues;
+
+        let genesis = GenesisValues::mainnet();
+
+        let groups: Vec<_> = read_blocks(Path::new("../test_data"))
+            .unwrap()
+            .by_epoch(&genesis)
+            .map(Result::unwrap)
+            .collect();
+
+        assert!(groups.len() > 1);
+        assert!(groups.windows(2).all(|x| x[0].0 < x[1].0));
+
+        for (epoch, blocks) in groups.iter() {
+            assert!(!blocks.is_empty());
+
+            for block in blocks {
+                let block = MultiEraBlock::decode(block).unwrap();
+                assert_eq!(block.epoch(&genesis).0, *epoch);
+            }
+        }
+
+        let count: usize = groups.iter().map(|(_, blocks)| blocks.len()).sum();
+        assert_eq!(count, 1777);
+    }
+
     #[test]
     fn can_read_multiple_chunks_from_folder_at_specific_point() {
         let reader = super::read_blocks_from_point(
@@ -1,3 +1,5 @@
+use pallas_codec::Fragment;
+use std::future::Future;
 use std::net::SocketAddr;
 use std::path::Path;
 use std::time::Duration;
@@ -13,7 +15,7 @@ use crate::miniprotocols::handshake::{n2c, n2n, Confirmation, VersionNumber};
 
 use crate::miniprotocols::{
     blockfetch, chainsync, handshake, keepalive, localstate, localtxsubmission, peersharing,
-    txmonitor, txsubmission, PROTOCOL_N2C_CHAIN_SYNC, PROTOCOL_N2C_HANDSHAKE,
+    txmonitor, txsubmission, Point, PROTOCOL_N2C_CHAIN_SYNC, PROTOCOL_N2C_HANDSHAKE,
     PROTOCOL_N2C_STATE_QUERY, PROTOCOL_N2C_TX_MONITOR, PROTOCOL_N2C_TX_SUBMISSION,
     PROTOCOL_N2N_BLOCK_FETCH, PROTOCOL_N2N_CHAIN_SYNC, PROTOCOL_N2N_HANDSHAKE,
     PROTOCOL_N2N_KEEP_ALIVE, PROTOCOL_N2N_PEER_SHARING, PROTOCOL_N2N_TX_SUBMISSION,
@@ -38,6 +40,12 @@ pub enum Error {
     #[error("keepalive server loop error")]
     KeepAliveServerLoop(keepalive::ServerError),
 
+    #[error("blockfetch server loop error")]
+    BlockfetchServerLoop(blockfetch::ServerError),
+
+    #[error("chainsync server loop error")]
+    ChainSyncServerLoop(chainsync::ServerError),
+
     #[error("handshake version not accepted")]
     IncompatibleVersion,
 }
@@ -98,6 +106,204 @@ impl KeepAliveLoop {
     }
 }
 
+pub type BlockfetchServerHandle = tokio::task::JoinHandle<Result<(), Error>>;
+
+/// Server side of the blockfetch mini-protocol backed by a block provider
+///
+/// The provider receives the range requested by the client and returns the
+/// CBOR bodies of the blocks within it, in order. An empty list is answered
+/// with `NoBlocks`.
+pub struct BlockfetchServer<F> {
+    server: blockfetch::Server,
+    provider: F,
+}
+
+impl<F> BlockfetchServer<F>
+where
+    F: Fn((Point, Point)) -> Vec<Vec<u8>>,
+{
+    pub fn new(server: blockfetch::Server, provider: F) -> Self {
+        Self { server, provider }
+    }
+
+    pub fn server(&mut self) -> &mut blockfetch::Server {
+        &mut self.server
+    }
+
+    /// Waits for the next client request and answers it
+    ///
+    /// Returns `false` once the client has sent `ClientDone`.
+    pub async fn serve_next(&mut self) -> Result<bool, Error> {
+        let request = self
+            .server
+            .recv_while_idle()
+            .await
+            .map_err(Error::BlockfetchServerLoop)?;
+
+        let blockfetch::BlockRequest(range) = match request {
+            Some(request) => request,
+            None => return Ok(false),
+        };
+
+        debug!(?range, "serving blockfetch range");
+
+        let blocks = (self.provider)(range);
+
+        self.server
+            .send_block_range(blocks)
+            .await
+            .map_err(Error::BlockfetchServerLoop)?;
+
+        Ok(true)
+    }
+
+    /// Answers client requests until the client is done
+    pub async fn run(mut self) -> Result<(), Error> {
+        while self.serve_next().await? {}
+
+        Ok(())
+    }
+}
+
+impl<F> BlockfetchServer<F>
+where
+    F: Fn((Point, Point)) -> Vec<Vec<u8>> + Send + 'static,
+{
+    pub fn spawn(self) -> BlockfetchServerHandle {
+        tokio::spawn(self.run())
+    }
+}
+
+pub type ChainSyncServerHandle = tokio::task::JoinHandle<Result<(), Error>>;
+
+/// An update sent to the client of a [`ChainSyncServer`]
+#[derive(Debug)]
+pub enum ChainSyncEvent<C> {
+    RollForward(C, chainsync::Tip),
+    RollBackward(Point, chainsync::Tip),
+}
+
+/// Chain data backing a [`ChainSyncServer`]
+///
+/// A source is owned by a single server, it's responsible for tracking the
+/// read-pointer of the client it serves.
+pub trait ChainSyncSource<C> {
+    /// Moves the read-pointer to the first point of the list that is part of
+    /// the chain, returning it along with the current tip
+    fn find_intersect(&mut self, points: &[Point]) -> (Option<Point>, chainsync::Tip);
+
+    /// Returns the update that follows the read-pointer, or `None` if the
+    /// client already reached the tip
+    fn next(&mut self) -> Option<ChainSyncEvent<C>>;
+
+    /// Waits until the chain moves past the tip and returns the corresponding
+    /// update
+    fn wait_next(&mut self) -> impl Future<Output = ChainSyncEvent<C>> + Send;
+}
+
+/// Server side of the chainsync mini-protocol backed by a [`ChainSyncSource`]
+///
+/// When the client reaches the tip, the server replies with `AwaitReply` and
+/// sends the next update as soon as the source provides it.
+pub struct ChainSyncServer<S, C>
+where
+    chainsync::Message<C>: Fragment,
+{
+    server: chainsync::Server<C>,
+    source: S,
+}
+
+impl<S, C> ChainSyncServer<S, C>
+where
+    S: ChainSyncSource<C>,
+    chainsync::Message<C>: Fragment,
+{
+    pub fn new(server: chainsync::Server<C>, source: S) -> Self {
+        Self { server, source }
+    }
+
+    pub fn server(&mut self) -> &mut chainsync::Server<C> {
+        &mut self.server
+    }
+
+    pub fn source(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    async fn send_event(&mut self, event: ChainSyncEvent<C>) -> Result<(), Error> {
+        match event {
+            ChainSyncEvent::RollForward(content, tip) => {
+                self.server.send_roll_forward(content, tip).await
+            }
+            ChainSyncEvent::RollBackward(point, tip) => {
+                self.server.send_roll_backward(point, tip).await
+            }
+        }
+        .map_err(Error::ChainSyncServerLoop)
+    }
+
+    /// Waits for the next client request and answers it
+    ///
+    /// Returns `false` once the client has sent `Done`.
+    pub async fn serve_next(&mut self) -> Result<bool, Error> {
+        let request = self
+            .server
+            .recv_while_idle()
+            .await
+            .map_err(Error::ChainSyncServerLoop)?;
+
+        match request {
+            Some(chainsync::ClientRequest::Intersect(points)) => {
+                debug!(?points, "serving chainsync intersect");
+
+                match self.source.find_intersect(&points) {
+                    (Some(point), tip) => self.server.send_intersect_found(point, tip).await,
+                    (None, tip) => self.server.send_intersect_not_found(tip).await,
+                }
+                .map_err(Error::ChainSyncServerLoop)?;
+            }
+            Some(chainsync::ClientRequest::RequestNext) => {
+                let event = match self.source.next() {
+                    Some(event) => event,
+                    None => {
+                        debug!("chainsync client reached the tip, awaiting");
+
+                        self.server
+                            .send_await_reply()
+                            .await
+                            .map_err(Error::ChainSyncServerLoop)?;
+
+                        self.source.wait_next().await
+                    }
+                };
+
+                self.send_event(event).await?;
+            }
+            None => return Ok(false),
+        }
+
+        Ok(true)
+    }
+
+    /// Answers client requests until the client is done
+    pub async fn run(mut self) -> Result<(), Error> {
+        while self.serve_next().await? {}
+
+        Ok(())
+    }
+}
+
+impl<S, C> ChainSyncServer<S, C>
+where
+    S: ChainSyncSource<C> + Send + 'static,
+    C: Send + Sync + 'static,
+    chainsync::Message<C>: Fragment,
+{
+    pub fn spawn(self) -> ChainSyncServerHandle {
+        tokio::spawn(self.run())
+    }
+}
+
 /// Client of N2N Ouroboros
 pub struct PeerClient {
     pub plexer: RunningPlexer,
@@ -106,6 +312,7 @@ pub struct PeerClient {
     pub blockfetch: blockfetch::Client,
     pub txsubmission: txsubmission::Client,
     pub peersharing: peersharing::Client,
+    accepted_version: (u64, n2n::VersionData),
 }
 
 impl PeerClient {
@@ -136,10 +343,17 @@ impl PeerClient {
             .await
             .map_err(Error::HandshakeProtocol)?;
 
-        if let handshake::Confirmation::Rejected(reason) = handshake {
-            error!(?reason, "handshake refused");
-            return Err(Error::IncompatibleVersion);
-        }
+        let accepted_version = match handshake {
+            handshake::Confirmation::Accepted(version, data) => (version, data),
+            handshake::Confirmation::Rejected(reason) => {
+                error!(?reason, "handshake refused");
+                return Err(Error::IncompatibleVersion);
+            }
+            handshake::Confirmation::QueryReply(_) => {
+                error!("handshake query reply when we expected acceptance");
+                return Err(Error::HandshakeProtocol(handshake::Error::InvalidInbound));
+            }
+        };
 
         let keepalive = KeepAliveLoop::client(
             keepalive,
@@ -154,6 +368,7 @@ impl PeerClient {
             blockfetch: blockfetch::Client::new(bf_channel),
             txsubmission: txsubmission::Client::new(txsub_channel),
             peersharing: peersharing::Client::new(peersharing_channel),
+            accepted_version,
         };
 
         Ok(client)
@@ -184,11 +399,96 @@ impl PeerClient {
         &mut self.peersharing
     }
 
+    /// Version number and params accepted by the remote peer
+    ///
+    /// Use it to check which optional features were negotiated, e.g. only
+    /// attempt peer-sharing if `peer_sharing()` returns `Some(true)`.
+    pub fn accepted_version(&self) -> &(u64, n2n::VersionData) {
+        &self.accepted_version
+    }
+
+    /// Intersects the chain at the first known point and returns a follower of
+    /// the headers that come after it
+    ///
+    /// Over N2N, chainsync delivers headers only, which is enough to decide
+    /// whether a block is worth downloading; its body can then be fetched
+    /// through the follower. Points should be sorted from most to least recent.
+    /// An empty list intersects at the origin.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IntersectionNotFound` if none of the points is part of the
+    /// peer's chain.
+    pub async fn follow_headers(
+        &mut self,
+        from: Vec<Point>,
+    ) -> Result<HeaderFollower<'_>, chainsync::ClientError> {
+        let intersection = if from.is_empty() {
+            self.chainsync.intersect_origin().await?
+        } else {
+            let (point, _) = self.chainsync.find_intersect(from).await?;
+            point.ok_or(chainsync::ClientError::IntersectionNotFound)?
+        };
+
+        Ok(HeaderFollower {
+            chainsync: &mut self.chainsync,
+            blockfetch: &mut self.blockfetch,
+            intersection,
+        })
+    }
+
     pub async fn abort(self) {
         self.plexer.abort().await
     }
 }
 
+/// Headers following an intersection, as returned by
+/// [`PeerClient::follow_headers`]
+///
+/// Each call to `next_header` yields the next header, a rollback, or `Await`
+/// once the tip is reached; the call after an `Await` waits for the chain to
+/// move. In between, the body of any block announced so far can be fetched
+/// with blockfetch.
+///
+/// Like [`BlockFollower`], this isn't a `futures::Stream` so that each
+/// `next_header` call runs to completion, and so that blocks can be fetched
+/// in between.
+pub struct HeaderFollower<'a> {
+    chainsync: &'a mut chainsync::N2NClient,
+    blockfetch: &'a mut blockfetch::Client,
+    intersection: Point,
+}
+
+impl HeaderFollower<'_> {
+    pub fn intersection(&self) -> &Point {
+        &self.intersection
+    }
+
+    pub async fn next_header(
+        &mut self,
+    ) -> Result<chainsync::NextResponse<chainsync::HeaderContent>, chainsync::ClientError> {
+        self.chainsync.request_or_await_next().await
+    }
+
+    /// Downloads the body of the block at the given point
+    pub async fn fetch_block(
+        &mut self,
+        point: Point,
+    ) -> Result<blockfetch::Body, blockfetch::ClientError> {
+        self.blockfetch.fetch_single(point).await
+    }
+
+    /// Downloads the body of the block of a decoded header
+    #[cfg(feature = "traverse")]
+    pub async fn fetch_block_of(
+        &mut self,
+        header: &pallas_traverse::MultiEraHeader<'_>,
+    ) -> Result<blockfetch::Body, blockfetch::ClientError> {
+        let point = Point::Specific(header.slot(), header.hash().to_vec());
+        self.fetch_block(point).await
+    }
+}
+
 /// Server of N2N Ouroboros
 pub struct PeerServer {
     pub plexer: RunningPlexer,
@@ -436,11 +736,67 @@ impl NodeClient {
         &mut self.monitor
     }
 
+    /// Intersects the chain at the first known point and returns a follower of
+    /// the blocks that come after it
+    ///
+    /// Over N2C, chainsync delivers whole blocks, so no blockfetch round-trip
+    /// is needed. Points should be sorted from most to least recent. An empty
+    /// list intersects at the origin.
+    ///
+    /// # Errors
+    ///
+    /// Returns `IntersectionNotFound` if none of the points is part of the
+    /// node's chain.
+    pub async fn follow_blocks(
+        &mut self,
+        from: Vec<Point>,
+    ) -> Result<BlockFollower<'_>, chainsync::ClientError> {
+        let intersection = if from.is_empty() {
+            self.chainsync.intersect_origin().await?
+        } else {
+            let (point, _) = self.chainsync.find_intersect(from).await?;
+            point.ok_or(chainsync::ClientError::IntersectionNotFound)?
+        };
+
+        Ok(BlockFollower {
+            chainsync: &mut self.chainsync,
+            intersection,
+        })
+    }
+
     pub async fn abort(self) {
         self.plexer.abort().await
     }
 }
 
+/// Blocks following an intersection, as returned by
+/// [`NodeClient::follow_blocks`]
+///
+/// The node starts by rolling back to the intersection point. After that,
+/// each call yields the next block, a rollback, or `Await` once the tip is
+/// reached; the call after an `Await` waits for the chain to move.
+///
+/// This isn't a `futures::Stream` on purpose: dropping a pending
+/// `next_block` call halfway through leaves the chainsync client with a
+/// request in flight and out of step with the node, so each call should run
+/// to completion rather than be raced or cancelled by stream combinators.
+pub struct BlockFollower<'a> {
+    chainsync: &'a mut chainsync::N2CClient,
+    intersection: Point,
+}
+
+impl BlockFollower<'_> {
+    pub fn intersection(&self) -> &Point {
+        &self.intersection
+    }
+
+    pub async fn next_block(
+        &mut self,
+    ) -> Result<chainsync::NextResponse<chainsync::BlockContent>, chainsync::ClientError> {
+        self.chainsync.request_or_await_next().await
+    }
+}
+
 /// Server of N2C Ouroboros.
 #[cfg(unix)]
 pub struct NodeServer {
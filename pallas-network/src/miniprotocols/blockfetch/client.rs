@@ -38,7 +38,12 @@ pub type HasBlocks = Option<()>;
 /// This struct is used to interact with the Cardano network and fetch blocks
 /// from a remote node. It handles the state transitions and message exchange
 /// required to communicate with the network using the BlockFetch mini-protocol.
-pub struct Client(State, multiplexer::ChannelBuffer);
+///
+/// Range requests can be pipelined: the server answers them one batch at a
+/// time, in the order they were sent, so several of them can be in flight to
+/// hide the round-trip latency. The state of the client tracks the oldest
+/// outstanding request.
+pub struct Client(State, multiplexer::ChannelBuffer, usize);
 
 impl Client {
     /// Create a new BlockFetch client from a multiplexer agent channel.
@@ -48,7 +53,7 @@ impl Client {
     /// * `channel` - A multiplexer agent channel used for communication with
     ///   the remote node.
     pub fn new(channel: multiplexer::AgentChannel) -> Self {
-        Self(State::Idle, multiplexer::ChannelBuffer::new(channel))
+        Self(State::Idle, multiplexer::ChannelBuffer::new(channel), 0)
     }
 
     /// Get the current state of the client.
@@ -58,6 +63,11 @@ impl Client {
         &self.0
     }
 
+    /// Number of range requests sent whose batch hasn't been fully received
+    pub fn in_flight(&self) -> usize {
+        self.2
+    }
+
     /// Check if the client is done.
     ///
     /// Returns true if the client is in the `Done` state, false otherwise.
@@ -131,10 +141,50 @@ impl Client {
         let msg = Message::RequestRange { range };
         self.send_message(&msg).await?;
         self.0 = State::Busy;
+        self.2 = 1;
 
         Ok(())
     }
 
+    /// Send a range request without waiting for the batches already in
+    /// flight
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be sent or if the client is
+    /// done.
+    pub async fn send_request_range_pipelined(&mut self, range: Range) -> Result<(), ClientError> {
+        if self.0 == State::Done {
+            return Err(ClientError::InvalidOutbound);
+        }
+
+        self.1
+            .send_msg_chunks(&Message::RequestRange { range })
+            .await
+            .map_err(ClientError::Plexer)?;
+
+        if self.0 == State::Idle {
+            self.0 = State::Busy;
+        }
+
+        self.2 += 1;
+
+        debug!(in_flight = self.2, "pipelined range request");
+
+        Ok(())
+    }
+
+    /// Moves to the state of the next outstanding request once the current
+    /// batch is over
+    fn complete_batch(&mut self) {
+        self.2 = self.2.saturating_sub(1);
+
+        self.0 = match self.2 {
+            0 => State::Idle,
+            _ => State::Busy,
+        };
+    }
+
     pub async fn recv_while_busy(&mut self) -> Result<HasBlocks, ClientError> {
         match self.recv_message().await? {
             Message::StartBatch => {
@@ -144,7 +194,7 @@ impl Client {
             }
             Message::NoBlocks => {
                 warn!("no blocks");
-                self.0 = State::Idle;
+                self.complete_batch();
                 Ok(None)
             }
             _ => Err(ClientError::InvalidInbound),
@@ -173,7 +223,7 @@ impl Client {
         match self.recv_message().await? {
             Message::Block { body } => Ok(Some(body)),
             Message::BatchDone => {
-                self.0 = State::Idle;
+                self.complete_batch();
                 Ok(None)
             }
             _ => Err(ClientError::InvalidInbound),
@@ -230,6 +280,58 @@ impl Client {
         Ok(all)
     }
 
+    /// Receive the whole batch answering the oldest outstanding request
+    ///
+    /// Returns `None` if the server had no blocks for the requested range.
+    pub async fn recv_batch(&mut self) -> Result<Option<Vec<Body>>, ClientError> {
+        if self.recv_while_busy().await?.is_none() {
+            return Ok(None);
+        }
+
+        let mut all = vec![];
+
+        while let Some(block) = self.recv_while_streaming().await? {
+            all.push(block);
+        }
+
+        Ok(Some(all))
+    }
+
+    /// Fetch several ranges keeping up to `window` requests in flight
+    ///
+    /// The batches are returned in the same order as the ranges, with `None`
+    /// for the ones the server had no blocks for. A single connection serves
+    /// one batch at a time, so the window only hides latency; spreading
+    /// ranges across connections to different peers is up to the caller.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if other requests are already in flight, if a
+    /// message cannot be sent or if an inbound message is invalid.
+    pub async fn fetch_ranges(
+        &mut self,
+        ranges: impl IntoIterator<Item = Range>,
+        window: usize,
+    ) -> Result<Vec<Option<Vec<Body>>>, ClientError> {
+        self.assert_agency_is_ours()?;
+
+        let mut ranges = ranges.into_iter().peekable();
+        let mut all = vec![];
+
+        while ranges.peek().is_some() || self.2 > 0 {
+            while self.2 < window.max(1) {
+                match ranges.next() {
+                    Some(range) => self.send_request_range_pipelined(range).await?,
+                    None => break,
+                }
+            }
+
+            all.push(self.recv_batch().await?);
+        }
+
+        Ok(all)
+    }
+
     /// Send a `ClientDone` message to the remote node and set the client's
     /// state to `Done`.
     ///
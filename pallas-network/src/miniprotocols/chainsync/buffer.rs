@@ -79,6 +79,16 @@ impl RollbackBuffer {
         self.points.front()
     }
 
+    /// Number of points that a rollback to `point` would discard
+    ///
+    /// Returns `None` if the point is not in the buffer, in which case the
+    /// depth of the rollback can't be known from the buffered points alone.
+    /// Call this before `roll_back` to learn how much downstream state needs
+    /// to be reverted.
+    pub fn rollback_depth(&self, point: &Point) -> Option<usize> {
+        self.position(point).map(|x| self.points.len() - x - 1)
+    }
+
     /// Unwind the buffer up to a certain point, clearing orphaned items
     ///
     /// If the buffer contains the rollback point, we can safely discard from
@@ -179,6 +189,15 @@ mod tests {
         assert_eq!(remaining.len(), 3);
     }
 
+    #[test]
+    fn rollback_depth_counts_orphaned_points() {
+        let buffer = build_filled_buffer(6);
+
+        assert_eq!(buffer.rollback_depth(&dummy_point(2)), Some(3));
+        assert_eq!(buffer.rollback_depth(&dummy_point(5)), Some(0));
+        assert_eq!(buffer.rollback_depth(&dummy_point(100)), None);
+    }
+
     #[test]
     fn roll_back_outside_scope_works() {
         let mut buffer = build_filled_buffer(6);
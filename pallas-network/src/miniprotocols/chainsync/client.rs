@@ -36,7 +36,15 @@ pub enum NextResponse<CONTENT> {
     Await,
 }
 
-pub struct Client<O>(State, multiplexer::ChannelBuffer, PhantomData<O>)
+/// Client side of the chainsync mini-protocol
+///
+/// Besides the regular one-request-at-a-time flow, the client supports
+/// pipelining: sending several `RequestNext` messages before their responses
+/// arrive, which hides the round-trip latency when syncing in bulk. The state
+/// of the client tracks the oldest in-flight request; the number of requests
+/// still waiting for their roll forward / backward is available through
+/// [`Client::in_flight`].
+pub struct Client<O>(State, multiplexer::ChannelBuffer, PhantomData<O>, usize)
 where
     Message<O>: Fragment;
 
@@ -55,6 +63,7 @@ where
             State::Idle,
             multiplexer::ChannelBuffer::new(channel),
             PhantomData {},
+            0,
         )
     }
 
@@ -63,6 +72,12 @@ where
         &self.0
     }
 
+    /// Number of `RequestNext` messages sent that are still waiting for a
+    /// roll forward or roll backward
+    pub fn in_flight(&self) -> usize {
+        self.3
+    }
+
     /// Checks if the client is done.
     pub fn is_done(&self) -> bool {
         self.0 == State::Done
@@ -220,10 +235,52 @@ where
         let msg = Message::RequestNext;
         self.send_message(&msg).await?;
         self.0 = State::CanAwait;
+        self.3 = 1;
+
+        Ok(())
+    }
+
+    /// Sends a RequestNext message without waiting for the responses of the
+    /// ones already in flight
+    ///
+    /// The server answers requests in order, so the responses can be read
+    /// with [`Client::recv_next_response`]. Pipelining is not allowed while
+    /// the server is waiting for the chain to grow (`MustReply`), since
+    /// there's no latency to hide at the tip.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the message cannot be sent or if the client is not
+    /// idle nor waiting for a pipelined response.
+    pub async fn send_request_next_pipelined(&mut self) -> Result<(), ClientError> {
+        if !matches!(self.0, State::Idle | State::CanAwait) {
+            return Err(ClientError::InvalidOutbound);
+        }
+
+        self.1
+            .send_msg_chunks(&Message::<O>::RequestNext)
+            .await
+            .map_err(ClientError::Plexer)?;
+
+        self.0 = State::CanAwait;
+        self.3 += 1;
+
+        debug!(in_flight = self.3, "pipelined request next");
 
         Ok(())
     }
 
+    /// Moves to the state of the next in-flight request once the current one
+    /// got its roll forward / backward
+    fn complete_request(&mut self) {
+        self.3 = self.3.saturating_sub(1);
+
+        self.0 = match self.3 {
+            0 => State::Idle,
+            _ => State::CanAwait,
+        };
+    }
+
     /// Receives a response while the client is in the CanAwait state.
     ///
     /// # Errors
@@ -236,11 +293,11 @@ where
                 Ok(NextResponse::Await)
             }
             Message::RollForward(a, b) => {
-                self.0 = State::Idle;
+                self.complete_request();
                 Ok(NextResponse::RollForward(a, b))
             }
             Message::RollBackward(a, b) => {
-                self.0 = State::Idle;
+                self.complete_request();
                 Ok(NextResponse::RollBackward(a, b))
             }
             _ => Err(ClientError::InvalidInbound),
@@ -255,17 +312,54 @@ where
     pub async fn recv_while_must_reply(&mut self) -> Result<NextResponse<O>, ClientError> {
         match self.recv_message().await? {
             Message::RollForward(a, b) => {
-                self.0 = State::Idle;
+                self.complete_request();
                 Ok(NextResponse::RollForward(a, b))
             }
             Message::RollBackward(a, b) => {
-                self.0 = State::Idle;
+                self.complete_request();
                 Ok(NextResponse::RollBackward(a, b))
             }
             _ => Err(ClientError::InvalidInbound),
         }
     }
 
+    /// Receives the response to the oldest in-flight request
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there's no request in flight or if the inbound
+    /// message is invalid.
+    pub async fn recv_next_response(&mut self) -> Result<NextResponse<O>, ClientError> {
+        match self.0 {
+            State::CanAwait => self.recv_while_can_await().await,
+            State::MustReply => self.recv_while_must_reply().await,
+            _ => Err(ClientError::AgencyIsOurs),
+        }
+    }
+
+    /// Keeps up to `depth` requests in flight and returns the next response
+    ///
+    /// Each call tops the pipeline up to `depth` outstanding `RequestNext`
+    /// messages and then waits for the oldest one. Once the server replies
+    /// with `Await` (the tip was reached), no new requests are pipelined until
+    /// the chain moves; the requests already in flight are still answered, one
+    /// per call. A depth of 1 behaves like [`Client::request_or_await_next`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a message cannot be sent or if the inbound message
+    /// is invalid.
+    pub async fn request_next_pipelined(
+        &mut self,
+        depth: usize,
+    ) -> Result<NextResponse<O>, ClientError> {
+        while self.3 < depth.max(1) && self.0 != State::MustReply {
+            self.send_request_next_pipelined().await?;
+        }
+
+        self.recv_next_response().await
+    }
+
     /// Sends a RequestNext message to the server.
     ///
     /// # Errors
@@ -290,7 +384,7 @@ where
         if self.has_agency() {
             self.request_next().await
         } else {
-            self.recv_while_must_reply().await
+            self.recv_next_response().await
         }
     }
 
@@ -137,8 +137,7 @@ impl<'b> Decode<'b, ()> for HeaderContent {
             0 => {
                 d.array()?;
 
-                // can't find a reference anywhere about the structure of these values, but they
-                // seem to provide the Byron-specific variant of the header
+                // the Byron subtag (epoch boundary or main header) and the block size
                 let (a, b): (u8, u64) = d.decode()?;
 
                 d.tag()?;
@@ -31,11 +31,26 @@ pub enum Message<C> {
 
 #[derive(Debug)]
 pub struct HeaderContent {
+    /// Hard-fork era index of the header (0 for Byron, 1 for Shelley, ...)
     pub variant: u8,
+    /// Byron only: the subtag that tells epoch boundary (0) and main (1)
+    /// headers apart, followed by the size of the block
     pub byron_prefix: Option<(u8, u64)>,
+    /// The header bytes, as encoded by the era
     pub cbor: Vec<u8>,
 }
 
+#[cfg(feature = "traverse")]
+impl HeaderContent {
+    /// Decodes the header using the decoder of the era it belongs to
+    ///
+    /// The era is picked from the variant and, for Byron, from the prefix that
+    /// tells epoch boundary and main headers apart.
+    pub fn decode(&self) -> Result<pallas_traverse::MultiEraHeader<'_>, pallas_traverse::Error> {
+        pallas_traverse::MultiEraHeader::decode_wrapped(self.variant, self.byron_prefix, &self.cbor)
+    }
+}
+
 #[derive(Debug)]
 pub struct BlockContent(pub Vec<u8>);
 
@@ -55,3 +70,36 @@ impl From<BlockContent> for Vec<u8> {
 
 #[derive(Debug)]
 pub struct SkippedContent;
+
+#[cfg(all(test, feature = "traverse"))]
+mod tests {
+    use pallas_traverse::MultiEraBlock;
+
+    use super::*;
+
+    #[test]
+    fn header_content_decodes_to_era_header() {
+        for (block, variant, byron_prefix) in [
+            (
+                include_str!("../../../../test_data/byron2.block"),
+                0,
+                Some((1, 0)),
+            ),
+            (include_str!("../../../../test_data/conway1.block"), 6, None),
+        ] {
+            let cbor = hex::decode(block).unwrap();
+            let block = MultiEraBlock::decode(&cbor).unwrap();
+
+            let content = HeaderContent {
+                variant,
+                byron_prefix,
+                cbor: block.header().cbor().to_vec(),
+            };
+
+            let header = content.decode().unwrap();
+
+            assert_eq!(header.hash(), block.hash());
+            assert_eq!(header.slot(), block.slot());
+        }
+    }
+}
@@ -1,6 +1,8 @@
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
+use std::str::FromStr;
 
 use pallas_codec::minicbor::{decode, encode, Decode, Decoder, Encode, Encoder};
+use thiserror::Error;
 
 /// Well-known magic for testnet
 pub const TESTNET_MAGIC: u64 = 1097911063;
@@ -70,19 +72,45 @@ pub const PROTOCOL_N2C_STATE_QUERY: u16 = 7;
 pub const PROTOCOL_N2C_TX_MONITOR: u16 = 9;
 
 /// A point within a chain
-#[derive(Clone, Eq, PartialEq, Hash)]
+///
+/// Points are ordered by slot, with `Origin` coming before any specific point.
+/// Points sharing the same slot are ordered by hash so that the ordering stays
+/// consistent with equality.
+#[derive(Clone, Eq, PartialEq, Hash, PartialOrd, Ord)]
 pub enum Point {
     Origin,
     Specific(u64, Vec<u8>),
 }
 
 impl Point {
+    /// Slot of the point, `Origin` is considered to be at slot zero
     pub fn slot_or_default(&self) -> u64 {
         match self {
             Point::Origin => 0,
             Point::Specific(slot, _) => *slot,
         }
     }
+
+    pub fn is_origin(&self) -> bool {
+        matches!(self, Point::Origin)
+    }
+
+    /// Hash of the block the point refers to, `None` for `Origin`
+    pub fn hash(&self) -> Option<&[u8]> {
+        match self {
+            Point::Origin => None,
+            Point::Specific(_, hash) => Some(hash),
+        }
+    }
+
+    /// Returns true if this point is in a later slot than `other`
+    pub fn is_after(&self, other: &Point) -> bool {
+        match (self, other) {
+            (Point::Origin, _) => false,
+            (Point::Specific(..), Point::Origin) => true,
+            (Point::Specific(a, _), Point::Specific(b, _)) => a > b,
+        }
+    }
 }
 
 impl Debug for Point {
@@ -100,6 +128,55 @@ impl Point {
     }
 }
 
+/// Formats the point as `origin` or `slot/hash`, the same format accepted by
+/// `FromStr`
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Origin => write!(f, "origin"),
+            Self::Specific(slot, hash) => write!(f, "{}/{}", slot, hex::encode(hash)),
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq)]
+pub enum PointParseError {
+    #[error("expected `origin` or `slot/hash`, got `{0}`")]
+    InvalidFormat(String),
+
+    #[error("invalid slot number: {0}")]
+    InvalidSlot(std::num::ParseIntError),
+
+    #[error("invalid hash hex: {0}")]
+    InvalidHash(hex::FromHexError),
+
+    #[error("expected a 32-byte hash, got {0} bytes")]
+    InvalidHashLength(usize),
+}
+
+impl FromStr for Point {
+    type Err = PointParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "origin" {
+            return Ok(Point::Origin);
+        }
+
+        let (slot, hash) = s
+            .split_once('/')
+            .ok_or_else(|| PointParseError::InvalidFormat(s.to_owned()))?;
+
+        let slot = slot.parse().map_err(PointParseError::InvalidSlot)?;
+        let hash = hex::decode(hash).map_err(PointParseError::InvalidHash)?;
+
+        if hash.len() != 32 {
+            return Err(PointParseError::InvalidHashLength(hash.len()));
+        }
+
+        Ok(Point::Specific(slot, hash))
+    }
+}
+
 impl Encode<()> for Point {
     fn encode<W: encode::Write>(
         &self,
@@ -132,3 +209,66 @@ impl<'b> Decode<'b, ()> for Point {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn point_ordering() {
+        let origin = Point::Origin;
+        let early = Point::Specific(10, vec![0xff]);
+        let late = Point::Specific(20, vec![0x00]);
+        let sibling = Point::Specific(20, vec![0x01]);
+
+        assert!(origin < early);
+        assert!(early < late);
+        assert!(late < sibling);
+        assert_eq!(late.cmp(&late.clone()), std::cmp::Ordering::Equal);
+
+        assert!(late.is_after(&early));
+        assert!(early.is_after(&origin));
+        assert!(!origin.is_after(&origin));
+        assert!(!sibling.is_after(&late));
+
+        let mut points = vec![sibling.clone(), origin.clone(), late.clone(), early.clone()];
+        points.sort();
+        assert_eq!(points, vec![origin, early, late, sibling]);
+    }
+
+    #[test]
+    fn point_string_roundtrip() {
+        let hash = "4e9bbbb67e3ae262133d94c3da5bffce7b1127fc436e7433b87668dba34c354a";
+        let text = format!("2492700/{hash}");
+
+        let point = Point::from_str(&text).unwrap();
+        assert_eq!(point, Point::Specific(2492700, hex::decode(hash).unwrap()));
+        assert_eq!(point.to_string(), text);
+
+        assert_eq!(Point::from_str("origin").unwrap(), Point::Origin);
+        assert_eq!(Point::Origin.to_string(), "origin");
+    }
+
+    #[test]
+    fn point_string_errors() {
+        assert!(matches!(
+            Point::from_str("2492700"),
+            Err(PointParseError::InvalidFormat(_))
+        ));
+
+        assert!(matches!(
+            Point::from_str("abc/00"),
+            Err(PointParseError::InvalidSlot(_))
+        ));
+
+        assert!(matches!(
+            Point::from_str("10/zz"),
+            Err(PointParseError::InvalidHash(_))
+        ));
+
+        assert_eq!(
+            Point::from_str("10/c0ffee"),
+            Err(PointParseError::InvalidHashLength(3))
+        );
+    }
+}
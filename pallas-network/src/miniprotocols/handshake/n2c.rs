@@ -74,6 +74,30 @@ impl VersionTable {
         VersionTable { values }
     }
 
+    /// Table with the most recent versions negotiated by the node, with the
+    /// query flag off. Use `with_query` to override it.
+    pub fn latest(network_magic: u64) -> VersionTable {
+        let values = vec![
+            (PROTOCOL_V15, VersionData(network_magic, Some(false))),
+            (PROTOCOL_V16, VersionData(network_magic, Some(false))),
+        ]
+        .into_iter()
+        .collect::<HashMap<u64, VersionData>>();
+
+        VersionTable { values }
+    }
+
+    /// Sets the query flag for every version that supports it (v15+)
+    pub fn with_query(mut self, value: bool) -> Self {
+        for data in self.values.values_mut() {
+            if data.1.is_some() {
+                data.1 = Some(value);
+            }
+        }
+
+        self
+    }
+
     pub fn v15_with_query(network_magic: u64) -> VersionTable {
         let values = vec![(PROTOCOL_V15, VersionData(network_magic, Some(true)))]
             .into_iter()
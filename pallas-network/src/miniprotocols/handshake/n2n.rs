@@ -11,8 +11,10 @@ const PROTOCOL_V10: u64 = 10;
 const PROTOCOL_V11: u64 = 11;
 const PROTOCOL_V12: u64 = 12;
 const PROTOCOL_V13: u64 = 13;
+const PROTOCOL_V14: u64 = 14;
 
 const PEER_SHARING_DISABLED: u8 = 0;
+const PEER_SHARING_ENABLED: u8 = 1;
 
 impl VersionTable {
     #[deprecated(note = "no longer supported by spec")]
@@ -141,6 +143,65 @@ impl VersionTable {
 
         VersionTable { values }
     }
+
+    /// Table with the most recent versions negotiated by the node
+    ///
+    /// Every entry is set up as an initiator-only client, with peer-sharing
+    /// disabled and the query flag off. Use the `with_*` methods to override
+    /// any of these params.
+    pub fn latest(network_magic: u64) -> VersionTable {
+        let values = [PROTOCOL_V13, PROTOCOL_V14]
+            .into_iter()
+            .map(|version| {
+                let data = VersionData::new(
+                    network_magic,
+                    true,
+                    Some(PEER_SHARING_DISABLED),
+                    Some(false),
+                );
+
+                (version, data)
+            })
+            .collect::<HashMap<u64, VersionData>>();
+
+        VersionTable { values }
+    }
+
+    /// Sets the diffusion mode for every version in the table
+    pub fn with_initiator_only_diffusion_mode(mut self, value: bool) -> Self {
+        for data in self.values.values_mut() {
+            data.initiator_only_diffusion_mode = value;
+        }
+
+        self
+    }
+
+    /// Sets the peer-sharing flag for every version that supports it (v11+)
+    pub fn with_peer_sharing(mut self, value: bool) -> Self {
+        let value = match value {
+            true => PEER_SHARING_ENABLED,
+            false => PEER_SHARING_DISABLED,
+        };
+
+        for data in self.values.values_mut() {
+            if data.peer_sharing.is_some() {
+                data.peer_sharing = Some(value);
+            }
+        }
+
+        self
+    }
+
+    /// Sets the query flag for every version that supports it (v11+)
+    pub fn with_query(mut self, value: bool) -> Self {
+        for data in self.values.values_mut() {
+            if data.query.is_some() {
+                data.query = Some(value);
+            }
+        }
+
+        self
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -165,6 +226,52 @@ impl VersionData {
             query,
         }
     }
+
+    /// Builds the params for a specific version of the protocol
+    ///
+    /// The peer-sharing and query flags are only part of the params starting
+    /// with v11, they are dropped for older versions.
+    pub fn for_version(
+        version: u64,
+        network_magic: u64,
+        initiator_only_diffusion_mode: bool,
+        peer_sharing: bool,
+        query: bool,
+    ) -> Self {
+        if version < PROTOCOL_V11 {
+            return Self::new(network_magic, initiator_only_diffusion_mode, None, None);
+        }
+
+        let peer_sharing = match peer_sharing {
+            true => PEER_SHARING_ENABLED,
+            false => PEER_SHARING_DISABLED,
+        };
+
+        Self::new(
+            network_magic,
+            initiator_only_diffusion_mode,
+            Some(peer_sharing),
+            Some(query),
+        )
+    }
+
+    pub fn network_magic(&self) -> u64 {
+        self.network_magic
+    }
+
+    pub fn initiator_only_diffusion_mode(&self) -> bool {
+        self.initiator_only_diffusion_mode
+    }
+
+    /// Whether peer-sharing is enabled, `None` for versions before v11
+    pub fn peer_sharing(&self) -> Option<bool> {
+        self.peer_sharing.map(|x| x != PEER_SHARING_DISABLED)
+    }
+
+    /// Whether the query flag is set, `None` for versions before v11
+    pub fn query(&self) -> Option<bool> {
+        self.query
+    }
 }
 
 impl Encode<()> for VersionData {
@@ -214,3 +321,57 @@ impl<'b> Decode<'b, ()> for VersionData {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas_codec::minicbor;
+
+    use super::*;
+    use crate::miniprotocols::handshake::Message;
+
+    // propose for v13 and v14 as sent by a mainnet relay running in duplex
+    // mode with peer-sharing enabled
+    const NODE_PROPOSE_V13_V14: &str = "8200a20d841a2d964a09f401f40e841a2d964a09f401f4";
+
+    #[test]
+    fn version_data_flags_roundtrip() {
+        let bytes = hex::decode(NODE_PROPOSE_V13_V14).unwrap();
+        let msg: Message<VersionData> = minicbor::decode(&bytes).unwrap();
+
+        let versions = match &msg {
+            Message::Propose(versions) => versions,
+            _ => unreachable!(),
+        };
+
+        for data in versions.values.values() {
+            assert_eq!(data.network_magic(), 764824073);
+            assert!(!data.initiator_only_diffusion_mode());
+            assert_eq!(data.peer_sharing(), Some(true));
+            assert_eq!(data.query(), Some(false));
+        }
+
+        let expected = VersionTable::latest(764824073)
+            .with_initiator_only_diffusion_mode(false)
+            .with_peer_sharing(true);
+
+        let encoded = minicbor::to_vec(Message::Propose(expected)).unwrap();
+        assert_eq!(hex::encode(encoded), NODE_PROPOSE_V13_V14);
+    }
+
+    #[test]
+    fn version_data_flags_dropped_before_v11() {
+        let data = VersionData::for_version(PROTOCOL_V10, 1, true, true, true);
+        assert_eq!(data.peer_sharing(), None);
+        assert_eq!(data.query(), None);
+
+        let encoded = minicbor::to_vec(&data).unwrap();
+        assert_eq!(hex::encode(encoded), "8201f5");
+
+        let data = VersionData::for_version(PROTOCOL_V13, 1, true, true, true);
+        assert_eq!(data.peer_sharing(), Some(true));
+        assert_eq!(data.query(), Some(true));
+
+        let encoded = minicbor::to_vec(&data).unwrap();
+        assert_eq!(hex::encode(encoded), "8401f501f5");
+    }
+}
@@ -42,6 +42,34 @@ impl From<AcquireFailure> for ClientError {
     }
 }
 
+/// Response of a query that is kept even if it can't be decoded
+///
+/// Newer node versions might add fields to a response, which makes decoding
+/// into the expected type fail. Callers can still inspect the raw CBOR in
+/// that case.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryResult<T> {
+    /// The response, if it matched the expected structure
+    pub decoded: Option<T>,
+
+    /// The CBOR of the response, as sent by the node
+    pub raw: Vec<u8>,
+}
+
+impl<T> From<AnyCbor> for QueryResult<T>
+where
+    for<'b> T: pallas_codec::minicbor::Decode<'b, ()>,
+{
+    fn from(response: AnyCbor) -> Self {
+        let decoded = pallas_codec::minicbor::decode(response.raw_bytes()).ok();
+
+        Self {
+            decoded,
+            raw: response.unwrap(),
+        }
+    }
+}
+
 pub struct GenericClient(State, multiplexer::ChannelBuffer);
 
 impl GenericClient {
@@ -205,6 +233,37 @@ impl GenericClient {
 
         response.into_decode().map_err(ClientError::InvalidCbor)
     }
+
+    /// Like [Self::query], but keeps the raw response and doesn't fail if it
+    /// can't be decoded
+    pub async fn query_with_raw<Q, R>(&mut self, request: Q) -> Result<QueryResult<R>, ClientError>
+    where
+        Q: pallas_codec::minicbor::Encode<()>,
+        for<'b> R: pallas_codec::minicbor::Decode<'b, ()>,
+    {
+        let request = AnyCbor::from_encode(request);
+        let response = self.query_any(request).await?;
+
+        Ok(response.into())
+    }
 }
 
 pub type Client = GenericClient;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undecodable_response_keeps_raw_cbor() {
+        let result: QueryResult<(u16, u16)> = AnyCbor::from_encode((1u16, 2u16)).into();
+        assert_eq!(result.decoded, Some((1, 2)));
+
+        // a response with an extra field the client doesn't know about
+        let response = AnyCbor::from_encode((1u16, 2u16, 3u16));
+        let result: QueryResult<(u16, u16)> = response.clone().into();
+
+        assert_eq!(result.decoded, None);
+        assert_eq!(result.raw, response.unwrap());
+    }
+}
@@ -153,7 +153,7 @@ impl<'b> Decode<'b, ()> for BlockQuery {
             // 21 => Ok(Self::GetPoolDistr(())),
             // 22 => Ok(Self::GetStakeDelegDeposits(())),
             // 23 => Ok(Self::GetConstitutionHash),
-            _ => unreachable!(),
+            _ => Err(decode::Error::message("unknown block query tag")),
         }
     }
 }
@@ -418,3 +418,48 @@ impl<C> minicbor::encode::Encode<C> for FilteredDelegsRewards {
         Ok(())
     }
 }
+
+impl<'b, C, S, T> minicbor::decode::Decode<'b, C> for Either<S, T>
+where
+    S: minicbor::decode::Decode<'b, C>,
+    T: minicbor::decode::Decode<'b, C>,
+{
+    fn decode(d: &mut minicbor::Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
+        d.array()?;
+
+        match d.u16()? {
+            0 => Ok(Either::Left(d.decode_with(ctx)?)),
+            1 => Ok(Either::Right(d.decode_with(ctx)?)),
+            _ => Err(minicbor::decode::Error::message(
+                "unknown variant index for Either",
+            )),
+        }
+    }
+}
+
+impl<C, S, T> minicbor::encode::Encode<C> for Either<S, T>
+where
+    S: minicbor::encode::Encode<C>,
+    T: minicbor::encode::Encode<C>,
+{
+    fn encode<W: minicbor::encode::Write>(
+        &self,
+        e: &mut minicbor::Encoder<W>,
+        ctx: &mut C,
+    ) -> Result<(), minicbor::encode::Error<W::Error>> {
+        e.array(2)?;
+
+        match self {
+            Either::Left(x) => {
+                e.u16(0)?;
+                e.encode_with(x, ctx)?;
+            }
+            Either::Right(x) => {
+                e.u16(1)?;
+                e.encode_with(x, ctx)?;
+            }
+        };
+
+        Ok(())
+    }
+}
@@ -0,0 +1,223 @@
+//! Conversions between the pool registration data returned by the local-state
+//! queries and the pool registration certificates of `pallas-primitives`
+
+use pallas_codec::utils::{AnyUInt, Bytes, Nullable};
+use pallas_crypto::hash::Hash;
+use pallas_primitives::{alonzo, conway};
+use thiserror::Error;
+
+use super::{PoolIds, PoolMetadata, PoolParams, RationalNumber, Relay};
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ConversionError {
+    #[error("invalid length for {0}, expected {1} bytes but found {2}")]
+    InvalidHashLength(&'static str, usize, usize),
+
+    #[error("certificate is not a pool registration")]
+    NotPoolRegistration,
+}
+
+fn to_hash<const BYTES: usize>(
+    field: &'static str,
+    bytes: &Bytes,
+) -> Result<Hash<BYTES>, ConversionError> {
+    let array: [u8; BYTES] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| ConversionError::InvalidHashLength(field, BYTES, bytes.len()))?;
+
+    Ok(Hash::new(array))
+}
+
+fn owners_to_hashes(owners: &PoolIds) -> Result<Vec<Hash<28>>, ConversionError> {
+    owners
+        .hashes
+        .iter()
+        .map(|x| to_hash("pool owner", x))
+        .collect()
+}
+
+fn hashes_to_owners<'a>(owners: impl IntoIterator<Item = &'a Hash<28>>) -> PoolIds {
+    PoolIds {
+        hashes: owners.into_iter().map(|x| x.to_vec().into()).collect(),
+    }
+}
+
+impl From<&Relay> for pallas_primitives::Relay {
+    fn from(value: &Relay) -> Self {
+        match value {
+            Relay::SingleHostAddr(a, b, c) => Self::SingleHostAddr(a.clone(), b.clone(), c.clone()),
+            Relay::SingleHostName(a, b) => Self::SingleHostName(a.clone(), b.clone()),
+            Relay::MultiHostName(a) => Self::MultiHostName(a.clone()),
+        }
+    }
+}
+
+impl From<&pallas_primitives::Relay> for Relay {
+    fn from(value: &pallas_primitives::Relay) -> Self {
+        match value {
+            pallas_primitives::Relay::SingleHostAddr(a, b, c) => {
+                Self::SingleHostAddr(a.clone(), b.clone(), c.clone())
+            }
+            pallas_primitives::Relay::SingleHostName(a, b) => {
+                Self::SingleHostName(a.clone(), b.clone())
+            }
+            pallas_primitives::Relay::MultiHostName(a) => Self::MultiHostName(a.clone()),
+        }
+    }
+}
+
+fn to_primitive_metadata(
+    metadata: &Nullable<PoolMetadata>,
+) -> Nullable<pallas_primitives::PoolMetadata> {
+    metadata.clone().map(|x| pallas_primitives::PoolMetadata {
+        url: x.url,
+        hash: x.hash,
+    })
+}
+
+fn from_primitive_metadata(
+    metadata: &Nullable<pallas_primitives::PoolMetadata>,
+) -> Nullable<PoolMetadata> {
+    metadata.clone().map(|x| PoolMetadata {
+        url: x.url,
+        hash: x.hash,
+    })
+}
+
+macro_rules! pool_registration_conversions {
+    ($era:ident, $owners:expr) => {
+        impl TryFrom<&PoolParams> for $era::Certificate {
+            type Error = ConversionError;
+
+            fn try_from(value: &PoolParams) -> Result<Self, Self::Error> {
+                Ok($era::Certificate::PoolRegistration {
+                    operator: to_hash("operator", &value.operator)?,
+                    vrf_keyhash: to_hash("vrf key hash", &value.vrf_keyhash)?,
+                    pledge: value.pledge.into(),
+                    cost: value.cost.into(),
+                    margin: pallas_primitives::RationalNumber {
+                        numerator: value.margin.numerator,
+                        denominator: value.margin.denominator,
+                    },
+                    reward_account: value.reward_account.clone(),
+                    pool_owners: $owners(owners_to_hashes(&value.pool_owners)?),
+                    relays: value.relays.iter().map(Into::into).collect(),
+                    pool_metadata: to_primitive_metadata(&value.pool_metadata),
+                })
+            }
+        }
+
+        impl TryFrom<&$era::Certificate> for PoolParams {
+            type Error = ConversionError;
+
+            fn try_from(value: &$era::Certificate) -> Result<Self, Self::Error> {
+                match value {
+                    $era::Certificate::PoolRegistration {
+                        operator,
+                        vrf_keyhash,
+                        pledge,
+                        cost,
+                        margin,
+                        reward_account,
+                        pool_owners,
+                        relays,
+                        pool_metadata,
+                    } => Ok(PoolParams {
+                        operator: operator.to_vec().into(),
+                        vrf_keyhash: vrf_keyhash.to_vec().into(),
+                        pledge: AnyUInt::U64(*pledge),
+                        cost: AnyUInt::U64(*cost),
+                        margin: RationalNumber {
+                            numerator: margin.numerator,
+                            denominator: margin.denominator,
+                        },
+                        reward_account: reward_account.clone(),
+                        pool_owners: hashes_to_owners(pool_owners.iter()),
+                        relays: relays.iter().map(Into::into).collect(),
+                        pool_metadata: from_primitive_metadata(pool_metadata),
+                    }),
+                    _ => Err(ConversionError::NotPoolRegistration),
+                }
+            }
+        }
+    };
+}
+
+pool_registration_conversions!(alonzo, std::convert::identity);
+pool_registration_conversions!(conway, Into::into);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pool_params() -> PoolParams {
+        PoolParams {
+            operator: vec![1; 28].into(),
+            vrf_keyhash: vec![2; 32].into(),
+            pledge: AnyUInt::U64(1_000_000_000),
+            cost: AnyUInt::U32(340_000_000),
+            margin: RationalNumber {
+                numerator: 1,
+                denominator: 100,
+            },
+            reward_account: hex::decode(
+                "e1cccccccccccccccccccccccccccccccccccccccccccccccccccccccc",
+            )
+            .unwrap()
+            .into(),
+            pool_owners: PoolIds {
+                hashes: [vec![3; 28].into(), vec![4; 28].into()].into(),
+            },
+            relays: vec![
+                Relay::SingleHostName(Nullable::Some(3001), "relay.example.com".into()),
+                Relay::MultiHostName("pool.example.com".into()),
+            ],
+            pool_metadata: Nullable::Some(PoolMetadata {
+                url: "https://example.com/pool.json".into(),
+                hash: Hash::new([5; 32]),
+            }),
+        }
+    }
+
+    #[test]
+    fn pool_params_roundtrip_through_certificates() {
+        let params = pool_params();
+
+        let cert = conway::Certificate::try_from(&params).unwrap();
+        assert!(matches!(
+            &cert,
+            conway::Certificate::PoolRegistration { cost: 340_000_000, pool_owners, .. }
+                if pool_owners.len() == 2
+        ));
+
+        let back = PoolParams::try_from(&cert).unwrap();
+        assert_eq!(back.operator, params.operator);
+        assert_eq!(u64::from(back.cost), u64::from(params.cost));
+        assert_eq!(back.pool_owners, params.pool_owners);
+        assert_eq!(back.relays, params.relays);
+        assert_eq!(back.pool_metadata, params.pool_metadata);
+
+        let cert = alonzo::Certificate::try_from(&params).unwrap();
+        let back = PoolParams::try_from(&cert).unwrap();
+        assert_eq!(back.vrf_keyhash, params.vrf_keyhash);
+        assert_eq!(back.margin, params.margin);
+    }
+
+    #[test]
+    fn invalid_hashes_are_rejected() {
+        let mut params = pool_params();
+        params.vrf_keyhash = vec![2; 28].into();
+
+        assert_eq!(
+            conway::Certificate::try_from(&params),
+            Err(ConversionError::InvalidHashLength("vrf key hash", 32, 28))
+        );
+
+        let cert = conway::Certificate::PoolRetirement(Hash::new([1; 28]), 300);
+        assert_eq!(
+            PoolParams::try_from(&cert),
+            Err(ConversionError::NotPoolRegistration)
+        );
+    }
+}
@@ -11,11 +11,18 @@ use pallas_codec::{
     minicbor::{Decode, Encode},
     utils::AnyCbor,
 };
+use serde::{Deserialize, Serialize};
 
 pub mod primitives;
 
 pub use primitives::{PoolMetadata, Relay};
 
+#[cfg(feature = "primitives")]
+mod conversions;
+
+#[cfg(feature = "primitives")]
+pub use conversions::ConversionError;
+
 use crate::miniprotocols::Point;
 
 use super::{Client, ClientError};
@@ -83,6 +90,24 @@ pub enum Value {
     Multiasset(Coin, Multiasset<Coin>),
 }
 
+impl Value {
+    /// The amount of lovelace, regardless of the presence of other assets
+    pub fn coin(&self) -> u64 {
+        match self {
+            Self::Coin(x) => x.into(),
+            Self::Multiasset(x, _) => x.into(),
+        }
+    }
+
+    /// The native assets, if any
+    pub fn assets(&self) -> Option<&Multiasset<Coin>> {
+        match self {
+            Self::Coin(_) => None,
+            Self::Multiasset(_, x) => Some(x),
+        }
+    }
+}
+
 #[derive(Debug, Encode, Decode, PartialEq)]
 pub struct SystemStart {
     #[n(0)]
@@ -119,6 +144,65 @@ pub type ProtocolVersion = (ProtocolVersionMajor, ProtocolVersionMinor);
 
 pub type CostModel = Vec<i64>;
 
+#[cfg(feature = "primitives")]
+pub use pallas_primitives::Language;
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct PoolVotingThresholds {
+    #[n(0)]
+    pub motion_no_confidence: UnitInterval,
+
+    #[n(1)]
+    pub committee_normal: UnitInterval,
+
+    #[n(2)]
+    pub committee_no_confidence: UnitInterval,
+
+    #[n(3)]
+    pub hard_fork_initiation: UnitInterval,
+
+    #[n(4)]
+    pub security_voting_threshold: UnitInterval,
+}
+
+#[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
+pub struct DRepVotingThresholds {
+    #[n(0)]
+    pub motion_no_confidence: UnitInterval,
+
+    #[n(1)]
+    pub committee_normal: UnitInterval,
+
+    #[n(2)]
+    pub committee_no_confidence: UnitInterval,
+
+    #[n(3)]
+    pub update_constitution: UnitInterval,
+
+    #[n(4)]
+    pub hard_fork_initiation: UnitInterval,
+
+    #[n(5)]
+    pub pp_network_group: UnitInterval,
+
+    #[n(6)]
+    pub pp_economic_group: UnitInterval,
+
+    #[n(7)]
+    pub pp_technical_group: UnitInterval,
+
+    #[n(8)]
+    pub pp_governance_group: UnitInterval,
+
+    #[n(9)]
+    pub treasury_withdrawal: UnitInterval,
+}
+
+/// Cost models keyed by the integer id of their language
+///
+/// Entries are encoded as a definite-length map in ascending key order,
+/// skipping missing languages, which is the canonical form used by the
+/// ledger.
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
 #[cbor(map)]
 pub struct CostMdls {
@@ -127,6 +211,20 @@ pub struct CostMdls {
 
     #[n(1)]
     pub plutus_v2: Option<CostModel>,
+
+    #[n(2)]
+    pub plutus_v3: Option<CostModel>,
+}
+
+#[cfg(feature = "primitives")]
+impl CostMdls {
+    pub fn cost_model(&self, lang: Language) -> Option<&CostModel> {
+        match lang {
+            Language::PlutusV1 => self.plutus_v1.as_ref(),
+            Language::PlutusV2 => self.plutus_v2.as_ref(),
+            Language::PlutusV3 => self.plutus_v3.as_ref(),
+        }
+    }
 }
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
@@ -193,6 +291,24 @@ pub struct ProtocolParam {
     pub collateral_percentage: Option<u32>,
     #[n(21)]
     pub max_collateral_inputs: Option<u32>,
+    #[n(22)]
+    pub pool_voting_thresholds: Option<PoolVotingThresholds>,
+    #[n(23)]
+    pub drep_voting_thresholds: Option<DRepVotingThresholds>,
+    #[n(24)]
+    pub min_committee_size: Option<u64>,
+    #[n(25)]
+    pub committee_term_limit: Option<Epoch>,
+    #[n(26)]
+    pub governance_action_validity_period: Option<Epoch>,
+    #[n(27)]
+    pub governance_action_deposit: Option<Coin>,
+    #[n(28)]
+    pub drep_deposit: Option<Coin>,
+    #[n(29)]
+    pub drep_inactivity_period: Option<Epoch>,
+    #[n(30)]
+    pub minfee_refscript_cost_per_byte: Option<UnitInterval>,
 }
 
 #[derive(Debug, Encode, Decode, PartialEq)]
@@ -201,6 +317,53 @@ pub struct StakeDistribution {
     pub pools: KeyValuePairs<Bytes, Pool>,
 }
 
+fn cmp_ratio(a: &RationalNumber, b: &RationalNumber) -> std::cmp::Ordering {
+    let left = a.numerator as u128 * b.denominator as u128;
+    let right = b.numerator as u128 * a.denominator as u128;
+
+    left.cmp(&right)
+}
+
+impl StakeDistribution {
+    pub fn pool(&self, pool_hash: &[u8]) -> Option<&Pool> {
+        self.pools
+            .iter()
+            .find(|(hash, _)| hash.as_slice() == pool_hash)
+            .map(|(_, pool)| pool)
+    }
+
+    /// Fraction of the total active stake delegated to the pool
+    pub fn relative_stake(&self, pool_hash: &[u8]) -> Option<RationalNumber> {
+        self.pool(pool_hash).map(|x| x.stakes.clone())
+    }
+
+    /// Amount of lovelace delegated to the pool, given the total active stake
+    /// of the epoch (see [Snapshots::total_active_stake])
+    ///
+    /// The distribution only carries relative stakes, so the result is
+    /// rounded down.
+    pub fn active_stake(&self, pool_hash: &[u8], total_active_stake: u64) -> Option<u64> {
+        let stakes = &self.pool(pool_hash)?.stakes;
+
+        if stakes.denominator == 0 {
+            return None;
+        }
+
+        let stake =
+            total_active_stake as u128 * stakes.numerator as u128 / stakes.denominator as u128;
+
+        u64::try_from(stake).ok()
+    }
+
+    /// Pools sorted by descending stake
+    pub fn sorted_by_stake(&self) -> Vec<(&Bytes, &Pool)> {
+        let mut pools: Vec<_> = self.pools.iter().map(|(hash, pool)| (hash, pool)).collect();
+        pools.sort_by(|(_, a), (_, b)| cmp_ratio(&b.stakes, &a.stakes));
+
+        pools
+    }
+}
+
 /// The use of `BTreeMap`s as per [Pools] definition ensures that the hashes are
 /// in order (otherwise, the node will reject some queries).
 #[derive(Debug, PartialEq, Clone)]
@@ -286,6 +449,53 @@ impl From<(u8, Bytes)> for StakeAddr {
 }
 
 pub type StakeAddrs = BTreeSet<StakeAddr>;
+
+/// Haskell's `Either`, encoded as a two-element array led by the variant
+/// index
+#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Either<S, T> {
+    Left(S),
+    Right(T),
+}
+
+impl<S, T> Either<S, T> {
+    pub fn left(value: S) -> Self {
+        Self::Left(value)
+    }
+
+    pub fn right(value: T) -> Self {
+        Self::Right(value)
+    }
+
+    pub fn is_left(&self) -> bool {
+        matches!(self, Self::Left(_))
+    }
+
+    pub fn is_right(&self) -> bool {
+        matches!(self, Self::Right(_))
+    }
+
+    pub fn map_left<U>(self, f: impl FnOnce(S) -> U) -> Either<U, T> {
+        match self {
+            Self::Left(x) => Either::Left(f(x)),
+            Self::Right(x) => Either::Right(x),
+        }
+    }
+
+    pub fn map_right<U>(self, f: impl FnOnce(T) -> U) -> Either<S, U> {
+        match self {
+            Self::Left(x) => Either::Left(x),
+            Self::Right(x) => Either::Right(f(x)),
+        }
+    }
+}
+
+/// Stake to compute rewards for, either as a plain amount or as the one
+/// currently delegated by a stake credential
+pub type RewardStake = Either<Coin, StakeAddr>;
+
+/// Expected rewards of delegating each [RewardStake] to each pool
+pub type NonMyopicMemberRewards = KeyValuePairs<RewardStake, KeyValuePairs<Bytes, Coin>>;
 pub type Delegations = KeyValuePairs<StakeAddr, Bytes>;
 pub type RewardAccounts = KeyValuePairs<StakeAddr, u64>;
 
@@ -336,6 +546,22 @@ pub enum TransactionOutput {
     Legacy(LegacyTransactionOutput),
 }
 
+impl TransactionOutput {
+    pub fn address(&self) -> &Bytes {
+        match self {
+            Self::Current(x) => &x.address,
+            Self::Legacy(x) => &x.address,
+        }
+    }
+
+    pub fn amount(&self) -> &Value {
+        match self {
+            Self::Current(x) => &x.amount,
+            Self::Legacy(x) => &x.amount,
+        }
+    }
+}
+
 #[derive(Debug, Encode, Decode, PartialEq, Eq, Clone)]
 #[cbor(map)]
 pub struct PostAlonsoTransactionOutput {
@@ -394,6 +620,14 @@ pub struct Snapshots {
     pub snapshot_stake_go_total: u64,
 }
 
+impl Snapshots {
+    /// Total stake used for leader election in the current epoch, which is
+    /// the one of the `set` snapshot
+    pub fn total_active_stake(&self) -> u64 {
+        self.snapshot_stake_set_total
+    }
+}
+
 #[derive(Debug, Encode, Decode, PartialEq, Clone)]
 pub struct Stakes {
     #[n(0)]
@@ -476,6 +710,20 @@ pub async fn get_chain_block_no(client: &mut Client) -> Result<ChainBlockNumber,
     Ok(result)
 }
 
+/// Get the expected rewards of delegating the given stakes to each pool.
+pub async fn get_non_myopic_member_rewards(
+    client: &mut Client,
+    era: u16,
+    stakes: BTreeSet<RewardStake>,
+) -> Result<NonMyopicMemberRewards, ClientError> {
+    let query = BlockQuery::GetNonMyopicMemberRewards(AnyCbor::from_encode(stakes));
+    let query = LedgerQuery::BlockQuery(era, query);
+    let query = Request::LedgerQuery(query);
+    let (result,): (_,) = client.query(query).await?;
+
+    Ok(result)
+}
+
 /// Get the current protocol parameters.
 pub async fn get_current_pparams(
     client: &mut Client,
@@ -570,6 +818,24 @@ pub async fn get_stake_pool_params(
     Ok(result.0)
 }
 
+/// Get the relays registered by the given pools.
+///
+/// Pools that are not registered are left out of the result.
+pub async fn get_pool_relays(
+    client: &mut Client,
+    era: u16,
+    pool_ids: PoolIds,
+) -> Result<BTreeMap<Bytes, Vec<Relay>>, ClientError> {
+    let params = get_stake_pool_params(client, era, pool_ids).await?;
+
+    let relays = params
+        .into_iter()
+        .map(|(pool, params)| (pool, params.relays))
+        .collect();
+
+    Ok(relays)
+}
+
 /// Get the genesis configuration for the given era.
 pub async fn get_genesis_config(
     client: &mut Client,
@@ -620,3 +886,175 @@ pub async fn get_utxo_whole(client: &mut Client, era: u16) -> Result<UTxOWhole,
 
     Ok(result)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pools_are_ranked_by_relative_stake() {
+        let pool = |hash: u8, numerator, denominator| {
+            (
+                Bytes::from(vec![hash; 28]),
+                Pool {
+                    stakes: RationalNumber {
+                        numerator,
+                        denominator,
+                    },
+                    hashes: vec![hash; 32].into(),
+                },
+            )
+        };
+
+        let distribution = StakeDistribution {
+            pools: KeyValuePairs::from(vec![pool(1, 1, 10), pool(2, 3, 5), pool(3, 3, 10)]),
+        };
+
+        let ranked: Vec<_> = distribution
+            .sorted_by_stake()
+            .into_iter()
+            .map(|(hash, _)| hash[0])
+            .collect();
+
+        assert_eq!(ranked, vec![2, 3, 1]);
+
+        assert_eq!(
+            distribution.relative_stake(&[3; 28]),
+            Some(RationalNumber {
+                numerator: 3,
+                denominator: 10
+            })
+        );
+        assert_eq!(distribution.relative_stake(&[4; 28]), None);
+
+        assert_eq!(
+            distribution.active_stake(&[2; 28], 1_000_000),
+            Some(600_000)
+        );
+        assert_eq!(distribution.active_stake(&[1; 28], 25), Some(2));
+    }
+
+    #[cfg(feature = "primitives")]
+    #[test]
+    fn cost_models_are_canonically_encoded() {
+        // {0: [1, 2], 1: [3], 2: [4, -1]}
+        let cbor = hex::decode("a30082010201810302820420").unwrap();
+        let models: CostMdls = minicbor::decode(&cbor).unwrap();
+
+        assert_eq!(models.cost_model(Language::PlutusV1), Some(&vec![1, 2]));
+        assert_eq!(models.cost_model(Language::PlutusV3), Some(&vec![4, -1]));
+        assert_eq!(minicbor::to_vec(&models).unwrap(), cbor);
+
+        // {2: [2], 0: [1]} is re-encoded in ascending key order
+        let cbor = hex::decode("a202810200810a").unwrap();
+        let models: CostMdls = minicbor::decode(&cbor).unwrap();
+
+        assert_eq!(models.cost_model(Language::PlutusV2), None);
+        assert_eq!(
+            hex::encode(minicbor::to_vec(&models).unwrap()),
+            "a200810a028102"
+        );
+    }
+
+    #[test]
+    fn either_is_encoded_with_variant_index() {
+        let stake = RewardStake::left(AnyUInt::U32(1_000_000));
+        assert!(stake.is_left());
+        assert_eq!(
+            hex::encode(minicbor::to_vec(&stake).unwrap()),
+            "82001a000f4240"
+        );
+
+        let addr = StakeAddr::from((0, Bytes::from(vec![1; 28])));
+        let stake = RewardStake::right(addr.clone());
+        assert!(stake.is_right());
+
+        let cbor = minicbor::to_vec(&stake).unwrap();
+        assert_eq!(cbor[..3], [0x82, 0x01, 0x82]);
+        assert_eq!(minicbor::decode::<RewardStake>(&cbor).unwrap(), stake);
+
+        let mapped = stake.map_right(|x| x.payload.len());
+        assert_eq!(mapped, Either::Right(28));
+
+        let mapped = RewardStake::left(AnyUInt::MajorByte(5)).map_left(|x| x.as_u64() * 2);
+        assert_eq!(mapped, Either::<u64, StakeAddr>::Left(10));
+
+        // amounts sort before credentials, as in the ledger
+        let stakes = BTreeSet::from([
+            RewardStake::right(addr),
+            RewardStake::left(AnyUInt::MajorByte(5)),
+        ]);
+        assert!(stakes.first().unwrap().is_left());
+    }
+
+    #[test]
+    fn conway_params_extend_babbage_layout() {
+        let mut params = ProtocolParam {
+            minfee_a: Some(44),
+            minfee_b: Some(155381),
+            max_block_body_size: Some(90112),
+            max_transaction_size: Some(16384),
+            max_block_header_size: Some(1100),
+            key_deposit: Some(AnyUInt::U32(2000000)),
+            pool_deposit: Some(AnyUInt::U32(500000000)),
+            maximum_epoch: Some(18),
+            desired_number_of_stake_pools: Some(500),
+            pool_pledge_influence: Some(RationalNumber {
+                numerator: 3,
+                denominator: 10,
+            }),
+            expansion_rate: Some(RationalNumber {
+                numerator: 3,
+                denominator: 1000,
+            }),
+            treasury_growth_rate: Some(RationalNumber {
+                numerator: 1,
+                denominator: 5,
+            }),
+            protocol_version: Some((8, 0)),
+            min_pool_cost: Some(AnyUInt::U32(170000000)),
+            ada_per_utxo_byte: Some(AnyUInt::U16(4310)),
+            cost_models_for_script_languages: None,
+            execution_costs: None,
+            max_tx_ex_units: None,
+            max_block_ex_units: None,
+            max_value_size: Some(5000),
+            collateral_percentage: Some(150),
+            max_collateral_inputs: Some(3),
+            pool_voting_thresholds: None,
+            drep_voting_thresholds: None,
+            min_committee_size: None,
+            committee_term_limit: None,
+            governance_action_validity_period: None,
+            governance_action_deposit: None,
+            drep_deposit: None,
+            drep_inactivity_period: None,
+            minfee_refscript_cost_per_byte: None,
+        };
+
+        // babbage params are a 22-element array
+        let cbor = minicbor::to_vec(&params).unwrap();
+        assert_eq!(cbor[0], 0x96);
+        assert_eq!(minicbor::decode::<ProtocolParam>(&cbor).unwrap(), params);
+
+        params.min_committee_size = Some(7);
+        params.drep_deposit = Some(AnyUInt::U32(500000000));
+        params.minfee_refscript_cost_per_byte = Some(RationalNumber {
+            numerator: 15,
+            denominator: 1,
+        });
+
+        // conway params are a 31-element array
+        let cbor = minicbor::to_vec(&params).unwrap();
+        assert_eq!(cbor[..2], [0x98, 31]);
+        assert_eq!(minicbor::decode::<ProtocolParam>(&cbor).unwrap(), params);
+    }
+
+    #[test]
+    fn unknown_block_query_is_a_decode_error() {
+        // [99]
+        let cbor = hex::decode("811863").unwrap();
+
+        assert!(minicbor::decode::<BlockQuery>(&cbor).is_err());
+    }
+}
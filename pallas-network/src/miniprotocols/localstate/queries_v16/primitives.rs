@@ -4,6 +4,9 @@ pub use pallas_codec::utils::{Bytes, Nullable};
 pub use pallas_crypto::hash::Hash;
 
 use pallas_codec::minicbor::{self, Decode, Encode};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use crate::miniprotocols::peersharing::PeerAddress;
 
 #[derive(Encode, Decode, Debug, PartialEq, Eq, Clone)]
 pub struct PoolMetadata {
@@ -89,3 +92,139 @@ impl<C> minicbor::encode::Encode<C> for Relay {
         }
     }
 }
+
+/// Port assumed for relays that don't specify one
+pub const DEFAULT_RELAY_PORT: Port = 3001;
+
+fn invalid_relay(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, msg)
+}
+
+fn relay_port(port: &Nullable<Port>) -> Result<u16, std::io::Error> {
+    let port = port.as_option().copied().unwrap_or(DEFAULT_RELAY_PORT);
+    u16::try_from(port).map_err(|_| invalid_relay("relay port out of range"))
+}
+
+fn peer_address(addr: SocketAddr) -> PeerAddress {
+    match addr {
+        SocketAddr::V4(x) => PeerAddress::V4(*x.ip(), x.port() as Port),
+        SocketAddr::V6(x) => PeerAddress::V6(*x.ip(), x.port() as Port),
+    }
+}
+
+impl Relay {
+    /// Resolves the relay into the addresses of the peers that can be
+    /// connected to
+    ///
+    /// Relays without a port use `DEFAULT_RELAY_PORT`. Host names are resolved
+    /// through the system resolver.
+    ///
+    /// `MultiHostName` relays are meant to be resolved through DNS SRV
+    /// records, which the system resolver doesn't support. As a best-effort,
+    /// the name is resolved as a regular host on the default port, and a
+    /// failed lookup yields no addresses instead of an error.
+    ///
+    /// Note that the ledger serializes IPv6 addresses as four 32-bit words in
+    /// little-endian, which is taken into account here.
+    pub async fn to_peer_ids(&self) -> Result<Vec<PeerAddress>, std::io::Error> {
+        match self {
+            Relay::SingleHostAddr(port, v4, v6) => {
+                let port = relay_port(port)? as Port;
+                let mut peers = vec![];
+
+                if let Some(ip) = v4.as_option() {
+                    let ip: [u8; 4] = ip
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| invalid_relay("invalid IPv4 length"))?;
+
+                    peers.push(PeerAddress::V4(Ipv4Addr::from(ip), port));
+                }
+
+                if let Some(ip) = v6.as_option() {
+                    let mut ip: [u8; 16] = ip
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| invalid_relay("invalid IPv6 length"))?;
+
+                    ip.chunks_mut(4).for_each(|word| word.reverse());
+
+                    peers.push(PeerAddress::V6(Ipv6Addr::from(ip), port));
+                }
+
+                Ok(peers)
+            }
+            Relay::SingleHostName(port, name) => {
+                let port = relay_port(port)?;
+                let addrs = tokio::net::lookup_host((name.as_str(), port)).await?;
+
+                Ok(addrs.map(peer_address).collect())
+            }
+            Relay::MultiHostName(name) => {
+                let port = DEFAULT_RELAY_PORT as u16;
+
+                match tokio::net::lookup_host((name.as_str(), port)).await {
+                    Ok(addrs) => Ok(addrs.map(peer_address).collect()),
+                    Err(_) => Ok(vec![]),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn relay_addresses_are_decoded() {
+        let v6 = hex::decode("b80d0120000000000000000001000000").unwrap();
+
+        let relay = Relay::SingleHostAddr(
+            Nullable::Null,
+            Nullable::Some(vec![10, 0, 0, 1].into()),
+            Nullable::Some(v6.into()),
+        );
+
+        let peers = relay.to_peer_ids().await.unwrap();
+
+        assert_eq!(
+            peers,
+            vec![
+                PeerAddress::V4("10.0.0.1".parse().unwrap(), DEFAULT_RELAY_PORT),
+                PeerAddress::V6("2001:db8::1".parse().unwrap(), DEFAULT_RELAY_PORT),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn relay_host_names_are_resolved() {
+        let relay = Relay::SingleHostName(Nullable::Some(6000), "localhost".into());
+        let peers = relay.to_peer_ids().await.unwrap();
+
+        assert!(!peers.is_empty());
+        assert!(peers.iter().all(|x| match x {
+            PeerAddress::V4(ip, port) => ip.is_loopback() && *port == 6000,
+            PeerAddress::V6(ip, port) => ip.is_loopback() && *port == 6000,
+        }));
+    }
+
+    #[tokio::test]
+    async fn invalid_relays_are_rejected() {
+        let relay = Relay::SingleHostAddr(
+            Nullable::Some(70000),
+            Nullable::Some(vec![10, 0, 0, 1].into()),
+            Nullable::Null,
+        );
+
+        assert!(relay.to_peer_ids().await.is_err());
+
+        let relay = Relay::SingleHostAddr(
+            Nullable::Null,
+            Nullable::Some(vec![10, 0, 1].into()),
+            Nullable::Null,
+        );
+
+        assert!(relay.to_peer_ids().await.is_err());
+    }
+}
@@ -74,7 +74,7 @@ where
         match (&self.0, msg) {
             (State::Idle, Message::RequestTxIds(..)) => Ok(()),
             (State::Idle, Message::RequestTxs(..)) => Ok(()),
-            _ => Err(Error::InvalidInbound),
+            _ => Err(Error::InvalidOutbound),
         }
     }
 
@@ -86,7 +86,7 @@ where
             (State::TxIdsBlocking, Message::Done) => Ok(()),
             (State::TxIdsNonBlocking, Message::ReplyTxIds(..)) => Ok(()),
             (State::Txs, Message::ReplyTxs(..)) => Ok(()),
-            _ => Err(Error::InvalidOutbound),
+            _ => Err(Error::InvalidInbound),
         }
     }
 
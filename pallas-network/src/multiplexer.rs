@@ -1,6 +1,8 @@
 //! A multiplexer of several mini-protocols through a single bearer
 
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 use byteorder::{ByteOrder, NetworkEndian};
 use pallas_codec::{minicbor, Fragment};
@@ -224,6 +226,9 @@ pub enum Error {
     #[error("no data available in bearer to complete segment")]
     EmptyBearer,
 
+    #[error("bearer closed by the remote party")]
+    BearerClosed,
+
     #[error("bearer I/O error")]
     BearerIo(tokio::io::Error),
 
@@ -252,26 +257,41 @@ pub enum Error {
 type EgressChannel = tokio::sync::mpsc::Sender<Payload>;
 type Egress = HashMap<Protocol, EgressChannel>;
 
+/// Set once the demuxer reaches the end of the bearer, so that agents can
+/// tell a remote close apart from any other reason for the demuxer to stop
+type BearerEof = Arc<AtomicBool>;
+
 const EGRESS_MSG_QUEUE_BUFFER: usize = 100;
 
-pub struct Demuxer(BearerReadHalf, Egress);
+pub struct Demuxer(BearerReadHalf, Egress, BearerEof);
 
 impl Demuxer {
     pub fn new(bearer: BearerReadHalf) -> Self {
         let egress = HashMap::new();
-        Self(bearer, egress)
+        Self(bearer, egress, BearerEof::default())
+    }
+
+    async fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), Error> {
+        match self.0.read_exact(buf).await {
+            Ok(_) => Ok(()),
+            Err(err) if err.kind() == tokio::io::ErrorKind::UnexpectedEof => {
+                self.2.store(true, Ordering::Release);
+                Err(Error::BearerClosed)
+            }
+            Err(err) => Err(Error::BearerIo(err)),
+        }
     }
 
     pub async fn read_segment(&mut self) -> Result<(Protocol, Payload), Error> {
         trace!("waiting for segment header");
         let mut buf = vec![0u8; HEADER_LEN];
-        self.0.read_exact(&mut buf).await.map_err(Error::BearerIo)?;
+        self.read_exact(&mut buf).await?;
         let header = Header::from(buf.as_slice());
 
         trace!("waiting for full segment");
         let segment_size = header.payload_len as usize;
         let mut buf = vec![0u8; segment_size];
-        self.0.read_exact(&mut buf).await.map_err(Error::BearerIo)?;
+        self.read_exact(&mut buf).await?;
 
         Ok((header.protocol, buf))
     }
@@ -397,6 +417,7 @@ pub struct AgentChannel {
     protocol: Protocol,
     to_plexer: ToPlexerPort,
     from_plexer: FromPlexerPort,
+    bearer_eof: BearerEof,
 }
 
 impl AgentChannel {
@@ -404,11 +425,13 @@ impl AgentChannel {
         protocol: Protocol,
         to_plexer: ToPlexerPort,
         from_plexer: FromPlexerPort,
+        bearer_eof: BearerEof,
     ) -> Self {
         Self {
             protocol,
             from_plexer,
             to_plexer,
+            bearer_eof,
         }
     }
 
@@ -416,11 +439,13 @@ impl AgentChannel {
         protocol: Protocol,
         to_plexer: ToPlexerPort,
         from_plexer: FromPlexerPort,
+        bearer_eof: BearerEof,
     ) -> Self {
         Self {
             protocol,
             from_plexer,
             to_plexer,
+            bearer_eof,
         }
     }
 
@@ -431,8 +456,18 @@ impl AgentChannel {
             .map_err(|SendError((protocol, payload))| Error::AgentEnqueue(protocol, payload))
     }
 
+    /// Waits for the next chunk sent by the remote party
+    ///
+    /// Once the demuxer stops this fails instead of waiting forever, with
+    /// [Error::BearerClosed] if the bearer reached its end or
+    /// [Error::AgentDequeue] for any other reason (eg: the plexer was aborted
+    /// or failed reading the bearer).
     pub async fn dequeue_chunk(&mut self) -> Result<Payload, Error> {
-        self.from_plexer.recv().await.ok_or(Error::AgentDequeue)
+        match self.from_plexer.recv().await {
+            Some(chunk) => Ok(chunk),
+            None if self.bearer_eof.load(Ordering::Acquire) => Err(Error::BearerClosed),
+            None => Err(Error::AgentDequeue),
+        }
     }
 }
 
@@ -466,13 +501,15 @@ impl Plexer {
     pub fn subscribe_client(&mut self, protocol: Protocol) -> AgentChannel {
         let to_plexer = self.muxer.clone_sender();
         let from_plexer = self.demuxer.subscribe(protocol ^ 0x8000);
-        AgentChannel::for_client(protocol, to_plexer, from_plexer)
+        let bearer_eof = self.demuxer.2.clone();
+        AgentChannel::for_client(protocol, to_plexer, from_plexer, bearer_eof)
     }
 
     pub fn subscribe_server(&mut self, protocol: Protocol) -> AgentChannel {
         let to_plexer = self.muxer.clone_sender();
         let from_plexer = self.demuxer.subscribe(protocol);
-        AgentChannel::for_server(protocol ^ 0x8000, to_plexer, from_plexer)
+        let bearer_eof = self.demuxer.2.clone();
+        AgentChannel::for_server(protocol ^ 0x8000, to_plexer, from_plexer, bearer_eof)
     }
 
     pub fn spawn(self) -> RunningPlexer {
@@ -599,7 +636,7 @@ mod tests {
         let (to_plexer, _) = tokio::sync::mpsc::channel(100);
         let (into_plexer, from_plexer) = tokio::sync::mpsc::channel(100);
 
-        let channel = AgentChannel::for_client(0, to_plexer, from_plexer);
+        let channel = AgentChannel::for_client(0, to_plexer, from_plexer, BearerEof::default());
 
         into_plexer.send(input).await.unwrap();
 
@@ -621,7 +658,7 @@ mod tests {
         let (to_plexer, _) = tokio::sync::mpsc::channel(100);
         let (into_plexer, from_plexer) = tokio::sync::mpsc::channel(100);
 
-        let channel = AgentChannel::for_client(0, to_plexer, from_plexer);
+        let channel = AgentChannel::for_client(0, to_plexer, from_plexer, BearerEof::default());
 
         while !input.is_empty() {
             let chunk = Vec::from(input.drain(0..2).as_slice());
@@ -637,4 +674,49 @@ mod tests {
 
         assert_eq!(msg, out_msg);
     }
+
+    #[tokio::test]
+    async fn closed_bearer_is_reported_to_agents() {
+        let listener = tcp::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let bearer = Bearer::connect_tcp(addr).await.unwrap();
+        // plain socket, so that dropping it closes gracefully instead of
+        // resetting the connection
+        let (remote, _) = listener.accept().await.unwrap();
+
+        let mut plexer = Plexer::new(bearer);
+        let mut channel = plexer.subscribe_client(0);
+        let mut demuxer = plexer.demuxer;
+
+        drop(remote);
+
+        assert!(matches!(demuxer.tick().await, Err(Error::BearerClosed)));
+
+        drop(demuxer);
+
+        assert!(matches!(
+            channel.dequeue_chunk().await,
+            Err(Error::BearerClosed)
+        ));
+    }
+
+    #[tokio::test]
+    async fn aborted_plexer_is_not_reported_as_closed_bearer() {
+        let listener = tcp::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let bearer = Bearer::connect_tcp(addr).await.unwrap();
+        let (_remote, _) = Bearer::accept_tcp(&listener).await.unwrap();
+
+        let mut plexer = Plexer::new(bearer);
+        let mut channel = plexer.subscribe_client(0);
+
+        plexer.spawn().abort().await;
+
+        assert!(matches!(
+            channel.dequeue_chunk().await,
+            Err(Error::AgentDequeue)
+        ));
+    }
 }
@@ -6,12 +6,15 @@ use pallas_network::miniprotocols::localstate::queries_v16::{
     RationalNumber, Relay, Snapshots, StakeAddr, Stakes, SystemStart, UnitInterval, Value,
 };
 use pallas_network::{
-    facades::{NodeClient, PeerClient, PeerServer},
+    facades::{
+        BlockfetchServer, ChainSyncEvent, ChainSyncServer, ChainSyncSource, NodeClient, PeerClient,
+        PeerServer,
+    },
     miniprotocols::{
         blockfetch,
         blockfetch::BlockRequest,
         chainsync::{self, NextResponse},
-        chainsync::{ClientRequest, HeaderContent, Tip},
+        chainsync::{BlockContent, ClientRequest, HeaderContent, Tip},
         handshake,
         handshake::n2n::VersionData,
         localstate,
@@ -272,6 +275,568 @@ pub async fn blockfetch_server_and_client_happy_path() {
     tokio::try_join!(client, server).unwrap();
 }
 
+#[tokio::test]
+pub async fn blockfetch_server_facade_serves_from_provider() {
+    let point1 = Point::Specific(1, vec![0x01]);
+    let point2 = Point::Specific(2, vec![0x02]);
+    let unknown = Point::Specific(3, vec![0x03]);
+
+    let block_bodies = vec![
+        hex::decode("deadbeefdeadbeef").unwrap(),
+        hex::decode("c0ffeec0ffeec0ffee").unwrap(),
+    ];
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30004))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn({
+        let bodies = block_bodies.clone();
+        let known = (point1.clone(), point2.clone());
+
+        async move {
+            let peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+
+            let provider = move |range: (Point, Point)| match range == known {
+                true => bodies.clone(),
+                false => vec![],
+            };
+
+            BlockfetchServer::new(peer_server.blockfetch, provider)
+                .run()
+                .await
+                .unwrap();
+
+            peer_server.plexer.abort().await;
+        }
+    });
+
+    let client = tokio::spawn(async move {
+        let mut client_to_server_conn = PeerClient::connect("localhost:30004", 0).await.unwrap();
+
+        let (version, data) = client_to_server_conn.accepted_version();
+
+        assert_eq!(*version, 13);
+        assert_eq!(data.network_magic(), 0);
+        assert!(data.initiator_only_diffusion_mode());
+        assert_eq!(data.peer_sharing(), Some(false));
+
+        let client_bf = client_to_server_conn.blockfetch();
+
+        let received = client_bf
+            .fetch_range((point1.clone(), point2.clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(received, block_bodies);
+
+        client_bf
+            .send_request_range((unknown.clone(), unknown.clone()))
+            .await
+            .unwrap();
+
+        assert!(client_bf.recv_while_busy().await.unwrap().is_none());
+
+        client_bf.send_done().await.unwrap();
+    });
+
+    tokio::try_join!(client, server).unwrap();
+}
+
+#[tokio::test]
+pub async fn blockfetch_client_pipelines_ranges() {
+    let points: Vec<_> = (1..=4u64)
+        .map(|i| Point::Specific(i, vec![i as u8]))
+        .collect();
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30007))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn(async move {
+        let peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+
+        // each range holds its start and end slots, except for slot 3
+        let provider = |range: (Point, Point)| match range {
+            (Point::Specific(3, _), _) => vec![],
+            (from, to) => vec![
+                vec![from.slot_or_default() as u8],
+                vec![to.slot_or_default() as u8],
+            ],
+        };
+
+        BlockfetchServer::new(peer_server.blockfetch, provider)
+            .run()
+            .await
+            .unwrap();
+
+        peer_server.plexer.abort().await;
+    });
+
+    let client = tokio::spawn(async move {
+        let mut client_to_server_conn = PeerClient::connect("localhost:30007", 0).await.unwrap();
+
+        let client_bf = client_to_server_conn.blockfetch();
+
+        let ranges: Vec<_> = points.iter().map(|p| (p.clone(), p.clone())).collect();
+        let received = client_bf.fetch_ranges(ranges, 2).await.unwrap();
+
+        assert_eq!(
+            received,
+            vec![
+                Some(vec![vec![1], vec![1]]),
+                Some(vec![vec![2], vec![2]]),
+                None,
+                Some(vec![vec![4], vec![4]]),
+            ]
+        );
+
+        assert_eq!(client_bf.in_flight(), 0);
+        assert_eq!(*client_bf.state(), blockfetch::State::Idle);
+
+        client_bf
+            .send_request_range_pipelined((points[0].clone(), points[1].clone()))
+            .await
+            .unwrap();
+        client_bf
+            .send_request_range_pipelined((points[2].clone(), points[3].clone()))
+            .await
+            .unwrap();
+
+        assert_eq!(client_bf.in_flight(), 2);
+        assert!(client_bf.send_done().await.is_err());
+
+        assert_eq!(
+            client_bf.recv_batch().await.unwrap(),
+            Some(vec![vec![1], vec![2]])
+        );
+        assert_eq!(client_bf.recv_batch().await.unwrap(), None);
+
+        client_bf.send_done().await.unwrap();
+    });
+
+    tokio::try_join!(client, server).unwrap();
+}
+
+struct VecHeaderSource {
+    chain: Vec<(Point, Vec<u8>)>,
+    pending: Option<(Point, Vec<u8>)>,
+    cursor: usize,
+}
+
+impl VecHeaderSource {
+    fn tip(&self) -> Tip {
+        let (point, _) = self.chain.last().unwrap();
+        Tip(point.clone(), self.chain.len() as u64)
+    }
+}
+
+impl ChainSyncSource<HeaderContent> for VecHeaderSource {
+    fn find_intersect(&mut self, points: &[Point]) -> (Option<Point>, Tip) {
+        let found = points
+            .iter()
+            .find_map(|p| self.chain.iter().position(|(x, _)| x == p));
+
+        match found {
+            Some(index) => {
+                self.cursor = index + 1;
+                (Some(self.chain[index].0.clone()), self.tip())
+            }
+            None => (None, self.tip()),
+        }
+    }
+
+    fn next(&mut self) -> Option<ChainSyncEvent<HeaderContent>> {
+        let (_, cbor) = self.chain.get(self.cursor)?.clone();
+        self.cursor += 1;
+
+        let header = HeaderContent {
+            variant: 1,
+            byron_prefix: None,
+            cbor,
+        };
+
+        Some(ChainSyncEvent::RollForward(header, self.tip()))
+    }
+
+    fn wait_next(&mut self) -> impl std::future::Future<Output = ChainSyncEvent<HeaderContent>> {
+        // once the pending header is served, the chain stops growing
+        let event = self.pending.take().map(|next| {
+            self.chain.push(next);
+            self.next().unwrap()
+        });
+
+        async move {
+            match event {
+                Some(event) => event,
+                None => std::future::pending().await,
+            }
+        }
+    }
+}
+
+#[tokio::test]
+pub async fn chainsync_server_facade_serves_from_source() {
+    let point1 = Point::Specific(1, vec![0x01]);
+    let point2 = Point::Specific(2, vec![0x02]);
+    let point3 = Point::Specific(3, vec![0x03]);
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30005))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn({
+        let source = VecHeaderSource {
+            chain: vec![
+                (point1.clone(), hex::decode("deadbeef").unwrap()),
+                (point2.clone(), hex::decode("c0ffee").unwrap()),
+            ],
+            pending: Some((point3.clone(), hex::decode("cafe").unwrap())),
+            cursor: 0,
+        };
+
+        async move {
+            let peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+
+            ChainSyncServer::new(peer_server.chainsync, source)
+                .run()
+                .await
+                .unwrap();
+
+            peer_server.plexer.abort().await;
+        }
+    });
+
+    let client = tokio::spawn(async move {
+        let mut client_to_server_conn = PeerClient::connect("localhost:30005", 0).await.unwrap();
+
+        let client_cs = client_to_server_conn.chainsync();
+
+        let (found, tip) = client_cs
+            .find_intersect(vec![Point::Specific(9, vec![0x09]), point1.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(found, Some(point1.clone()));
+        assert_eq!(tip.0, point2.clone());
+
+        match client_cs.request_next().await.unwrap() {
+            NextResponse::RollForward(content, tip) => {
+                assert_eq!(content.cbor, hex::decode("c0ffee").unwrap());
+                assert_eq!(tip.0, point2.clone());
+            }
+            _ => panic!("unexpected response"),
+        }
+
+        client_cs.send_request_next().await.unwrap();
+
+        match client_cs.recv_while_can_await().await.unwrap() {
+            NextResponse::Await => (),
+            _ => panic!("unexpected response"),
+        }
+
+        match client_cs.recv_while_must_reply().await.unwrap() {
+            NextResponse::RollForward(content, tip) => {
+                assert_eq!(content.cbor, hex::decode("cafe").unwrap());
+                assert_eq!(tip.0, point3.clone());
+                assert_eq!(tip.1, 3);
+            }
+            _ => panic!("unexpected response"),
+        }
+
+        client_cs.send_done().await.unwrap();
+    });
+
+    tokio::try_join!(client, server).unwrap();
+}
+
+#[tokio::test]
+pub async fn peer_client_follows_headers_and_fetches_blocks() {
+    let point1 = Point::Specific(1, vec![0x01]);
+    let point2 = Point::Specific(2, vec![0x02]);
+    let point3 = Point::Specific(3, vec![0x03]);
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30009))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn({
+        let source = VecHeaderSource {
+            chain: vec![
+                (point1.clone(), hex::decode("deadbeef").unwrap()),
+                (point2.clone(), hex::decode("c0ffee").unwrap()),
+                (point3.clone(), hex::decode("cafe").unwrap()),
+            ],
+            pending: None,
+            cursor: 0,
+        };
+
+        // only the body of the second block is available
+        let known = (point2.clone(), point2.clone());
+        let provider = move |range: (Point, Point)| match range == known {
+            true => vec![hex::decode("b0d1").unwrap()],
+            false => vec![],
+        };
+
+        async move {
+            let peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+
+            let chainsync = ChainSyncServer::new(peer_server.chainsync, source).spawn();
+            let blockfetch = BlockfetchServer::new(peer_server.blockfetch, provider).spawn();
+
+            chainsync.await.unwrap().unwrap();
+            blockfetch.await.unwrap().unwrap();
+
+            peer_server.plexer.abort().await;
+        }
+    });
+
+    let client = tokio::spawn(async move {
+        let mut client_to_server_conn = PeerClient::connect("localhost:30009", 0).await.unwrap();
+
+        let mut headers = client_to_server_conn
+            .follow_headers(vec![point1.clone()])
+            .await
+            .unwrap();
+
+        assert_eq!(headers.intersection(), &point1);
+
+        match headers.next_header().await.unwrap() {
+            NextResponse::RollForward(content, _) => {
+                assert_eq!(content.cbor, hex::decode("c0ffee").unwrap());
+            }
+            _ => panic!("unexpected response"),
+        }
+
+        let body = headers.fetch_block(point2.clone()).await.unwrap();
+        assert_eq!(body, hex::decode("b0d1").unwrap());
+
+        match headers.next_header().await.unwrap() {
+            NextResponse::RollForward(content, _) => {
+                assert_eq!(content.cbor, hex::decode("cafe").unwrap());
+            }
+            _ => panic!("unexpected response"),
+        }
+
+        assert!(matches!(
+            headers.fetch_block(point3.clone()).await,
+            Err(blockfetch::ClientError::NoBlocks)
+        ));
+
+        client_to_server_conn.chainsync().send_done().await.unwrap();
+        client_to_server_conn
+            .blockfetch()
+            .send_done()
+            .await
+            .unwrap();
+    });
+
+    tokio::try_join!(client, server).unwrap();
+}
+
+#[tokio::test]
+pub async fn chainsync_client_pipelines_requests() {
+    let chain: Vec<_> = (1..=5u64)
+        .map(|i| (Point::Specific(i, vec![i as u8]), vec![i as u8; 4]))
+        .collect();
+
+    let point1 = chain[0].0.clone();
+    let point6 = Point::Specific(6, vec![0x06]);
+
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30006))
+        .await
+        .unwrap();
+
+    let server = tokio::spawn({
+        let source = VecHeaderSource {
+            chain,
+            pending: Some((point6.clone(), vec![0x06; 4])),
+            cursor: 0,
+        };
+
+        async move {
+            let peer_server = PeerServer::accept(&listener, 0).await.unwrap();
+
+            ChainSyncServer::new(peer_server.chainsync, source)
+                .run()
+                .await
+                .unwrap();
+        }
+    });
+
+    let mut client = PeerClient::connect("localhost:30006", 0).await.unwrap();
+    let client_cs = client.chainsync();
+
+    let (found, _) = client_cs.find_intersect(vec![point1]).await.unwrap();
+    assert!(found.is_some());
+
+    for i in 2..=5u8 {
+        match client_cs.request_next_pipelined(3).await.unwrap() {
+            NextResponse::RollForward(content, _) => assert_eq!(content.cbor, vec![i; 4]),
+            x => panic!("unexpected response {x:?}"),
+        }
+
+        assert_eq!(client_cs.in_flight(), 2);
+        assert_eq!(*client_cs.state(), chainsync::State::CanAwait);
+    }
+
+    // the pipeline is topped up once more before the server reaches the tip
+    match client_cs.request_next_pipelined(3).await.unwrap() {
+        NextResponse::Await => (),
+        x => panic!("unexpected response {x:?}"),
+    }
+
+    assert_eq!(*client_cs.state(), chainsync::State::MustReply);
+    assert_eq!(client_cs.in_flight(), 3);
+
+    // no requests are pipelined while waiting at the tip
+    assert!(matches!(
+        client_cs.send_request_next_pipelined().await,
+        Err(chainsync::ClientError::InvalidOutbound)
+    ));
+
+    match client_cs.request_next_pipelined(3).await.unwrap() {
+        NextResponse::RollForward(content, tip) => {
+            assert_eq!(content.cbor, vec![0x06; 4]);
+            assert_eq!(tip.0, point6);
+        }
+        x => panic!("unexpected response {x:?}"),
+    }
+
+    assert_eq!(client_cs.in_flight(), 2);
+    assert!(matches!(
+        client_cs.send_done().await,
+        Err(chainsync::ClientError::AgencyIsTheirs)
+    ));
+
+    server.abort();
+    client.abort().await;
+}
+
+#[cfg(unix)]
+struct VecBlockSource {
+    chain: Vec<(Point, Vec<u8>)>,
+    rollback: Option<Point>,
+    cursor: usize,
+}
+
+#[cfg(unix)]
+impl VecBlockSource {
+    fn tip(&self) -> Tip {
+        let (point, _) = self.chain.last().unwrap();
+        Tip(point.clone(), self.chain.len() as u64)
+    }
+}
+
+#[cfg(unix)]
+impl ChainSyncSource<BlockContent> for VecBlockSource {
+    fn find_intersect(&mut self, points: &[Point]) -> (Option<Point>, Tip) {
+        let found = points
+            .iter()
+            .find_map(|p| self.chain.iter().position(|(x, _)| x == p));
+
+        match found {
+            Some(index) => {
+                let point = self.chain[index].0.clone();
+                self.cursor = index + 1;
+                self.rollback = Some(point.clone());
+                (Some(point), self.tip())
+            }
+            None => (None, self.tip()),
+        }
+    }
+
+    fn next(&mut self) -> Option<ChainSyncEvent<BlockContent>> {
+        // nodes roll back to the intersection before sending blocks
+        if let Some(point) = self.rollback.take() {
+            return Some(ChainSyncEvent::RollBackward(point, self.tip()));
+        }
+
+        let (_, cbor) = self.chain.get(self.cursor)?.clone();
+        self.cursor += 1;
+
+        Some(ChainSyncEvent::RollForward(BlockContent(cbor), self.tip()))
+    }
+
+    fn wait_next(&mut self) -> impl std::future::Future<Output = ChainSyncEvent<BlockContent>> {
+        std::future::pending()
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+pub async fn node_client_streams_blocks() {
+    let point1 = Point::Specific(1, vec![0x01]);
+    let point2 = Point::Specific(2, vec![0x02]);
+
+    let socket_path = Path::new("node3.socket");
+
+    if socket_path.exists() {
+        fs::remove_file(socket_path).unwrap();
+    }
+
+    let listener = UnixListener::bind(socket_path).unwrap();
+
+    let server = tokio::spawn({
+        let source = VecBlockSource {
+            chain: vec![
+                (point1.clone(), hex::decode("deadbeef").unwrap()),
+                (point2.clone(), hex::decode("c0ffee").unwrap()),
+            ],
+            rollback: None,
+            cursor: 0,
+        };
+
+        async move {
+            let server = pallas_network::facades::NodeServer::accept(&listener, 0)
+                .await
+                .unwrap();
+
+            let pallas_network::facades::NodeServer {
+                plexer, chainsync, ..
+            } = server;
+
+            ChainSyncServer::new(chainsync, source).run().await.unwrap();
+
+            plexer.abort().await;
+        }
+    });
+
+    let client = tokio::spawn(async move {
+        let mut client = NodeClient::connect(socket_path, 0).await.unwrap();
+
+        let missing = client
+            .follow_blocks(vec![Point::Specific(9, vec![0x09])])
+            .await;
+
+        assert!(matches!(
+            missing,
+            Err(chainsync::ClientError::IntersectionNotFound)
+        ));
+
+        let mut blocks = client.follow_blocks(vec![point1.clone()]).await.unwrap();
+
+        assert_eq!(blocks.intersection(), &point1);
+
+        match blocks.next_block().await.unwrap() {
+            NextResponse::RollBackward(point, _) => assert_eq!(point, point1),
+            x => panic!("unexpected response {x:?}"),
+        }
+
+        match blocks.next_block().await.unwrap() {
+            NextResponse::RollForward(block, tip) => {
+                assert_eq!(block.0, hex::decode("c0ffee").unwrap());
+                assert_eq!(tip.0, point2);
+            }
+            x => panic!("unexpected response {x:?}"),
+        }
+
+        client.chainsync().send_done().await.unwrap();
+    });
+
+    tokio::try_join!(client, server).unwrap();
+}
+
 #[tokio::test]
 #[ignore]
 pub async fn chainsync_server_and_client_happy_path_n2n() {
@@ -769,6 +1334,15 @@ pub async fn local_state_query_server_and_client_happy_path() {
                 max_value_size: None,
                 collateral_percentage: None,
                 max_collateral_inputs: None,
+                pool_voting_thresholds: None,
+                drep_voting_thresholds: None,
+                min_committee_size: None,
+                committee_term_limit: None,
+                governance_action_validity_period: None,
+                governance_action_deposit: None,
+                drep_deposit: None,
+                drep_inactivity_period: None,
+                minfee_refscript_cost_per_byte: None,
             }]);
 
             server.statequery().send_result(result).await.unwrap();
@@ -1151,6 +1725,15 @@ pub async fn local_state_query_server_and_client_happy_path() {
                 max_value_size: None,
                 collateral_percentage: None,
                 max_collateral_inputs: None,
+                pool_voting_thresholds: None,
+                drep_voting_thresholds: None,
+                min_committee_size: None,
+                committee_term_limit: None,
+                governance_action_validity_period: None,
+                governance_action_deposit: None,
+                drep_deposit: None,
+                drep_inactivity_period: None,
+                minfee_refscript_cost_per_byte: None,
             }]
         );
 
@@ -1790,3 +2373,73 @@ pub async fn peer_sharing_server_and_client_happy_path() {
 
     tokio::try_join!(client, server).unwrap();
 }
+
+#[tokio::test]
+pub async fn clients_reject_out_of_order_calls() {
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 30008))
+        .await
+        .unwrap();
+
+    // the server side only needs to keep the connection open, agency is
+    // checked locally before any message goes through the bearer
+    let (server, client) = tokio::join!(
+        Bearer::accept_tcp(&listener),
+        Bearer::connect_tcp("localhost:30008")
+    );
+
+    let (_server, _) = server.unwrap();
+
+    let mut plexer = Plexer::new(client.unwrap());
+
+    let mut cs = chainsync::N2NClient::new(plexer.subscribe_client(2));
+    let mut bf = blockfetch::Client::new(plexer.subscribe_client(3));
+    let mut ls = localstate::Client::new(plexer.subscribe_client(7));
+
+    let plexer = plexer.spawn();
+
+    // receiving while the agency is ours
+
+    assert!(matches!(
+        cs.recv_while_can_await().await,
+        Err(chainsync::ClientError::AgencyIsOurs)
+    ));
+
+    assert!(matches!(
+        bf.recv_while_busy().await,
+        Err(blockfetch::ClientError::AgencyIsOurs)
+    ));
+
+    assert!(matches!(
+        ls.recv_while_acquiring().await,
+        Err(localstate::ClientError::AgencyIsOurs)
+    ));
+
+    // sending while the agency is theirs
+
+    cs.send_request_next().await.unwrap();
+
+    assert!(matches!(
+        cs.send_request_next().await,
+        Err(chainsync::ClientError::AgencyIsTheirs)
+    ));
+
+    let point = Point::Specific(1, vec![0x01]);
+
+    bf.send_request_range((point.clone(), point.clone()))
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        bf.send_request_range((point.clone(), point.clone())).await,
+        Err(blockfetch::ClientError::AgencyIsTheirs)
+    ));
+
+    ls.send_acquire(None).await.unwrap();
+
+    assert!(matches!(
+        ls.send_acquire(None).await,
+        Err(localstate::ClientError::AgencyIsTheirs)
+    ));
+
+    plexer.abort().await;
+}
@@ -427,6 +427,8 @@ impl<C> minicbor::encode::Encode<C> for Certificate {
     }
 }
 
+/// Plutus languages known to the Alonzo era, see [crate::Language] for the
+/// superset across eras
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 #[cbor(index_only)]
 pub enum Language {
@@ -434,6 +436,14 @@ pub enum Language {
     PlutusV1,
 }
 
+impl From<Language> for crate::Language {
+    fn from(value: Language) -> Self {
+        match value {
+            Language::PlutusV1 => crate::Language::PlutusV1,
+        }
+    }
+}
+
 #[deprecated(since = "0.31.0", note = "use `CostModels` instead")]
 pub type CostMdls = CostModels;
 
@@ -6,7 +6,7 @@ use serde::{Deserialize, Serialize};
 
 use pallas_codec::{
     minicbor::{self, Decode, Encode},
-    utils::{Bytes, CborWrap, KeepRaw, KeyValuePairs, MaybeIndefArray, Nullable},
+    utils::{Bytes, CborWrap, KeepRaw, KeyValuePairs, MaybeIndefArray, Nullable, OnlyRaw},
 };
 use pallas_crypto::hash::{Hash, Hasher};
 
@@ -121,6 +121,8 @@ pub use crate::alonzo::RequiredSigners;
 
 pub use crate::alonzo::Certificate;
 
+/// Plutus languages known to the Babbage era, see [crate::Language] for the
+/// superset across eras
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
 #[cbor(index_only)]
 pub enum Language {
@@ -131,6 +133,15 @@ pub enum Language {
     PlutusV2,
 }
 
+impl From<Language> for crate::Language {
+    fn from(value: Language) -> Self {
+        match value {
+            Language::PlutusV1 => crate::Language::PlutusV1,
+            Language::PlutusV2 => crate::Language::PlutusV2,
+        }
+    }
+}
+
 #[deprecated(since = "0.31.0", note = "use `CostModels` instead")]
 pub type CostMdls = CostModels;
 
@@ -297,13 +308,12 @@ pub fn derive_tagged_vrf_output(
     block_vrf_output_bytes: &[u8],
     derivation: VrfDerivation,
 ) -> Vec<u8> {
-    let mut tagged_vrf: Vec<u8> = match derivation {
-        VrfDerivation::Leader => vec![0x4C_u8], /* "L" */
-        VrfDerivation::Nonce => vec![0x4E_u8],  /* "N" */
+    let tag = match derivation {
+        VrfDerivation::Leader => 0x4C_u8, /* "L" */
+        VrfDerivation::Nonce => 0x4E_u8,  /* "N" */
     };
 
-    tagged_vrf.extend(block_vrf_output_bytes);
-    Hasher::<256>::hash(&tagged_vrf).to_vec()
+    Hasher::<256>::hash_tagged(block_vrf_output_bytes, tag).to_vec()
 }
 
 impl HeaderBody {
@@ -659,6 +669,18 @@ pub type MintedBlock<'b> = PseudoBlock<
     KeepRaw<'b, AuxiliaryData>,
 >;
 
+/// A minted block that skips decoding of its witness sets
+///
+/// Same as [MintedBlock], but witness sets are kept as raw CBOR bytes. Useful
+/// for consumers that don't need witnesses (eg: output indexers), since
+/// witnesses account for a large share of the decoding work.
+pub type MintedBlockRawWitnesses<'b> = PseudoBlock<
+    KeepRaw<'b, MintedHeader<'b>>,
+    KeepRaw<'b, MintedTransactionBody<'b>>,
+    OnlyRaw<'b, MintedWitnessSet<'b>>,
+    KeepRaw<'b, AuxiliaryData>,
+>;
+
 impl<'b> From<MintedBlock<'b>> for Block {
     fn from(x: MintedBlock<'b>) -> Self {
         Block {
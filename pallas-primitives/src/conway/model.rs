@@ -5,7 +5,7 @@
 use serde::{Deserialize, Serialize};
 
 use pallas_codec::minicbor::{self, Decode, Encode};
-use pallas_codec::utils::CborWrap;
+use pallas_codec::utils::{CborWrap, OnlyRaw};
 
 pub use crate::{
     plutus_data::*, AddrKeyhash, AssetName, Bytes, Coin, CostModel, DnsName, Epoch, ExUnits,
@@ -443,18 +443,7 @@ pub type CommitteeColdCredential = StakeCredential;
 
 pub type CommitteeHotCredential = StakeCredential;
 
-#[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
-#[cbor(index_only)]
-pub enum Language {
-    #[n(0)]
-    PlutusV1,
-
-    #[n(1)]
-    PlutusV2,
-
-    #[n(2)]
-    PlutusV3,
-}
+pub use crate::Language;
 
 #[deprecated(since = "0.31.0", note = "use `CostModels` instead")]
 pub type CostMdls = CostModels;
@@ -1547,6 +1536,18 @@ pub type MintedBlock<'b> = PseudoBlock<
     KeepRaw<'b, AuxiliaryData>,
 >;
 
+/// A minted block that skips decoding of its witness sets
+///
+/// Same as [MintedBlock], but witness sets are kept as raw CBOR bytes. Useful
+/// for consumers that don't need witnesses (eg: output indexers), since
+/// witnesses account for a large share of the decoding work.
+pub type MintedBlockRawWitnesses<'b> = PseudoBlock<
+    KeepRaw<'b, MintedHeader<'b>>,
+    KeepRaw<'b, MintedTransactionBody<'b>>,
+    OnlyRaw<'b, MintedWitnessSet<'b>>,
+    KeepRaw<'b, AuxiliaryData>,
+>;
+
 impl<'b> From<MintedBlock<'b>> for Block {
     fn from(x: MintedBlock<'b>) -> Self {
         Block {
@@ -1620,7 +1621,7 @@ impl<'b> From<MintedTx<'b>> for Tx {
 
 #[cfg(test)]
 mod tests {
-    use pallas_codec::minicbor;
+    use pallas_codec::{minicbor, utils::Set};
 
     use super::MintedBlock;
 
@@ -1651,6 +1652,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn set_tag_is_optional_but_canonical() {
+        let keyhash = "00".repeat(28);
+
+        let tagged = hex::decode(format!("d9010281581c{keyhash}")).unwrap();
+        let untagged = hex::decode(format!("81581c{keyhash}")).unwrap();
+
+        for bytes in [&tagged, &untagged] {
+            let signers: super::RequiredSigners = minicbor::decode(bytes).unwrap();
+            assert_eq!(signers.len(), 1);
+            assert_eq!(minicbor::to_vec(&signers).unwrap(), tagged);
+        }
+
+        let input = format!("825820{}00", "11".repeat(32));
+
+        let tagged = hex::decode(format!("d9010281{input}")).unwrap();
+        let untagged = hex::decode(format!("81{input}")).unwrap();
+
+        for bytes in [&tagged, &untagged] {
+            let inputs: Set<super::TransactionInput> = minicbor::decode(bytes).unwrap();
+            assert_eq!(inputs.len(), 1);
+            assert_eq!(minicbor::to_vec(&inputs).unwrap(), tagged);
+        }
+
+        // tag 259 (maps with non-string keys) is not a valid set tag
+        let wrong = hex::decode(format!("d9010381{input}")).unwrap();
+        assert!(minicbor::decode::<Set<super::TransactionInput>>(&wrong).is_err());
+        assert!(minicbor::decode::<super::RequiredSigners>(&wrong).is_err());
+    }
+
     // #[test]
     // fn fragments_decoding() {
     //     // peculiar array of outputs used in an hydra transaction
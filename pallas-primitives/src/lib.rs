@@ -72,6 +72,165 @@ pub enum Metadatum {
     Map(KeyValuePairs<Metadatum, Metadatum>),
 }
 
+/// Bounds enforced by [Metadatum::decode_with_limits]
+///
+/// Metadata is arbitrary user data, nesting or collection lengths crafted to
+/// be pathological could overflow the stack or trigger huge allocations.
+/// The ledger doesn't bound metadata in any of these ways, so the regular
+/// `Decode` implementation stays unbounded to accept anything valid on chain;
+/// these limits are an opt-in for callers decoding untrusted input. Regardless
+/// of these limits, a collection can't declare more items than there are bytes
+/// left in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetadatumLimits {
+    /// Maximum nesting of arrays and maps, a top-level collection has depth 1
+    pub max_depth: usize,
+
+    /// Maximum number of items of a single array or map
+    pub max_collection_size: usize,
+}
+
+impl Default for MetadatumLimits {
+    /// Limits that are well beyond anything seen in real-world metadata
+    fn default() -> Self {
+        Self {
+            max_depth: 256,
+            max_collection_size: usize::MAX,
+        }
+    }
+}
+
+impl MetadatumLimits {
+    /// Checks the length declared by a definite collection
+    fn check_len(&self, d: &minicbor::Decoder, len: u64) -> Result<(), minicbor::decode::Error> {
+        let remaining = (d.input().len() - d.position()) as u64;
+
+        if len > remaining {
+            return Err(minicbor::decode::Error::message(
+                "metadatum collection is longer than the input",
+            ));
+        }
+
+        self.check_count(len)
+    }
+
+    /// Checks the number of items of a collection decoded so far
+    fn check_count(&self, count: u64) -> Result<(), minicbor::decode::Error> {
+        if count > self.max_collection_size as u64 {
+            return Err(minicbor::decode::Error::message(
+                "metadatum collection exceeds the maximum size",
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl Metadatum {
+    /// Decodes a metadatum enforcing custom limits on its nesting and the size
+    /// of its collections
+    pub fn decode_with_limits(
+        d: &mut minicbor::Decoder,
+        limits: &MetadatumLimits,
+    ) -> Result<Self, minicbor::decode::Error> {
+        Self::decode_limited(d, limits, 0)
+    }
+
+    fn decode_limited(
+        d: &mut minicbor::Decoder,
+        limits: &MetadatumLimits,
+        depth: usize,
+    ) -> Result<Self, minicbor::decode::Error> {
+        match d.datatype()? {
+            minicbor::data::Type::U8
+            | minicbor::data::Type::U16
+            | minicbor::data::Type::U32
+            | minicbor::data::Type::U64
+            | minicbor::data::Type::I8
+            | minicbor::data::Type::I16
+            | minicbor::data::Type::I32
+            | minicbor::data::Type::I64
+            | minicbor::data::Type::Int => {
+                let i = d.decode()?;
+                Ok(Metadatum::Int(i))
+            }
+            minicbor::data::Type::Bytes => Ok(Metadatum::Bytes(d.decode()?)),
+            minicbor::data::Type::String => Ok(Metadatum::Text(d.decode()?)),
+            minicbor::data::Type::Array | minicbor::data::Type::ArrayIndef => {
+                Self::check_depth(limits, depth)?;
+
+                let mut items = vec![];
+                let len = d.array()?;
+
+                if let Some(len) = len {
+                    limits.check_len(d, len)?;
+                }
+
+                while Self::has_next(d, len, items.len())? {
+                    limits.check_count(items.len() as u64 + 1)?;
+                    items.push(Self::decode_limited(d, limits, depth + 1)?);
+                }
+
+                Ok(Metadatum::Array(items))
+            }
+            minicbor::data::Type::Map | minicbor::data::Type::MapIndef => {
+                Self::check_depth(limits, depth)?;
+
+                let mut items = vec![];
+                let len = d.map()?;
+
+                if let Some(len) = len {
+                    limits.check_len(d, len)?;
+                }
+
+                while Self::has_next(d, len, items.len())? {
+                    limits.check_count(items.len() as u64 + 1)?;
+                    let key = Self::decode_limited(d, limits, depth + 1)?;
+                    let value = Self::decode_limited(d, limits, depth + 1)?;
+                    items.push((key, value));
+                }
+
+                match len {
+                    Some(_) => Ok(Metadatum::Map(KeyValuePairs::Def(items))),
+                    None => Ok(Metadatum::Map(KeyValuePairs::Indef(items))),
+                }
+            }
+            _ => Err(minicbor::decode::Error::message(
+                "Can't turn data type into metadatum",
+            )),
+        }
+    }
+
+    fn check_depth(limits: &MetadatumLimits, depth: usize) -> Result<(), minicbor::decode::Error> {
+        if depth >= limits.max_depth {
+            return Err(minicbor::decode::Error::message(
+                "metadatum exceeds the maximum nesting depth",
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Whether a collection has more items, consuming the break of indefinite
+    /// collections once reached
+    fn has_next(
+        d: &mut minicbor::Decoder,
+        len: Option<u64>,
+        decoded: usize,
+    ) -> Result<bool, minicbor::decode::Error> {
+        match len {
+            Some(len) => Ok((decoded as u64) < len),
+            None => match d.datatype()? {
+                minicbor::data::Type::Break => {
+                    d.set_position(d.position() + 1);
+                    Ok(false)
+                }
+                _ => Ok(true),
+            },
+        }
+    }
+}
+
 impl<'b, C> minicbor::Decode<'b, C> for Metadatum {
     fn decode(d: &mut minicbor::Decoder<'b>, ctx: &mut C) -> Result<Self, minicbor::decode::Error> {
         match d.datatype()? {
@@ -183,10 +342,114 @@ pub enum NonceVariant {
     Nonce,
 }
 
+/// Version of the Plutus language of a script
+///
+/// Encoded on the wire (cost models, language views) by its integer id,
+/// which is one less than the tag prepended to the script bytes when hashing
+/// them. Covers the languages of every era, the Alonzo and Babbage models
+/// keep their own narrower enums so that their decoders reject languages
+/// introduced later.
+#[derive(
+    Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy,
+)]
+#[cbor(index_only)]
+pub enum Language {
+    #[n(0)]
+    PlutusV1,
+
+    #[n(1)]
+    PlutusV2,
+
+    #[n(2)]
+    PlutusV3,
+}
+
+impl Language {
+    /// Tag prepended to the script bytes to compute the script hash
+    pub fn script_tag(&self) -> u8 {
+        u8::from(*self) + 1
+    }
+
+    pub fn from_script_tag(tag: u8) -> Option<Self> {
+        tag.checked_sub(1).and_then(|x| Self::try_from(x).ok())
+    }
+}
+
+impl From<Language> for u8 {
+    fn from(value: Language) -> Self {
+        match value {
+            Language::PlutusV1 => 0,
+            Language::PlutusV2 => 1,
+            Language::PlutusV3 => 2,
+        }
+    }
+}
+
+/// Integer id that doesn't match any known [Language]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownLanguage(pub u8);
+
+impl std::fmt::Display for UnknownLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown plutus language id {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownLanguage {}
+
+impl TryFrom<u8> for Language {
+    type Error = UnknownLanguage;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Language::PlutusV1),
+            1 => Ok(Language::PlutusV2),
+            2 => Ok(Language::PlutusV3),
+            x => Err(UnknownLanguage(x)),
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Encode, Decode, Debug, PartialEq, Eq, Clone)]
 #[cbor(transparent)]
 pub struct PlutusScript<const VERSION: usize>(#[n(0)] pub Bytes);
 
+impl<const VERSION: usize> PlutusScript<VERSION> {
+    /// Language matching the version parameter, using this with a version
+    /// other than 1, 2 or 3 fails to compile
+    pub const LANGUAGE: Language = match VERSION {
+        1 => Language::PlutusV1,
+        2 => Language::PlutusV2,
+        3 => Language::PlutusV3,
+        _ => panic!("unknown plutus script version"),
+    };
+
+    pub fn language(&self) -> Language {
+        Self::LANGUAGE
+    }
+
+    /// Decodes a script wrapped in an extra CBOR bytestring, as found in the
+    /// `cborHex` of text envelope files
+    pub fn from_double_cbor(cbor: &[u8]) -> Result<Self, minicbor::decode::Error> {
+        let script = Self(minicbor::decode::<Bytes>(cbor)?);
+
+        // the inner bytes must also be a bytestring, wrapping the program
+        script.flat_program()?;
+
+        Ok(script)
+    }
+
+    /// Encodes the script wrapped in an extra CBOR bytestring
+    pub fn to_double_cbor(&self) -> Vec<u8> {
+        minicbor::to_vec(&self.0).unwrap()
+    }
+
+    /// The flat-encoded program, without the CBOR bytestring wrapping it
+    pub fn flat_program(&self) -> Result<&[u8], minicbor::decode::Error> {
+        minicbor::Decoder::new(self.0.as_slice()).bytes()
+    }
+}
+
 impl<const VERSION: usize> AsRef<[u8]> for PlutusScript<VERSION> {
     fn as_ref(&self) -> &[u8] {
         self.0.as_slice()
@@ -371,6 +634,92 @@ impl<C> minicbor::encode::Encode<C> for StakeCredential {
     }
 }
 
+/// Whether a credential is controlled by a key or by a script
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum CredentialKind {
+    Key,
+    Script,
+}
+
+/// The governance role of a credential, as distinguished by CIP-129
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum GovernanceRole {
+    CommitteeHot,
+    CommitteeCold,
+    DRep,
+}
+
+impl GovernanceRole {
+    fn cip129_hrp(&self) -> &'static str {
+        match self {
+            GovernanceRole::CommitteeHot => "cc_hot",
+            GovernanceRole::CommitteeCold => "cc_cold",
+            GovernanceRole::DRep => "drep",
+        }
+    }
+
+    fn cip129_header(&self) -> u8 {
+        match self {
+            GovernanceRole::CommitteeHot => 0b0000_0000,
+            GovernanceRole::CommitteeCold => 0b0001_0000,
+            GovernanceRole::DRep => 0b0010_0000,
+        }
+    }
+}
+
+impl StakeCredential {
+    pub fn kind(&self) -> CredentialKind {
+        match self {
+            StakeCredential::AddrKeyhash(_) => CredentialKind::Key,
+            StakeCredential::ScriptHash(_) => CredentialKind::Script,
+        }
+    }
+
+    pub fn as_hash(&self) -> &Hash<28> {
+        match self {
+            StakeCredential::AddrKeyhash(x) => x,
+            StakeCredential::ScriptHash(x) => x,
+        }
+    }
+
+    /// Encode the credential hash as bech32, using the CIP-5 prefix for its
+    /// kind (`stake_vkh` or `script`)
+    pub fn to_bech32(&self) -> Result<String, bech32::Error> {
+        let hrp = match self.kind() {
+            CredentialKind::Key => "stake_vkh",
+            CredentialKind::Script => "script",
+        };
+
+        let base32 = bech32::ToBase32::to_base32(&self.as_hash().as_ref());
+        bech32::encode(hrp, base32, bech32::Variant::Bech32)
+    }
+
+    /// Encode the credential as the CIP-129 identifier of a governance role
+    ///
+    /// The payload is the credential hash prefixed by a header byte that
+    /// carries both the role and the kind of the credential.
+    pub fn to_cip129_bech32(&self, role: GovernanceRole) -> Result<String, bech32::Error> {
+        let kind = match self.kind() {
+            CredentialKind::Key => 0b0000_0010,
+            CredentialKind::Script => 0b0000_0011,
+        };
+
+        let payload = [&[role.cip129_header() | kind], self.as_hash().as_ref()].concat();
+
+        let base32 = bech32::ToBase32::to_base32(&payload);
+        bech32::encode(role.cip129_hrp(), base32, bech32::Variant::Bech32)
+    }
+}
+
+impl std::fmt::Display for StakeCredential {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.kind() {
+            CredentialKind::Key => write!(f, "key:{}", self.as_hash()),
+            CredentialKind::Script => write!(f, "script:{}", self.as_hash()),
+        }
+    }
+}
+
 pub type TransactionIndex = u32;
 
 #[derive(
@@ -400,3 +749,159 @@ pub type UnitInterval = RationalNumber;
 pub struct VrfCert(#[n(0)] pub Bytes, #[n(1)] pub Bytes);
 
 pub type VrfKeyhash = Hash<32>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plutus_script_double_cbor_roundtrip() {
+        let bytes = hex::decode(include_str!("../../test_data/v2script.plutus")).unwrap();
+        let script = PlutusScript::<2>(bytes.clone().into());
+
+        // flat programs start with the plutus core version
+        assert_eq!(script.flat_program().unwrap()[..3], [1, 0, 0]);
+
+        let double = script.to_double_cbor();
+        assert_eq!(
+            PlutusScript::<2>::from_double_cbor(&double).unwrap(),
+            script
+        );
+
+        // a single-wrapped script doesn't hold a bytestring inside
+        assert!(PlutusScript::<2>::from_double_cbor(&bytes).is_err());
+    }
+
+    #[test]
+    fn languages_map_to_wire_ids_and_script_tags() {
+        let all = [Language::PlutusV1, Language::PlutusV2, Language::PlutusV3];
+
+        for (id, lang) in all.into_iter().enumerate() {
+            assert_eq!(u8::from(lang), id as u8);
+            assert_eq!(Language::try_from(id as u8), Ok(lang));
+            assert_eq!(Language::from_script_tag(lang.script_tag()), Some(lang));
+            assert_eq!(
+                minicbor::to_vec(lang).unwrap(),
+                minicbor::to_vec(id).unwrap()
+            );
+        }
+
+        assert_eq!(Language::try_from(3), Err(UnknownLanguage(3)));
+        assert_eq!(Language::from_script_tag(0), None);
+        assert_eq!(
+            PlutusScript::<3>(vec![].into()).language(),
+            Language::PlutusV3
+        );
+    }
+
+    #[test]
+    fn era_languages_reject_later_versions() {
+        let v2 = minicbor::to_vec(Language::PlutusV2).unwrap();
+        let v3 = minicbor::to_vec(Language::PlutusV3).unwrap();
+
+        assert!(minicbor::decode::<alonzo::Language>(&v2).is_err());
+        assert!(minicbor::decode::<babbage::Language>(&v2).is_ok());
+        assert!(minicbor::decode::<babbage::Language>(&v3).is_err());
+
+        // {1: [0]}, a PlutusV2 cost model isn't valid in an Alonzo update
+        let cost_models = hex::decode("a1018100").unwrap();
+        assert!(minicbor::decode::<alonzo::CostModels>(&cost_models).is_err());
+
+        assert_eq!(
+            Language::from(babbage::Language::PlutusV2),
+            Language::PlutusV2
+        );
+    }
+
+    #[test]
+    fn metadatum_limits_are_enforced() {
+        // [[[[1]]]] and {1: [1, 2, 3]}, in both definite and indefinite forms
+        let nested = hex::decode("8181818101").unwrap();
+        let nested_indef = hex::decode("9f9f9f9f01ffffffff").unwrap();
+        let wide = hex::decode("a10183010203").unwrap();
+        let wide_indef = hex::decode("bf0183010203ff").unwrap();
+
+        let decode = |cbor: &[u8]| minicbor::decode::<Metadatum>(cbor).unwrap();
+
+        // arrays are always encoded as definite, maps keep their original form
+        assert_eq!(decode(&nested), decode(&nested_indef));
+        assert_eq!(minicbor::to_vec(decode(&wide)).unwrap(), wide);
+        assert_eq!(minicbor::to_vec(decode(&wide_indef)).unwrap(), wide_indef);
+
+        let shallow = MetadatumLimits {
+            max_depth: 3,
+            ..Default::default()
+        };
+
+        for (cbor, ok) in [(&nested, false), (&nested_indef, false), (&wide, true)] {
+            let mut d = minicbor::Decoder::new(cbor);
+            assert_eq!(Metadatum::decode_with_limits(&mut d, &shallow).is_ok(), ok);
+        }
+
+        let narrow = MetadatumLimits {
+            max_collection_size: 2,
+            ..Default::default()
+        };
+
+        for (cbor, ok) in [(&nested, true), (&wide, false), (&wide_indef, false)] {
+            let mut d = minicbor::Decoder::new(cbor);
+            assert_eq!(Metadatum::decode_with_limits(&mut d, &narrow).is_ok(), ok);
+        }
+
+        // an array declaring 2^64 - 1 items and a deeply nested one are rejected
+        // without allocating or overflowing the stack
+        let defaults = MetadatumLimits::default();
+
+        let huge = hex::decode("9bffffffffffffffff01").unwrap();
+        let mut d = minicbor::Decoder::new(&huge);
+        assert!(Metadatum::decode_with_limits(&mut d, &defaults).is_err());
+
+        let deep = [vec![0x81; 100_000], vec![0x01]].concat();
+        let mut d = minicbor::Decoder::new(&deep);
+        assert!(Metadatum::decode_with_limits(&mut d, &defaults).is_err());
+
+        // the plain decoder doesn't cap nesting, the ledger doesn't either
+        let deeper = [vec![0x81; 300], vec![0x01]].concat();
+        assert!(minicbor::decode::<Metadatum>(&deeper).is_ok());
+        let mut d = minicbor::Decoder::new(&deeper);
+        assert!(Metadatum::decode_with_limits(&mut d, &defaults).is_err());
+    }
+
+    #[test]
+    fn stake_credential_helpers() {
+        let hash: Hash<28> = "00000000000000000000000000000000000000000000000000000000"
+            .parse()
+            .unwrap();
+
+        let key = StakeCredential::AddrKeyhash(hash);
+        let script = StakeCredential::ScriptHash(hash);
+
+        assert_eq!(key.kind(), CredentialKind::Key);
+        assert_eq!(script.kind(), CredentialKind::Script);
+        assert_eq!(key.as_hash(), script.as_hash());
+
+        assert_eq!(
+            key.to_string(),
+            "key:00000000000000000000000000000000000000000000000000000000"
+        );
+
+        assert!(key.to_bech32().unwrap().starts_with("stake_vkh1"));
+        assert!(script.to_bech32().unwrap().starts_with("script1"));
+
+        for (role, hrp, header) in [
+            (GovernanceRole::CommitteeHot, "cc_hot", 0x02),
+            (GovernanceRole::CommitteeCold, "cc_cold", 0x12),
+            (GovernanceRole::DRep, "drep", 0x22),
+        ] {
+            for (credential, kind) in [(&key, 0x00), (&script, 0x01)] {
+                let encoded = credential.to_cip129_bech32(role).unwrap();
+                let (decoded_hrp, data, _) = bech32::decode(&encoded).unwrap();
+                let payload: Vec<u8> = bech32::FromBase32::from_base32(&data).unwrap();
+
+                assert_eq!(decoded_hrp, hrp);
+                assert_eq!(payload[0], header | kind);
+                assert_eq!(&payload[1..], hash.as_ref());
+            }
+        }
+    }
+}
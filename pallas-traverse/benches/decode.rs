@@ -0,0 +1,30 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use pallas_traverse::MultiEraBlock;
+
+fn decode_blocks(c: &mut Criterion) {
+    let blocks = [
+        ("babbage9", include_str!("../../test_data/babbage9.block")),
+        ("conway1", include_str!("../../test_data/conway1.block")),
+    ];
+
+    let mut group = c.benchmark_group("decode");
+
+    for (name, block) in blocks {
+        let cbor = hex::decode(block).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("full", name), &cbor, |b, cbor| {
+            b.iter(|| MultiEraBlock::decode(cbor).unwrap())
+        });
+
+        group.bench_with_input(
+            BenchmarkId::new("skipping_witnesses", name),
+            &cbor,
+            |b, cbor| b.iter(|| MultiEraBlock::decode_skipping_witnesses(cbor).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, decode_blocks);
+criterion_main!(benches);
@@ -1,68 +1,142 @@
 use std::{borrow::Cow, ops::Deref};
 
-use pallas_codec::minicbor;
-use pallas_crypto::hash::Hash;
+use pallas_codec::{
+    minicbor,
+    utils::{KeepRaw, MaybeIndefArray},
+};
+use pallas_crypto::hash::{Hash, Hasher};
 use pallas_primitives::{alonzo, babbage, byron, conway};
 
 use crate::{
-    probe, support, Era, Error, MultiEraBlock, MultiEraHeader, MultiEraTx, MultiEraUpdate,
+    probe, support, DecodeProgress, Era, Error, MultiEraBlock, MultiEraHeader, MultiEraTx,
+    MultiEraUpdate,
 };
 
 type BlockWrapper<T> = (u16, T);
 
+/// CBOR of an empty map, used as stand-in for witness sets that were skipped
+const EMPTY_WITNESS_SET: &[u8] = &[0xa0];
+
+fn empty_witness_sets<'b, T>(count: usize) -> Result<MaybeIndefArray<KeepRaw<'b, T>>, Error>
+where
+    T: minicbor::Decode<'b, ()> + Clone,
+{
+    let empty: KeepRaw<T> = minicbor::decode(EMPTY_WITNESS_SET).map_err(Error::invalid_cbor)?;
+
+    Ok(MaybeIndefArray::Def(vec![empty; count]))
+}
+
+/// Combine the hashes of each block body segment into the block body hash
+///
+/// Each segment is re-encoded before hashing: the items inside keep their
+/// original CBOR, but the outer containers (array and map headers, map keys
+/// and invalid tx indexes) are written out again. The invalid transactions
+/// segment only exists from Alonzo onwards.
+fn segwit_body_hash<B, W, A, I>(
+    bodies: &B,
+    witnesses: &W,
+    aux_data: &A,
+    invalid_txs: Option<&I>,
+) -> Hash<32>
+where
+    B: minicbor::Encode<()>,
+    W: minicbor::Encode<()>,
+    A: minicbor::Encode<()>,
+    I: minicbor::Encode<()>,
+{
+    let mut hasher = Hasher::<256>::new();
+
+    hasher.input(Hasher::<256>::hash_cbor(bodies).as_ref());
+    hasher.input(Hasher::<256>::hash_cbor(witnesses).as_ref());
+    hasher.input(Hasher::<256>::hash_cbor(aux_data).as_ref());
+
+    if let Some(invalid_txs) = invalid_txs {
+        hasher.input(Hasher::<256>::hash_cbor(invalid_txs).as_ref());
+    }
+
+    hasher.finalize()
+}
+
+/// Checks if the block envelope and header can be read from the given bytes
+fn header_is_complete(cbor: &[u8]) -> bool {
+    let mut d = minicbor::Decoder::new(cbor);
+
+    // [era, [header, ...]]
+    d.array().is_ok() && d.u16().is_ok() && d.array().is_ok() && d.skip().is_ok()
+}
+
+/// Turns a block decoding error into one that describes how far decoding got
+///
+/// Running out of input is reported separately from invalid data, so that
+/// readers of data being written (eg: the tip of an immutable chunk) can tell
+/// if they need to wait for more bytes.
+fn block_decode_error(cbor: &[u8], era: Option<Era>, error: minicbor::decode::Error) -> Error {
+    let progress = DecodeProgress {
+        era,
+        header: header_is_complete(cbor),
+        offset: error.position().unwrap_or(cbor.len()),
+    };
+
+    if error.is_end_of_input() {
+        Error::IncompleteBlock(progress)
+    } else {
+        Error::CorruptBlock(progress, error.to_string())
+    }
+}
+
 impl<'b> MultiEraBlock<'b> {
     pub fn decode_epoch_boundary(cbor: &'b [u8]) -> Result<Self, Error> {
         let (_, block): BlockWrapper<byron::MintedEbBlock> =
-            minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+            minicbor::decode(cbor).map_err(|e| block_decode_error(cbor, Some(Era::Byron), e))?;
 
         Ok(Self::EpochBoundary(Box::new(block)))
     }
 
     pub fn decode_byron(cbor: &'b [u8]) -> Result<Self, Error> {
         let (_, block): BlockWrapper<byron::MintedBlock> =
-            minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+            minicbor::decode(cbor).map_err(|e| block_decode_error(cbor, Some(Era::Byron), e))?;
 
         Ok(Self::Byron(Box::new(block)))
     }
 
     pub fn decode_shelley(cbor: &'b [u8]) -> Result<Self, Error> {
         let (_, block): BlockWrapper<alonzo::MintedBlock> =
-            minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+            minicbor::decode(cbor).map_err(|e| block_decode_error(cbor, Some(Era::Shelley), e))?;
 
         Ok(Self::AlonzoCompatible(Box::new(block), Era::Shelley))
     }
 
     pub fn decode_allegra(cbor: &'b [u8]) -> Result<Self, Error> {
         let (_, block): BlockWrapper<alonzo::MintedBlock> =
-            minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+            minicbor::decode(cbor).map_err(|e| block_decode_error(cbor, Some(Era::Allegra), e))?;
 
         Ok(Self::AlonzoCompatible(Box::new(block), Era::Allegra))
     }
 
     pub fn decode_mary(cbor: &'b [u8]) -> Result<Self, Error> {
         let (_, block): BlockWrapper<alonzo::MintedBlock> =
-            minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+            minicbor::decode(cbor).map_err(|e| block_decode_error(cbor, Some(Era::Mary), e))?;
 
         Ok(Self::AlonzoCompatible(Box::new(block), Era::Mary))
     }
 
     pub fn decode_alonzo(cbor: &'b [u8]) -> Result<Self, Error> {
         let (_, block): BlockWrapper<alonzo::MintedBlock> =
-            minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+            minicbor::decode(cbor).map_err(|e| block_decode_error(cbor, Some(Era::Alonzo), e))?;
 
         Ok(Self::AlonzoCompatible(Box::new(block), Era::Alonzo))
     }
 
     pub fn decode_babbage(cbor: &'b [u8]) -> Result<Self, Error> {
         let (_, block): BlockWrapper<babbage::MintedBlock> =
-            minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+            minicbor::decode(cbor).map_err(|e| block_decode_error(cbor, Some(Era::Babbage), e))?;
 
         Ok(Self::Babbage(Box::new(block)))
     }
 
     pub fn decode_conway(cbor: &'b [u8]) -> Result<Self, Error> {
         let (_, block): BlockWrapper<conway::MintedBlock> =
-            minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+            minicbor::decode(cbor).map_err(|e| block_decode_error(cbor, Some(Era::Conway), e))?;
 
         Ok(Self::Conway(Box::new(block)))
     }
@@ -79,7 +153,58 @@ impl<'b> MultiEraBlock<'b> {
                 Era::Babbage => Self::decode_babbage(cbor),
                 Era::Conway => Self::decode_conway(cbor),
             },
-            probe::Outcome::Inconclusive => Err(Error::unknown_cbor(cbor)),
+            probe::Outcome::Inconclusive => {
+                let mut d = minicbor::Decoder::new(cbor);
+
+                match d.array().and_then(|_| d.u16()) {
+                    Err(e) if e.is_end_of_input() => Err(block_decode_error(cbor, None, e)),
+                    _ => Err(Error::unknown_cbor(cbor)),
+                }
+            }
+        }
+    }
+
+    /// Decode a block without decoding its witness sets
+    ///
+    /// Witness decoding accounts for a large share of the work and many
+    /// consumers (eg: output indexers) don't need them. The returned block
+    /// behaves as if every tx had an empty witness set: accessors such as
+    /// `vkey_witnesses`, `redeemers` or `plutus_data` return nothing and tx
+    /// sizes don't account for witnesses. Hashes are not affected.
+    ///
+    /// Skipping is supported for Babbage and Conway blocks; blocks from other
+    /// eras are fully decoded.
+    pub fn decode_skipping_witnesses(cbor: &'b [u8]) -> Result<MultiEraBlock<'b>, Error> {
+        match probe::block_era(cbor) {
+            probe::Outcome::Matched(Era::Babbage) => {
+                let (_, block): BlockWrapper<babbage::MintedBlockRawWitnesses> =
+                    minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+
+                let count = block.transaction_witness_sets.len();
+
+                Ok(Self::Babbage(Box::new(babbage::MintedBlock {
+                    header: block.header,
+                    transaction_bodies: block.transaction_bodies,
+                    transaction_witness_sets: empty_witness_sets(count)?,
+                    auxiliary_data_set: block.auxiliary_data_set,
+                    invalid_transactions: block.invalid_transactions,
+                })))
+            }
+            probe::Outcome::Matched(Era::Conway) => {
+                let (_, block): BlockWrapper<conway::MintedBlockRawWitnesses> =
+                    minicbor::decode(cbor).map_err(Error::invalid_cbor)?;
+
+                let count = block.transaction_witness_sets.len();
+
+                Ok(Self::Conway(Box::new(conway::MintedBlock {
+                    header: block.header,
+                    transaction_bodies: block.transaction_bodies,
+                    transaction_witness_sets: empty_witness_sets(count)?,
+                    auxiliary_data_set: block.auxiliary_data_set,
+                    invalid_transactions: block.invalid_transactions,
+                })))
+            }
+            _ => Self::decode(cbor),
         }
     }
 
@@ -224,6 +349,55 @@ impl<'b> MultiEraBlock<'b> {
         }
     }
 
+    /// Recompute the hash of the block body from its contents
+    ///
+    /// This is the value committed as `block_body_hash` in the header of
+    /// Shelley and later blocks. Byron blocks commit to their body through a
+    /// different proof structure which is not supported, so `None` is
+    /// returned for them. Blocks decoded with
+    /// [`MultiEraBlock::decode_skipping_witnesses`] don't hold their witnesses
+    /// and won't produce the original hash.
+    ///
+    /// The segment containers are re-encoded rather than taken from the
+    /// original bytes, so a body that used a non-canonical encoding for them
+    /// (eg: a non-minimal array length) hashes to a different value.
+    pub fn compute_body_hash(&self) -> Option<Hash<32>> {
+        match self {
+            MultiEraBlock::EpochBoundary(_) => None,
+            MultiEraBlock::Byron(_) => None,
+            MultiEraBlock::AlonzoCompatible(x, _) => Some(segwit_body_hash(
+                &x.transaction_bodies,
+                &x.transaction_witness_sets,
+                &x.auxiliary_data_set,
+                x.invalid_transactions.as_ref(),
+            )),
+            MultiEraBlock::Babbage(x) => Some(segwit_body_hash(
+                &x.transaction_bodies,
+                &x.transaction_witness_sets,
+                &x.auxiliary_data_set,
+                x.invalid_transactions.as_ref(),
+            )),
+            MultiEraBlock::Conway(x) => Some(segwit_body_hash(
+                &x.transaction_bodies,
+                &x.transaction_witness_sets,
+                &x.auxiliary_data_set,
+                x.invalid_transactions.as_ref(),
+            )),
+        }
+    }
+
+    /// Check that the block body matches the hash committed in the header
+    ///
+    /// Always `false` for Byron blocks, see
+    /// [`MultiEraBlock::compute_body_hash`]. It can also return `false` for a
+    /// valid block whose segment containers aren't canonically encoded.
+    pub fn verify_body_hash(&self) -> bool {
+        match (self.compute_body_hash(), self.header().block_body_hash()) {
+            (Some(computed), Some(expected)) => computed == expected,
+            _ => false,
+        }
+    }
+
     /// Return the size of the serialised block in bytes
     pub fn size(&self) -> usize {
         match self {
@@ -256,4 +430,106 @@ mod tests {
             assert_eq!(block.txs().len(), tx_count);
         }
     }
+
+    #[test]
+    fn skipping_witnesses_keeps_everything_else() {
+        let blocks = [
+            include_str!("../../test_data/babbage9.block"),
+            include_str!("../../test_data/conway1.block"),
+            include_str!("../../test_data/alonzo1.block"),
+        ];
+
+        for block_str in blocks {
+            let cbor = hex::decode(block_str).expect("invalid hex");
+
+            let full = MultiEraBlock::decode(&cbor).expect("invalid cbor");
+            let skimmed = MultiEraBlock::decode_skipping_witnesses(&cbor).expect("invalid cbor");
+
+            assert_eq!(full.hash(), skimmed.hash());
+            assert_eq!(full.txs().len(), skimmed.txs().len());
+
+            for (a, b) in full.txs().iter().zip(skimmed.txs().iter()) {
+                assert_eq!(a.hash(), b.hash());
+                assert_eq!(a.outputs().len(), b.outputs().len());
+                assert_eq!(a.metadata().as_alonzo(), b.metadata().as_alonzo());
+
+                if full.era() >= Era::Babbage {
+                    assert!(b.vkey_witnesses().is_empty());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_blocks_are_incomplete() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.block")).unwrap();
+
+        let progress = |len: usize| match MultiEraBlock::decode(&cbor[..len]) {
+            Err(Error::IncompleteBlock(x)) => x,
+            x => panic!("unexpected outcome {x:?}"),
+        };
+
+        let x = progress(1);
+        assert_eq!(x.era, None);
+        assert!(!x.header);
+
+        let x = progress(10);
+        assert_eq!(x.era, Some(Era::Conway));
+        assert!(!x.header);
+        assert!(x.offset <= 10);
+
+        let x = progress(cbor.len() - 1);
+        assert_eq!(x.era, Some(Era::Conway));
+        assert!(x.header);
+
+        assert!(MultiEraBlock::decode(&cbor[..cbor.len() / 2])
+            .unwrap_err()
+            .is_incomplete());
+    }
+
+    #[test]
+    fn corrupt_blocks_are_not_incomplete() {
+        let mut cbor = hex::decode(include_str!("../../test_data/conway1.block")).unwrap();
+
+        // a break code where the envelope expects the era tag
+        cbor[1] = 0xff;
+
+        match MultiEraBlock::decode_conway(&cbor) {
+            Err(Error::CorruptBlock(x, _)) => {
+                assert_eq!(x.era, Some(Era::Conway));
+                assert!(!x.header);
+                assert_eq!(x.offset, 1);
+            }
+            x => panic!("unexpected outcome {x:?}"),
+        }
+    }
+
+    #[test]
+    fn body_hash_matches_header() {
+        let blocks = [
+            include_str!("../../test_data/shelley1.block"),
+            include_str!("../../test_data/allegra1.block"),
+            include_str!("../../test_data/mary1.block"),
+            include_str!("../../test_data/alonzo1.block"),
+            include_str!("../../test_data/babbage9.block"),
+            include_str!("../../test_data/conway1.block"),
+        ];
+
+        for block_str in blocks {
+            let cbor = hex::decode(block_str).expect("invalid hex");
+            let block = MultiEraBlock::decode(&cbor).expect("invalid cbor");
+
+            assert_eq!(block.compute_body_hash(), block.header().block_body_hash());
+            assert!(block.verify_body_hash());
+        }
+
+        let cbor = hex::decode(include_str!("../../test_data/babbage9.block")).unwrap();
+        let skimmed = MultiEraBlock::decode_skipping_witnesses(&cbor).unwrap();
+        assert!(!skimmed.verify_body_hash());
+
+        let cbor = hex::decode(include_str!("../../test_data/byron2.block")).unwrap();
+        let byron = MultiEraBlock::decode(&cbor).unwrap();
+        assert!(byron.compute_body_hash().is_none());
+        assert!(!byron.verify_body_hash());
+    }
 }
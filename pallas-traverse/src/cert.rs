@@ -1,7 +1,33 @@
-use pallas_primitives::{alonzo, conway};
+use std::ops::Deref;
+
+use pallas_crypto::hash::Hash;
+use pallas_primitives::{alonzo, conway, StakeCredential};
+use thiserror::Error;
 
 use crate::MultiEraCert;
 
+/// Protocol params needed to compute the implicit deposits of certificates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DepositParams {
+    pub key_deposit: u64,
+    pub pool_deposit: u64,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum DepositError {
+    #[error("refund of a legacy stake deregistration needs the deposit paid at registration")]
+    MissingRegisteredDeposit,
+
+    #[error("deposit of {0} doesn't fit an i64")]
+    Overflow(u64),
+}
+
+fn push_key(out: &mut Vec<Hash<28>>, credential: &StakeCredential) {
+    if let StakeCredential::AddrKeyhash(x) = credential {
+        out.push(*x);
+    }
+}
+
 impl MultiEraCert<'_> {
     pub fn as_alonzo(&self) -> Option<&alonzo::Certificate> {
         match self {
@@ -16,4 +42,248 @@ impl MultiEraCert<'_> {
             _ => None,
         }
     }
+
+    /// Key hashes that must sign a tx carrying this certificate
+    ///
+    /// Script credentials are left out, as are the genesis keys required by
+    /// genesis delegation and MIR certificates.
+    pub(crate) fn required_key_hashes(&self) -> Vec<Hash<28>> {
+        let mut out = vec![];
+
+        match self {
+            MultiEraCert::AlonzoCompatible(x) => match x.deref().deref() {
+                alonzo::Certificate::StakeDeregistration(c)
+                | alonzo::Certificate::StakeDelegation(c, _) => push_key(&mut out, c),
+                alonzo::Certificate::PoolRegistration {
+                    operator,
+                    pool_owners,
+                    ..
+                } => {
+                    out.push(*operator);
+                    out.extend(pool_owners.iter());
+                }
+                alonzo::Certificate::PoolRetirement(operator, _) => out.push(*operator),
+                _ => (),
+            },
+            MultiEraCert::Conway(x) => match x.deref().deref() {
+                conway::Certificate::StakeRegistration(_) => (),
+                conway::Certificate::StakeDeregistration(c)
+                | conway::Certificate::StakeDelegation(c, _)
+                | conway::Certificate::Reg(c, _)
+                | conway::Certificate::UnReg(c, _)
+                | conway::Certificate::VoteDeleg(c, _)
+                | conway::Certificate::StakeVoteDeleg(c, _, _)
+                | conway::Certificate::StakeRegDeleg(c, _, _)
+                | conway::Certificate::VoteRegDeleg(c, _, _)
+                | conway::Certificate::StakeVoteRegDeleg(c, _, _, _)
+                | conway::Certificate::AuthCommitteeHot(c, _)
+                | conway::Certificate::ResignCommitteeCold(c, _)
+                | conway::Certificate::RegDRepCert(c, _, _)
+                | conway::Certificate::UnRegDRepCert(c, _)
+                | conway::Certificate::UpdateDRepCert(c, _) => push_key(&mut out, c),
+                conway::Certificate::PoolRegistration {
+                    operator,
+                    pool_owners,
+                    ..
+                } => {
+                    out.push(*operator);
+                    out.extend(pool_owners.iter());
+                }
+                conway::Certificate::PoolRetirement(operator, _) => out.push(*operator),
+            },
+            _ => (),
+        }
+
+        out
+    }
+
+    /// The deposit paid (positive) or refunded (negative) by this certificate
+    ///
+    /// Pre-Conway certificates don't carry the amount, so stake
+    /// (de)registrations use the key deposit of the params and pool
+    /// registrations the pool deposit. Conway certificates that register or
+    /// unregister a stake credential or a DRep carry the amount explicitly,
+    /// which is returned as is. Legacy stake registrations in Conway still pay
+    /// the key deposit of the params, but legacy deregistrations refund the
+    /// deposit recorded when the credential was registered, which only the
+    /// ledger state knows. Callers must supply it as `registered_deposit`,
+    /// which isn't used by any other certificate.
+    ///
+    /// Returns `None` for certificates that don't move deposits, including
+    /// pool retirements, whose deposit is refunded at the epoch boundary
+    /// instead of by the tx.
+    ///
+    /// Re-registering an existing pool doesn't pay a deposit, but telling
+    /// both cases apart requires the ledger state, so pool registrations
+    /// always report the pool deposit.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MissingRegisteredDeposit` for a Conway legacy deregistration
+    /// without `registered_deposit`, and `Overflow` for amounts that don't fit
+    /// an `i64`.
+    pub fn deposit(
+        &self,
+        params: &DepositParams,
+        registered_deposit: Option<u64>,
+    ) -> Result<Option<i64>, DepositError> {
+        let paid = |x: u64| i64::try_from(x).map_err(|_| DepositError::Overflow(x));
+        let refunded = |x: u64| paid(x).map(|x| -x);
+
+        let deposit = match self {
+            MultiEraCert::AlonzoCompatible(x) => match x.deref().deref() {
+                alonzo::Certificate::StakeRegistration(_) => paid(params.key_deposit)?,
+                alonzo::Certificate::StakeDeregistration(_) => refunded(params.key_deposit)?,
+                alonzo::Certificate::PoolRegistration { .. } => paid(params.pool_deposit)?,
+                _ => return Ok(None),
+            },
+            MultiEraCert::Conway(x) => match x.deref().deref() {
+                conway::Certificate::StakeRegistration(_) => paid(params.key_deposit)?,
+                conway::Certificate::StakeDeregistration(_) => {
+                    let deposit =
+                        registered_deposit.ok_or(DepositError::MissingRegisteredDeposit)?;
+                    refunded(deposit)?
+                }
+                conway::Certificate::PoolRegistration { .. } => paid(params.pool_deposit)?,
+                conway::Certificate::Reg(_, coin)
+                | conway::Certificate::StakeRegDeleg(_, _, coin)
+                | conway::Certificate::VoteRegDeleg(_, _, coin)
+                | conway::Certificate::StakeVoteRegDeleg(_, _, _, coin)
+                | conway::Certificate::RegDRepCert(_, coin, _) => paid(*coin)?,
+                conway::Certificate::UnReg(_, coin)
+                | conway::Certificate::UnRegDRepCert(_, coin) => refunded(*coin)?,
+                _ => return Ok(None),
+            },
+            _ => return Ok(None),
+        };
+
+        Ok(Some(deposit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use pallas_primitives::{conway::DRep, Nullable};
+
+    use super::*;
+
+    const PARAMS: DepositParams = DepositParams {
+        key_deposit: 2_000_000,
+        pool_deposit: 500_000_000,
+    };
+
+    fn conway(cert: conway::Certificate) -> MultiEraCert<'static> {
+        MultiEraCert::Conway(Box::new(Cow::Owned(cert)))
+    }
+
+    #[test]
+    fn conway_deposits_are_explicit() {
+        let credential = StakeCredential::AddrKeyhash(Hash::from([1u8; 28]));
+
+        let cases = [
+            (
+                conway::Certificate::Reg(credential.clone(), 3_000_000),
+                Some(3_000_000),
+            ),
+            (
+                conway::Certificate::UnReg(credential.clone(), 3_000_000),
+                Some(-3_000_000),
+            ),
+            (
+                conway::Certificate::VoteRegDeleg(credential.clone(), DRep::Abstain, 1_000_000),
+                Some(1_000_000),
+            ),
+            (
+                conway::Certificate::RegDRepCert(credential.clone(), 500_000_000, Nullable::Null),
+                Some(500_000_000),
+            ),
+            (
+                conway::Certificate::UnRegDRepCert(credential.clone(), 500_000_000),
+                Some(-500_000_000),
+            ),
+            (
+                conway::Certificate::StakeRegistration(credential.clone()),
+                Some(2_000_000),
+            ),
+            (
+                conway::Certificate::VoteDeleg(credential.clone(), DRep::NoConfidence),
+                None,
+            ),
+            (
+                conway::Certificate::PoolRetirement(Hash::from([2u8; 28]), 300),
+                None,
+            ),
+        ];
+
+        for (cert, expected) in cases {
+            assert_eq!(conway(cert).deposit(&PARAMS, None), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn conway_legacy_deregistration_refunds_registered_deposit() {
+        let credential = StakeCredential::AddrKeyhash(Hash::from([1u8; 28]));
+        let dereg = conway(conway::Certificate::StakeDeregistration(credential));
+
+        // the key deposit changed since the credential was registered
+        assert_eq!(
+            dereg.deposit(&PARAMS, Some(1_000_000)),
+            Ok(Some(-1_000_000))
+        );
+
+        assert_eq!(
+            dereg.deposit(&PARAMS, None),
+            Err(DepositError::MissingRegisteredDeposit)
+        );
+    }
+
+    #[test]
+    fn deposits_beyond_i64_are_rejected() {
+        let credential = StakeCredential::AddrKeyhash(Hash::from([1u8; 28]));
+        let huge = u64::MAX;
+
+        let reg = conway(conway::Certificate::Reg(credential.clone(), huge));
+        assert_eq!(
+            reg.deposit(&PARAMS, None),
+            Err(DepositError::Overflow(huge))
+        );
+
+        let unreg = conway(conway::Certificate::UnReg(credential.clone(), huge));
+        assert_eq!(
+            unreg.deposit(&PARAMS, None),
+            Err(DepositError::Overflow(huge))
+        );
+
+        let params = DepositParams {
+            key_deposit: huge,
+            pool_deposit: huge,
+        };
+
+        let reg = MultiEraCert::AlonzoCompatible(Box::new(Cow::Owned(
+            alonzo::Certificate::StakeRegistration(credential),
+        )));
+        assert_eq!(
+            reg.deposit(&params, None),
+            Err(DepositError::Overflow(huge))
+        );
+    }
+
+    #[test]
+    fn alonzo_deposits_come_from_params() {
+        let credential = StakeCredential::AddrKeyhash(Hash::from([1u8; 28]));
+
+        let reg = MultiEraCert::AlonzoCompatible(Box::new(Cow::Owned(
+            alonzo::Certificate::StakeRegistration(credential.clone()),
+        )));
+        assert_eq!(reg.deposit(&PARAMS, None), Ok(Some(2_000_000)));
+
+        let dereg = MultiEraCert::AlonzoCompatible(Box::new(Cow::Owned(
+            alonzo::Certificate::StakeDeregistration(credential),
+        )));
+        assert_eq!(dereg.deposit(&PARAMS, None), Ok(Some(-2_000_000)));
+
+        assert_eq!(MultiEraCert::NotApplicable.deposit(&PARAMS, None), Ok(None));
+    }
 }
@@ -0,0 +1,175 @@
+//! Integrity checks over sequences of blocks
+
+use pallas_crypto::hash::Hash;
+use thiserror::Error;
+
+use crate::MultiEraBlock;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ChainError {
+    #[error("block {number} doesn't point to a previous block")]
+    MissingPreviousHash { number: u64 },
+
+    #[error("block {number} points to {found}, expected {expected}")]
+    BrokenLink {
+        number: u64,
+        expected: Hash<32>,
+        found: Hash<32>,
+    },
+
+    #[error("block number {found} follows {previous}, expected {expected}")]
+    UnexpectedNumber {
+        previous: u64,
+        expected: u64,
+        found: u64,
+    },
+}
+
+/// Check that a sequence of blocks forms a contiguous chain
+///
+/// Each block must point to the hash of the block before it and have a block
+/// number one greater than it. Byron epoch boundary blocks share the number of
+/// the block that precedes them, so they're expected to repeat it instead. The
+/// first block of the sequence is taken as-is.
+pub fn verify_chain<'b>(
+    blocks: impl IntoIterator<Item = MultiEraBlock<'b>>,
+) -> Result<(), ChainError> {
+    let mut previous: Option<(Hash<32>, u64)> = None;
+
+    for block in blocks {
+        let number = block.number();
+
+        if let Some((previous_hash, previous_number)) = previous {
+            let found = block
+                .header()
+                .previous_hash()
+                .ok_or(ChainError::MissingPreviousHash { number })?;
+
+            if found != previous_hash {
+                return Err(ChainError::BrokenLink {
+                    number,
+                    expected: previous_hash,
+                    found,
+                });
+            }
+
+            let expected = match block {
+                MultiEraBlock::EpochBoundary(_) => previous_number,
+                _ => previous_number + 1,
+            };
+
+            if number != expected {
+                return Err(ChainError::UnexpectedNumber {
+                    previous: previous_number,
+                    expected,
+                    found: number,
+                });
+            }
+        }
+
+        previous = Some((block.hash(), number));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas_codec::{minicbor, utils::MaybeIndefArray};
+    use pallas_primitives::byron;
+
+    use super::*;
+
+    #[test]
+    fn single_block_is_a_chain() {
+        let cbor = hex::decode(include_str!("../../test_data/alonzo1.block")).unwrap();
+        let block = MultiEraBlock::decode(&cbor).unwrap();
+
+        assert_eq!(verify_chain([block]), Ok(()));
+        assert_eq!(verify_chain(std::iter::empty()), Ok(()));
+    }
+
+    #[test]
+    fn unrelated_blocks_are_rejected() {
+        let first = hex::decode(include_str!("../../test_data/alonzo4.block")).unwrap();
+        let first = MultiEraBlock::decode(&first).unwrap();
+
+        let second = hex::decode(include_str!("../../test_data/alonzo1.block")).unwrap();
+        let second = MultiEraBlock::decode(&second).unwrap();
+
+        let expected = ChainError::BrokenLink {
+            number: second.number(),
+            expected: first.hash(),
+            found: second.header().previous_hash().unwrap(),
+        };
+
+        assert_eq!(verify_chain([first, second]), Err(expected));
+    }
+
+    #[test]
+    fn consecutive_blocks_are_a_chain() {
+        // immutable db chunks are sequences of consecutive blocks
+        let chunk = std::fs::read("../test_data/01285.chunk").unwrap();
+        let mut decoder = minicbor::Decoder::new(&chunk);
+        let mut blocks = vec![];
+
+        while decoder.position() < chunk.len() {
+            let start = decoder.position();
+            decoder.skip().unwrap();
+            let cbor = &chunk[start..decoder.position()];
+            blocks.push(MultiEraBlock::decode(cbor).unwrap());
+        }
+
+        assert!(blocks.len() > 1);
+        assert_eq!(verify_chain(blocks), Ok(()));
+    }
+
+    fn relinked_ebb(prev_block: Hash<32>, number: u64) -> Vec<u8> {
+        let cbor = hex::decode(include_str!("../../test_data/genesis.block").trim()).unwrap();
+        let (tag, mut block): (u16, byron::EbBlock) = minicbor::decode(&cbor).unwrap();
+
+        block.header.prev_block = prev_block;
+        block.header.consensus_data.difficulty = MaybeIndefArray::Def(vec![number]);
+
+        minicbor::to_vec((tag, block)).unwrap()
+    }
+
+    fn relinked_block(prev_block: Hash<32>, number: u64) -> Vec<u8> {
+        let cbor = hex::decode(include_str!("../../test_data/byron5.block").trim()).unwrap();
+        let (tag, mut block): (u16, byron::Block) = minicbor::decode(&cbor).unwrap();
+
+        block.header.prev_block = prev_block;
+        block.header.consensus_data.2 = MaybeIndefArray::Def(vec![number]);
+
+        minicbor::to_vec((tag, block)).unwrap()
+    }
+
+    #[test]
+    fn chain_can_cross_epoch_boundary_blocks() {
+        // there's no real sequence of blocks around an EBB in the test data,
+        // so the genesis EBB and a later Byron block are re-linked to follow
+        // an actual Byron block
+        let first = hex::decode(include_str!("../../test_data/byron4.block").trim()).unwrap();
+        let first = MultiEraBlock::decode(&first).unwrap();
+
+        let ebb = relinked_ebb(first.hash(), first.number());
+        let ebb = MultiEraBlock::decode(&ebb).unwrap();
+        assert!(matches!(ebb, MultiEraBlock::EpochBoundary(_)));
+
+        let next = relinked_block(ebb.hash(), first.number() + 1);
+        let next = MultiEraBlock::decode(&next).unwrap();
+
+        assert_eq!(verify_chain([first.clone(), ebb, next]), Ok(()));
+
+        let ebb = relinked_ebb(first.hash(), first.number() + 1);
+        let ebb = MultiEraBlock::decode(&ebb).unwrap();
+
+        let expected = ChainError::UnexpectedNumber {
+            previous: first.number(),
+            expected: first.number(),
+            found: first.number() + 1,
+        };
+
+        assert_eq!(verify_chain([first, ebb]), Err(expected));
+    }
+}
@@ -1,4 +1,7 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    fmt::{Display, Formatter},
+    ops::RangeInclusive,
+};
 
 use crate::{Era, Feature};
 
@@ -16,6 +19,41 @@ impl Era {
             Feature::CIP1694 => self.ge(&Era::Conway),
         }
     }
+
+    /// Same as `has_feature`, named to read naturally at call sites that
+    /// branch on capabilities (eg: `if era.supports(Feature::CIP1694)`)
+    pub fn supports(&self, feature: Feature) -> bool {
+        self.has_feature(feature)
+    }
+
+    /// Era that corresponds to a major protocol version
+    ///
+    /// Versions newer than the ones known to this library are assumed to be
+    /// intra-era hard forks of the latest era.
+    pub fn from_protocol_version(major: u64) -> Era {
+        match major {
+            0..=1 => Era::Byron,
+            2 => Era::Shelley,
+            3 => Era::Allegra,
+            4 => Era::Mary,
+            5..=6 => Era::Alonzo,
+            7..=8 => Era::Babbage,
+            _ => Era::Conway,
+        }
+    }
+
+    /// Range of major protocol versions known to belong to this era
+    pub fn protocol_versions(&self) -> RangeInclusive<u64> {
+        match self {
+            Era::Byron => 0..=1,
+            Era::Shelley => 2..=2,
+            Era::Allegra => 3..=3,
+            Era::Mary => 4..=4,
+            Era::Alonzo => 5..=6,
+            Era::Babbage => 7..=8,
+            Era::Conway => 9..=10,
+        }
+    }
 }
 
 // for consistency, we use the same tag convention used by the node's cbor
@@ -65,3 +103,50 @@ impl Display for Era {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ERAS: [Era; 7] = [
+        Era::Byron,
+        Era::Shelley,
+        Era::Allegra,
+        Era::Mary,
+        Era::Alonzo,
+        Era::Babbage,
+        Era::Conway,
+    ];
+
+    #[test]
+    fn protocol_versions_roundtrip() {
+        let mut next = 0;
+
+        for era in ERAS {
+            let versions = era.protocol_versions();
+            assert_eq!(*versions.start(), next);
+
+            for major in versions.clone() {
+                assert_eq!(Era::from_protocol_version(major), era);
+            }
+
+            next = versions.end() + 1;
+        }
+
+        assert_eq!(Era::from_protocol_version(next), Era::Conway);
+    }
+
+    #[test]
+    fn supported_features() {
+        assert!(!Era::Byron.supports(Feature::Staking));
+        assert!(Era::Shelley.supports(Feature::Staking));
+        assert!(!Era::Shelley.supports(Feature::TimeLocks));
+        assert!(Era::Mary.supports(Feature::MultiAssets));
+        assert!(!Era::Mary.supports(Feature::SmartContracts));
+        assert!(Era::Alonzo.supports(Feature::SmartContracts));
+        assert!(!Era::Alonzo.supports(Feature::CIP31));
+        assert!(Era::Babbage.supports(Feature::CIP33));
+        assert!(!Era::Babbage.supports(Feature::CIP1694));
+        assert!(Era::Conway.supports(Feature::CIP1694));
+    }
+}
@@ -1,4 +1,4 @@
-use crate::{ComputeHash, OriginalHash};
+use crate::{ComputeHash, MultiEraTx, OriginalHash};
 use pallas_codec::utils::KeepRaw;
 use pallas_crypto::{
     hash::{Hash, Hasher},
@@ -70,6 +70,12 @@ impl ComputeHash<32> for alonzo::AuxiliaryData {
     }
 }
 
+impl OriginalHash<32> for KeepRaw<'_, alonzo::AuxiliaryData> {
+    fn original_hash(&self) -> pallas_crypto::hash::Hash<32> {
+        Hasher::<256>::hash(self.raw_cbor())
+    }
+}
+
 impl ComputeHash<28> for alonzo::NativeScript {
     fn compute_hash(&self) -> Hash<28> {
         Hasher::<224>::hash_tagged_cbor(self, 0)
@@ -151,6 +157,15 @@ impl ComputeHash<32> for babbage::DatumOption {
     }
 }
 
+impl OriginalHash<32> for babbage::MintedDatumOption<'_> {
+    fn original_hash(&self) -> Hash<32> {
+        match self {
+            babbage::PseudoDatumOption::Hash(hash) => *hash,
+            babbage::PseudoDatumOption::Data(data) => data.original_hash(),
+        }
+    }
+}
+
 // conway
 
 impl ComputeHash<32> for conway::TransactionBody {
@@ -177,6 +192,15 @@ impl ComputeHash<28> for PublicKey {
     }
 }
 
+// multi-era
+
+impl OriginalHash<32> for MultiEraTx<'_> {
+    /// The tx id, same as [`MultiEraTx::hash`]
+    fn original_hash(&self) -> Hash<32> {
+        self.hash()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{Era, MultiEraTx};
@@ -398,11 +422,19 @@ mod tests {
         let tx_bytes = hex::decode(tx_hex).unwrap();
         let tx = MultiEraTx::decode_for_era(Era::Babbage, &tx_bytes).unwrap();
 
+        let mut checked = 0;
+
         for output in tx.outputs() {
-            if let Some(MintedDatumOption::Data(datum)) = output.datum() {
-                assert_eq!(datum.original_hash().to_string(), expected);
+            if let Some(option) = output.datum() {
+                if let MintedDatumOption::Data(datum) = &option {
+                    assert_eq!(datum.original_hash().to_string(), expected);
+                    assert_eq!(option.original_hash().to_string(), expected);
+                    checked += 1;
+                }
             }
         }
+
+        assert!(checked > 0);
     }
 
     #[test]
@@ -419,4 +451,37 @@ mod tests {
             hex::decode("2b6b3949d380fea6cb1c1cf88490ea40b2c1ce87717df7869cb1c38e").unwrap()
         )
     }
+
+    #[test]
+    fn original_hashes_match_on_chain_references() {
+        let mut aux_checked = 0;
+
+        for block in [
+            include_str!("../../test_data/alonzo27.block"),
+            include_str!("../../test_data/babbage1.block"),
+            include_str!("../../test_data/babbage9.block"),
+        ] {
+            let cbor = hex::decode(block).unwrap();
+            let block = crate::MultiEraBlock::decode(&cbor).unwrap();
+
+            for tx in block.txs() {
+                assert_eq!(tx.original_hash(), tx.hash());
+
+                let declared = tx
+                    .as_alonzo()
+                    .and_then(|x| x.transaction_body.auxiliary_data_hash.clone())
+                    .or_else(|| {
+                        tx.as_babbage()
+                            .and_then(|x| x.transaction_body.auxiliary_data_hash.clone())
+                    });
+
+                if let (Some(aux), Some(declared)) = (tx.aux_data(), declared) {
+                    assert_eq!(aux.original_hash().as_ref(), &declared[..]);
+                    aux_checked += 1;
+                }
+            }
+        }
+
+        assert!(aux_checked > 0);
+    }
 }
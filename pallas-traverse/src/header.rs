@@ -31,6 +31,27 @@ impl<'b> MultiEraHeader<'b> {
         }
     }
 
+    /// Decode a header as delivered by the node-to-node chain-sync protocol
+    ///
+    /// `variant` is the hard-fork era index of the header and `byron_prefix`
+    /// the pair that precedes Byron headers, whose first value tells epoch
+    /// boundary (0) and main (1) headers apart. These map directly to the
+    /// fields of the chain-sync `HeaderContent` in `pallas-network`, which
+    /// exposes this through `HeaderContent::decode` with its `traverse` feature.
+    pub fn decode_wrapped(
+        variant: u8,
+        byron_prefix: Option<(u8, u64)>,
+        cbor: &'b [u8],
+    ) -> Result<Self, Error> {
+        match (variant, byron_prefix) {
+            (0, None) => Err(Error::InvalidCbor(
+                "byron header without its prefix".to_owned(),
+            )),
+            (0, Some((subtag, _))) => Self::decode(0, Some(subtag), cbor),
+            (variant, _) => Self::decode(variant, None, cbor),
+        }
+    }
+
     pub fn cbor(&self) -> &'b [u8] {
         match self {
             MultiEraHeader::EpochBoundary(x) => x.raw_cbor(),
@@ -87,6 +108,17 @@ impl<'b> MultiEraHeader<'b> {
         }
     }
 
+    /// The hash of the block body committed by this header, if the era has
+    /// one
+    pub fn block_body_hash(&self) -> Option<Hash<32>> {
+        match self {
+            MultiEraHeader::ShelleyCompatible(x) => Some(x.header_body.block_body_hash),
+            MultiEraHeader::BabbageCompatible(x) => Some(x.header_body.block_body_hash),
+            MultiEraHeader::EpochBoundary(_) => None,
+            MultiEraHeader::Byron(_) => None,
+        }
+    }
+
     pub fn header_body_cbor(&self) -> Option<&'b [u8]> {
         match self {
             MultiEraHeader::ShelleyCompatible(x) => Some(x.header_body.raw_cbor()),
@@ -160,3 +192,47 @@ impl<'b> MultiEraHeader<'b> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{MultiEraBlock, MultiEraHeader};
+
+    #[test]
+    fn decode_wrapped_matches_block_header() {
+        let blocks = [
+            (
+                include_str!("../../test_data/genesis.block"),
+                0,
+                Some((0, 0)),
+            ),
+            (
+                include_str!("../../test_data/byron2.block"),
+                0,
+                Some((1, 0)),
+            ),
+            (include_str!("../../test_data/shelley1.block"), 1, None),
+            (include_str!("../../test_data/alonzo1.block"), 4, None),
+            (include_str!("../../test_data/babbage9.block"), 5, None),
+            (include_str!("../../test_data/conway1.block"), 6, None),
+        ];
+
+        for (block_str, variant, byron_prefix) in blocks {
+            let cbor = hex::decode(block_str).unwrap();
+            let block = MultiEraBlock::decode(&cbor).unwrap();
+            let expected = block.header();
+
+            let header =
+                MultiEraHeader::decode_wrapped(variant, byron_prefix, expected.cbor()).unwrap();
+
+            assert_eq!(header.hash(), expected.hash());
+            assert_eq!(header.number(), expected.number());
+            assert_eq!(header.slot(), expected.slot());
+            assert_eq!(
+                matches!(header, MultiEraHeader::EpochBoundary(_)),
+                matches!(block, MultiEraBlock::EpochBoundary(_))
+            );
+        }
+
+        assert!(MultiEraHeader::decode_wrapped(0, None, &[]).is_err());
+    }
+}
@@ -2,14 +2,14 @@
 
 use pallas_codec::utils::NonZeroInt;
 use pallas_codec::utils::PositiveCoin;
-use std::{borrow::Cow, fmt::Display, hash::Hash as StdHash};
+use std::{borrow::Cow, collections::BTreeSet, fmt::Display, hash::Hash as StdHash};
 
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use pallas_codec::utils::{KeepRaw, KeyValuePairs, NonEmptyKeyValuePairs};
 use pallas_crypto::hash::Hash;
-use pallas_primitives::{alonzo, babbage, byron, conway};
+use pallas_primitives::{alonzo, babbage, byron, conway, PlutusScript};
 
 mod support;
 
@@ -17,6 +17,7 @@ pub mod assets;
 pub mod auxiliary;
 pub mod block;
 pub mod cert;
+pub mod chain;
 pub mod era;
 pub mod fees;
 pub mod governance;
@@ -24,9 +25,13 @@ pub mod hashes;
 pub mod header;
 pub mod input;
 pub mod meta;
+pub mod nonce;
 pub mod output;
+#[cfg(feature = "rayon")]
+pub mod par;
 pub mod probe;
 pub mod redeemers;
+pub mod script;
 pub mod signers;
 pub mod size;
 pub mod time;
@@ -202,6 +207,24 @@ pub enum MultiEraGovAction<'b> {
     Conway(Box<Cow<'b, conway::GovAction>>),
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ScriptKind {
+    Native,
+    PlutusV1,
+    PlutusV2,
+    PlutusV3,
+}
+
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum MultiEraScript<'b> {
+    Native(Box<Cow<'b, KeepRaw<'b, alonzo::NativeScript>>>),
+    PlutusV1(Cow<'b, PlutusScript<1>>),
+    PlutusV2(Cow<'b, PlutusScript<2>>),
+    PlutusV3(Cow<'b, PlutusScript<3>>),
+}
+
 #[derive(Debug, Clone)]
 #[non_exhaustive]
 pub enum MultiEraSigners<'b> {
@@ -210,9 +233,38 @@ pub enum MultiEraSigners<'b> {
     AlonzoCompatible(&'b alonzo::RequiredSigners),
 }
 
+/// Key hashes that must sign a tx, grouped by what requires them
+///
+/// The same key can show up in more than one group, eg: a stake key that
+/// both deregisters and withdraws rewards.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SignerRequirements {
+    /// Keys listed in the required signers field of the body
+    pub explicit: BTreeSet<Hash<28>>,
+    /// Payment keys of the spent and collateral outputs
+    pub inputs: BTreeSet<Hash<28>>,
+    /// Stake, pool and governance keys required by the certificates
+    pub certificates: BTreeSet<Hash<28>>,
+    /// Stake keys of the reward accounts being withdrawn
+    pub withdrawals: BTreeSet<Hash<28>>,
+    /// Keys of the committee members, dreps and pools casting votes
+    pub voters: BTreeSet<Hash<28>>,
+}
+
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
 pub struct OutputRef(Hash<32>, u64);
 
+/// How far a block decode got before failing
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeProgress {
+    /// Era of the block as read from its envelope, if it could be read
+    pub era: Option<Era>,
+    /// Whether the block header was fully available
+    pub header: bool,
+    /// Byte offset at which decoding failed
+    pub offset: usize,
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Invalid CBOR structure: {0}")]
@@ -229,6 +281,15 @@ pub enum Error {
 
     #[error("Invalid UTxO ref: {0}")]
     InvalidUtxoRef(String),
+
+    #[error("Incomplete block, input ended at offset {}", .0.offset)]
+    IncompleteBlock(DecodeProgress),
+
+    #[error("Corrupt block at offset {}: {1}", .0.offset)]
+    CorruptBlock(DecodeProgress, String),
+
+    #[error("Update doesn't apply to protocol params of a different era")]
+    UpdateEraMismatch,
 }
 
 impl Error {
@@ -243,12 +304,49 @@ impl Error {
     pub fn invalid_utxo_ref(str: &str) -> Self {
         Error::InvalidUtxoRef(str.to_owned())
     }
+
+    /// True if the error was caused by running out of input, which means
+    /// that decoding could succeed once more bytes are available
+    pub fn is_incomplete(&self) -> bool {
+        matches!(self, Error::IncompleteBlock(_))
+    }
 }
 
+/// Hashes a value by encoding it to CBOR first
+///
+/// The decoded value is re-encoded using the canonical encoding of this
+/// library, which is what you want for values built locally (eg: by a tx
+/// builder). For values that came from the chain the result only matches the
+/// on-chain hash if the original bytes happen to use the same encoding, see
+/// [`OriginalHash`].
 pub trait ComputeHash<const BYTES: usize> {
     fn compute_hash(&self) -> pallas_crypto::hash::Hash<BYTES>;
 }
 
+/// Hashes a decoded value using the bytes it was decoded from
+///
+/// Implemented for types that retain their original CBOR (usually through
+/// `KeepRaw`). No re-encoding takes place, so the hash always matches the one
+/// used by the chain, even when the original bytes use a non-canonical
+/// encoding (indefinite-length arrays, non-minimal integers, unsorted maps).
 pub trait OriginalHash<const BYTES: usize> {
     fn original_hash(&self) -> pallas_crypto::hash::Hash<BYTES>;
 }
+
+/// Provides the outputs pointed to by inputs, usually backed by a UTxO store
+///
+/// Helpers that need to look at the outputs being spent or referenced by a tx
+/// take a resolver. Closures with the same signature as `resolve` can be used
+/// as resolvers directly.
+pub trait UtxoResolver<'r> {
+    fn resolve(&self, input: &MultiEraInput) -> Option<MultiEraOutput<'r>>;
+}
+
+impl<'r, F> UtxoResolver<'r> for F
+where
+    F: Fn(&MultiEraInput) -> Option<MultiEraOutput<'r>>,
+{
+    fn resolve(&self, input: &MultiEraInput) -> Option<MultiEraOutput<'r>> {
+        self(input)
+    }
+}
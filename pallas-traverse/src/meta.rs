@@ -2,8 +2,8 @@ use pallas_primitives::alonzo;
 
 use crate::MultiEraMeta;
 
-impl MultiEraMeta<'_> {
-    pub fn as_alonzo(&self) -> Option<&alonzo::Metadata> {
+impl<'b> MultiEraMeta<'b> {
+    pub fn as_alonzo(&self) -> Option<&'b alonzo::Metadata> {
         match self {
             Self::AlonzoCompatible(x) => Some(x),
             _ => None,
@@ -17,12 +17,20 @@ impl MultiEraMeta<'_> {
         }
     }
 
-    pub fn find(&self, label: alonzo::MetadatumLabel) -> Option<&alonzo::Metadatum> {
+    pub fn find(&self, label: alonzo::MetadatumLabel) -> Option<&'b alonzo::Metadatum> {
         self.as_alonzo()?
             .iter()
             .find_map(|(key, value)| if key.eq(&label) { Some(value) } else { None })
     }
 
+    /// The labels present in the metadata, in their original order
+    pub fn labels(&self) -> Vec<alonzo::MetadatumLabel> {
+        match self.as_alonzo() {
+            Some(x) => x.iter().map(|(k, _)| *k).collect(),
+            None => vec![],
+        }
+    }
+
     pub fn collect<'a, T>(&'a self) -> T
     where
         T: FromIterator<(alonzo::MetadatumLabel, &'a alonzo::Metadatum)>,
@@ -0,0 +1,315 @@
+//! Evolution of the epoch nonce used by the Praos family of protocols
+//!
+//! Every Shelley-onwards block contributes its VRF nonce output to an
+//! _evolving_ nonce. The _candidate_ nonce follows the evolving one until the
+//! randomness stabilisation window of the epoch is reached, after which it
+//! stays frozen. At the epoch boundary, the candidate is combined with the
+//! hash of the block preceding the last block of the previous epoch to
+//! produce the nonce of the new epoch, which is the one that feeds the VRF
+//! leader check.
+
+use pallas_crypto::{
+    hash::{Hash, Hasher},
+    nonce::generate_rolling_nonce,
+};
+
+use crate::{
+    time::{Epoch, Slot},
+    wellknown::GenesisValues,
+    Error, MultiEraHeader,
+};
+
+/// The nonce values tracked across blocks
+///
+/// A `None` value corresponds to the neutral nonce of the consensus specs,
+/// which is the identity when combining nonces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NonceState {
+    /// Nonce that evolves with every block (eta_v)
+    pub evolving: Hash<32>,
+    /// Evolving nonce as of the stability window of the epoch (eta_c)
+    pub candidate: Hash<32>,
+    /// Nonce of the current epoch (eta_0)
+    pub epoch_nonce: Hash<32>,
+    /// Hash of the previous block of the last applied block
+    pub lab: Option<Hash<32>>,
+    /// Value of `lab` at the last epoch boundary (eta_h)
+    pub last_epoch_block: Option<Hash<32>>,
+    /// Slot of the last applied block
+    pub last_slot: Option<Slot>,
+}
+
+impl NonceState {
+    /// State at the start of the Shelley era, where all nonces are the hash
+    /// of the Shelley genesis file
+    pub fn from_genesis(initial: Hash<32>) -> Self {
+        Self {
+            evolving: initial,
+            candidate: initial,
+            epoch_nonce: initial,
+            lab: None,
+            last_epoch_block: None,
+            last_slot: None,
+        }
+    }
+}
+
+/// Combines two nonces, treating `None` as the neutral nonce
+fn combine(a: Hash<32>, b: Option<Hash<32>>) -> Hash<32> {
+    match b {
+        Some(b) => {
+            let mut hasher = Hasher::<256>::new();
+            hasher.update(a.as_ref()).update(b.as_ref());
+            hasher.finalize()
+        }
+        None => a,
+    }
+}
+
+/// Computes the evolving, candidate and epoch nonces from a sequence of
+/// headers
+///
+/// Headers must be ingested in chain order. Byron headers don't carry VRF
+/// values and are ignored, so feeding a full chain from origin is fine as
+/// long as the accumulator starts from the Shelley genesis state.
+#[derive(Debug, Clone)]
+pub struct NonceAccumulator {
+    genesis: GenesisValues,
+    stability_window: u64,
+    extra_entropy: Option<Hash<32>>,
+    state: NonceState,
+}
+
+impl NonceAccumulator {
+    /// Creates an accumulator from a known state
+    ///
+    /// The `stability_window` is the number of slots before the end of the
+    /// epoch at which the candidate nonce is frozen. It's `3k/f` up to the
+    /// Babbage era and `4k/f` from Conway onwards.
+    pub fn new(genesis: &GenesisValues, stability_window: u64, state: NonceState) -> Self {
+        Self {
+            genesis: genesis.clone(),
+            stability_window,
+            extra_entropy: None,
+            state,
+        }
+    }
+
+    /// Sets the value of the `extra_entropy` protocol parameter, mixed into
+    /// every epoch nonce computed while it's set (TPraos only)
+    pub fn set_extra_entropy(&mut self, extra_entropy: Option<Hash<32>>) {
+        self.extra_entropy = extra_entropy;
+    }
+
+    /// Changes the stability window, to be used when crossing into an era
+    /// that defines it differently
+    pub fn set_stability_window(&mut self, stability_window: u64) {
+        self.stability_window = stability_window;
+    }
+
+    pub fn state(&self) -> &NonceState {
+        &self.state
+    }
+
+    pub fn evolving_nonce(&self) -> Hash<32> {
+        self.state.evolving
+    }
+
+    pub fn candidate_nonce(&self) -> Hash<32> {
+        self.state.candidate
+    }
+
+    pub fn epoch_nonce(&self) -> Hash<32> {
+        self.state.epoch_nonce
+    }
+
+    fn epoch_of(&self, slot: Slot) -> Epoch {
+        self.genesis.absolute_slot_to_relative(slot).0
+    }
+
+    /// Applies the nonce of the next header of the chain
+    ///
+    /// If the header is the first one of a new epoch, the nonce of that epoch
+    /// is finalized before applying the header and is returned.
+    pub fn ingest(&mut self, header: &MultiEraHeader) -> Result<Option<Hash<32>>, Error> {
+        if matches!(
+            header,
+            MultiEraHeader::EpochBoundary(_) | MultiEraHeader::Byron(_)
+        ) {
+            return Ok(None);
+        }
+
+        let slot = header.slot();
+        let epoch = self.epoch_of(slot);
+
+        let new_epoch = match self.state.last_slot {
+            Some(last) => epoch > self.epoch_of(last),
+            None => false,
+        };
+
+        let finalized = if new_epoch {
+            let nonce = combine(self.state.candidate, self.state.last_epoch_block);
+            let nonce = combine(nonce, self.extra_entropy);
+
+            self.state.epoch_nonce = nonce;
+            self.state.last_epoch_block = self.state.lab;
+
+            Some(nonce)
+        } else {
+            None
+        };
+
+        let output = header.nonce_vrf_output()?;
+        self.state.evolving = generate_rolling_nonce(self.state.evolving, &output);
+
+        let next_epoch_start = self.genesis.relative_slot_to_absolute(epoch + 1, 0);
+
+        if slot + self.stability_window < next_epoch_start {
+            self.state.candidate = self.state.evolving;
+        }
+
+        self.state.lab = header.previous_hash();
+        self.state.last_slot = Some(slot);
+
+        Ok(finalized)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MultiEraBlock;
+
+    const MAINNET_STABILITY_WINDOW: u64 = 129600;
+
+    fn accumulator(initial: Hash<32>) -> NonceAccumulator {
+        NonceAccumulator::new(
+            &GenesisValues::mainnet(),
+            MAINNET_STABILITY_WINDOW,
+            NonceState::from_genesis(initial),
+        )
+    }
+
+    #[test]
+    fn nonces_evolve_and_finalize_at_epoch_boundary() {
+        let initial = Hash::new([7; 32]);
+        let mut acc = accumulator(initial);
+        let mut evolving = initial;
+
+        // two blocks early in epoch 298, then one of epoch 310
+        let blocks = [
+            include_str!("../../test_data/alonzo1.block"),
+            include_str!("../../test_data/alonzo2.block"),
+            include_str!("../../test_data/alonzo27.block"),
+        ]
+        .map(|x| hex::decode(x.trim()).unwrap());
+
+        let blocks: Vec<_> = blocks
+            .iter()
+            .map(|x| MultiEraBlock::decode(x).unwrap())
+            .collect();
+
+        for block in &blocks[..2] {
+            let header = block.header();
+
+            assert_eq!(acc.ingest(&header).unwrap(), None);
+
+            evolving = generate_rolling_nonce(evolving, &header.nonce_vrf_output().unwrap());
+            assert_eq!(acc.evolving_nonce(), evolving);
+            assert_eq!(acc.candidate_nonce(), evolving);
+            assert_eq!(acc.epoch_nonce(), initial);
+            assert_eq!(acc.state().lab, header.previous_hash());
+        }
+
+        let candidate = evolving;
+        let header = blocks[2].header();
+
+        // eta_h is still neutral, so the first epoch nonce is the candidate
+        assert_eq!(acc.ingest(&header).unwrap(), Some(candidate));
+        assert_eq!(acc.epoch_nonce(), candidate);
+        assert_eq!(
+            acc.state().last_epoch_block,
+            blocks[1].header().previous_hash()
+        );
+
+        evolving = generate_rolling_nonce(evolving, &header.nonce_vrf_output().unwrap());
+        assert_eq!(acc.evolving_nonce(), evolving);
+    }
+
+    #[test]
+    fn mainnet_epoch_nonces_are_reproduced() {
+        let hash = |x: &str| Hash::<32>::from(hex::decode(x).unwrap().as_slice());
+
+        // (eta_c, eta_h, extra entropy, published epoch nonce) of two mainnet
+        // epoch transitions, the second one mixing in the `extraEntropy`
+        // protocol parameter
+        let fixtures = [
+            (
+                "e86e133bd48ff5e79bec43af1ac3e348b539172f33e502d2c96735e8c51bd04d",
+                "d7a1ff2a365abed59c9ae346cba842b6d3df06d055dba79a113e0704b44cc3e9",
+                None,
+                "e536a0081ddd6d19786e9d708a85819a5c3492c0da7349f59c8ad3e17e4acd98",
+            ),
+            (
+                "d1340a9c1491f0face38d41fd5c82953d0eb48320d65e952414a0c5ebaf87587",
+                "ee91d679b0a6ce3015b894c575c799e971efac35c7a8cbdc2b3f579005e69abd",
+                Some("d982e06fd33e7440b43cefad529b7ecafbaa255e38178ad4189a37e4ce9bf1fa"),
+                "0022cfa563a5328c4fb5c8017121329e964c26ade5d167b1bd9b2ec967772b60",
+            ),
+        ];
+
+        // any header of a later epoch triggers the transition, its own VRF
+        // output only affects the evolving nonce
+        let block = hex::decode(include_str!("../../test_data/alonzo1.block").trim()).unwrap();
+        let block = MultiEraBlock::decode(&block).unwrap();
+        let header = block.header();
+
+        let genesis = GenesisValues::mainnet();
+        let (epoch, _) = genesis.absolute_slot_to_relative(header.slot());
+        let last_slot = genesis.relative_slot_to_absolute(epoch - 1, 0);
+
+        for (candidate, lab, extra_entropy, expected) in fixtures {
+            let state = NonceState {
+                evolving: hash(candidate),
+                candidate: hash(candidate),
+                epoch_nonce: Hash::new([0; 32]),
+                lab: None,
+                last_epoch_block: Some(hash(lab)),
+                last_slot: Some(last_slot),
+            };
+
+            let mut acc = NonceAccumulator::new(&genesis, MAINNET_STABILITY_WINDOW, state);
+            acc.set_extra_entropy(extra_entropy.map(hash));
+
+            assert_eq!(acc.ingest(&header).unwrap(), Some(hash(expected)));
+            assert_eq!(acc.epoch_nonce(), hash(expected));
+        }
+    }
+
+    #[test]
+    fn candidate_is_frozen_within_stability_window() {
+        let initial = Hash::new([7; 32]);
+        let mut acc = accumulator(initial);
+
+        // last slots of epoch 260
+        let block = hex::decode(include_str!("../../test_data/mary1.block").trim()).unwrap();
+        let block = MultiEraBlock::decode(&block).unwrap();
+
+        acc.ingest(&block.header()).unwrap();
+
+        assert_ne!(acc.evolving_nonce(), initial);
+        assert_eq!(acc.candidate_nonce(), initial);
+    }
+
+    #[test]
+    fn byron_headers_are_ignored() {
+        let state = NonceState::from_genesis(Hash::new([7; 32]));
+        let mut acc = accumulator(Hash::new([7; 32]));
+
+        let block = hex::decode(include_str!("../../test_data/byron1.block").trim()).unwrap();
+        let block = MultiEraBlock::decode(&block).unwrap();
+
+        assert_eq!(acc.ingest(&block.header()).unwrap(), None);
+        assert_eq!(acc.state(), &state);
+    }
+}
@@ -4,7 +4,7 @@ use pallas_addresses::{Address, ByronAddress, Error as AddressError};
 use pallas_codec::minicbor;
 use pallas_primitives::{alonzo, babbage, byron, conway};
 
-use crate::{Era, MultiEraOutput, MultiEraPolicyAssets, MultiEraValue};
+use crate::{Era, Error, MultiEraOutput, MultiEraPolicyAssets, MultiEraValue};
 
 impl<'b> MultiEraOutput<'b> {
     pub fn from_byron(output: &'b byron::TxOut) -> Self {
@@ -23,6 +23,21 @@ impl<'b> MultiEraOutput<'b> {
         Self::Conway(Box::new(Cow::Borrowed(output)))
     }
 
+    /// Borrows the content of this output instead of cloning it
+    ///
+    /// The returned output lives as long as the reference, which allows
+    /// mixing outputs borrowed from different sources in a single collection.
+    pub fn reborrow(&self) -> MultiEraOutput<'_> {
+        match self {
+            MultiEraOutput::AlonzoCompatible(x, era) => {
+                MultiEraOutput::from_alonzo_compatible(x, *era)
+            }
+            MultiEraOutput::Babbage(x) => MultiEraOutput::from_babbage(x),
+            MultiEraOutput::Conway(x) => MultiEraOutput::from_conway(x),
+            MultiEraOutput::Byron(x) => MultiEraOutput::from_byron(x),
+        }
+    }
+
     pub fn datum(&self) -> Option<conway::MintedDatumOption> {
         match self {
             MultiEraOutput::AlonzoCompatible(x, _) => {
@@ -44,7 +59,7 @@ impl<'b> MultiEraOutput<'b> {
         }
     }
 
-    pub fn script_ref(&self) -> Option<conway::MintedScriptRef> {
+    pub fn script_ref(&self) -> Option<conway::MintedScriptRef<'b>> {
         match &self {
             MultiEraOutput::AlonzoCompatible(..) => None,
             MultiEraOutput::Babbage(x) => match x.deref().deref() {
@@ -160,6 +175,41 @@ impl<'b> MultiEraOutput<'b> {
         }
     }
 
+    /// Decode an output without knowing its era, inferring it from the
+    /// structure of the CBOR
+    ///
+    /// Map-based outputs decode as Babbage, falling back to Conway for the
+    /// ones Babbage can't represent (eg: a Plutus V3 script ref). Array-based
+    /// outputs decode as Byron when the address is a Byron address and as
+    /// Alonzo-compatible (with `Era::Alonzo`) otherwise. The formats of
+    /// different eras overlap, so the inferred era is the earliest one that
+    /// can represent the output, not necessarily the one that produced it.
+    pub fn decode_any(cbor: &'b [u8]) -> Result<Self, Error> {
+        let mut probe = minicbor::Decoder::new(cbor);
+
+        match probe.datatype().map_err(Error::invalid_cbor)? {
+            minicbor::data::Type::Map | minicbor::data::Type::MapIndef => {
+                Self::decode(Era::Babbage, cbor)
+                    .or_else(|_| Self::decode(Era::Conway, cbor))
+                    .map_err(Error::invalid_cbor)
+            }
+            minicbor::data::Type::Array | minicbor::data::Type::ArrayIndef => {
+                probe.array().map_err(Error::invalid_cbor)?;
+
+                match probe.datatype().map_err(Error::invalid_cbor)? {
+                    minicbor::data::Type::Array | minicbor::data::Type::ArrayIndef => {
+                        Self::decode(Era::Byron, cbor).map_err(Error::invalid_cbor)
+                    }
+                    minicbor::data::Type::Bytes => {
+                        Self::decode(Era::Alonzo, cbor).map_err(Error::invalid_cbor)
+                    }
+                    _ => Err(Error::unknown_cbor(cbor)),
+                }
+            }
+            _ => Err(Error::unknown_cbor(cbor)),
+        }
+    }
+
     pub fn value(&self) -> MultiEraValue<'_> {
         match self {
             MultiEraOutput::Byron(x) => MultiEraValue::Byron(x.amount),
@@ -236,3 +286,46 @@ impl<'b> MultiEraOutput<'b> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MultiEraTx;
+
+    #[test]
+    fn decode_any_infers_era() {
+        let mut post_alonzo = 0;
+
+        for (era, tx) in [
+            (Era::Byron, include_str!("../../test_data/byron1.tx")),
+            (Era::Alonzo, include_str!("../../test_data/alonzo1.tx")),
+            (Era::Babbage, include_str!("../../test_data/babbage3.tx")),
+            (Era::Conway, include_str!("../../test_data/conway1.tx")),
+        ] {
+            let cbor = hex::decode(tx).unwrap();
+            let tx = MultiEraTx::decode_for_era(era, &cbor).unwrap();
+
+            for output in tx.outputs() {
+                let encoded = output.encode();
+                let decoded = MultiEraOutput::decode_any(&encoded).unwrap();
+
+                assert_eq!(decoded.encode(), encoded);
+                assert_eq!(decoded.value().coin(), output.value().coin());
+
+                match era {
+                    Era::Byron => assert_eq!(decoded.era(), Era::Byron),
+                    _ => assert_ne!(decoded.era(), Era::Byron),
+                }
+
+                if matches!(decoded.era(), Era::Babbage | Era::Conway) {
+                    post_alonzo += 1;
+                }
+            }
+        }
+
+        assert!(post_alonzo > 0);
+
+        assert!(MultiEraOutput::decode_any(&[0x01]).is_err());
+        assert!(MultiEraOutput::decode_any(&[0x82, 0x01, 0x02]).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+//! Parallel decoding of batches of blocks
+//!
+//! Decoded blocks borrow from the CBOR they were decoded from, so these
+//! helpers take a slice of (owned or borrowed) CBOR payloads and return blocks
+//! that borrow from it. The caller keeps the payloads alive for as long as the
+//! blocks are in use.
+
+use rayon::prelude::*;
+
+use crate::{Error, MultiEraBlock};
+
+/// Decode a batch of block CBORs using all available cores
+///
+/// Results are returned in the same order as the input; a block that fails to
+/// decode doesn't prevent the others from decoding.
+pub fn decode_blocks_par<B>(blocks: &[B]) -> Vec<Result<MultiEraBlock<'_>, Error>>
+where
+    B: AsRef<[u8]> + Sync,
+{
+    blocks
+        .par_iter()
+        .map(|cbor| MultiEraBlock::decode(cbor.as_ref()))
+        .collect()
+}
+
+/// Decode a batch of block CBORs in parallel and map each one to an owned
+/// value
+///
+/// Useful when the decoded blocks are only needed to extract some data, since
+/// the mapping also runs in parallel and the output doesn't borrow from the
+/// input.
+pub fn map_blocks_par<B, F, T>(blocks: &[B], f: F) -> Vec<Result<T, Error>>
+where
+    B: AsRef<[u8]> + Sync,
+    F: Fn(MultiEraBlock) -> T + Sync + Send,
+    T: Send,
+{
+    blocks
+        .par_iter()
+        .map(|cbor| MultiEraBlock::decode(cbor.as_ref()).map(&f))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_in_order() {
+        let blocks: Vec<Vec<u8>> = [
+            include_str!("../../test_data/byron2.block"),
+            include_str!("../../test_data/alonzo1.block"),
+            include_str!("../../test_data/babbage9.block"),
+            include_str!("../../test_data/conway1.block"),
+        ]
+        .iter()
+        .map(|x| hex::decode(x).unwrap())
+        .chain(std::iter::once(vec![0x00]))
+        .collect();
+
+        let decoded = decode_blocks_par(&blocks);
+        assert_eq!(decoded.len(), blocks.len());
+
+        for (cbor, result) in blocks.iter().zip(decoded.iter()).take(4) {
+            let expected = MultiEraBlock::decode(cbor).unwrap();
+            assert_eq!(result.as_ref().unwrap().hash(), expected.hash());
+        }
+
+        assert!(decoded[4].is_err());
+
+        let hashes = map_blocks_par(&blocks[..4], |x| x.hash());
+        let expected: Vec<_> = decoded[..4]
+            .iter()
+            .map(|x| x.as_ref().unwrap().hash())
+            .collect();
+
+        assert_eq!(
+            hashes.into_iter().map(Result::unwrap).collect::<Vec<_>>(),
+            expected
+        );
+    }
+}
@@ -99,3 +99,122 @@ impl<'b> MultiEraRedeemer<'b> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas_codec::utils::NonEmptyKeyValuePairs;
+
+    use crate::{Era, MultiEraTx};
+
+    use super::*;
+
+    #[test]
+    fn conway_map_and_list_forms_are_uniform() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let list_tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let mut owned: conway::Tx = list_tx.as_conway().unwrap().clone().into();
+
+        let pairs = match owned.transaction_witness_set.redeemer.take() {
+            Some(conway::Redeemers::List(x)) => x
+                .iter()
+                .map(|r| {
+                    let key = conway::RedeemersKey {
+                        tag: r.tag,
+                        index: r.index,
+                    };
+
+                    let value = conway::RedeemersValue {
+                        data: r.data.clone(),
+                        ex_units: r.ex_units,
+                    };
+
+                    (key, value)
+                })
+                .collect(),
+            _ => unreachable!("test tx uses the list form"),
+        };
+
+        owned.transaction_witness_set.redeemer =
+            Some(conway::Redeemers::Map(NonEmptyKeyValuePairs::Def(pairs)));
+
+        let cbor = minicbor::to_vec(&owned).unwrap();
+        let map_tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let list = list_tx.redeemers();
+        let map = map_tx.redeemers();
+
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.len(), map.len());
+
+        for (a, b) in list.iter().zip(map.iter()) {
+            assert!(b.as_conway().is_some());
+            assert_eq!(a.tag(), b.tag());
+            assert_eq!(a.index(), b.index());
+            assert_eq!(a.data(), b.data());
+            assert_eq!(a.ex_units(), b.ex_units());
+        }
+
+        let reward = map
+            .iter()
+            .find(|x| x.tag() == conway::RedeemerTag::Reward)
+            .unwrap();
+
+        assert_eq!(reward.index(), 0);
+        assert_eq!(reward.ex_units().mem, 420000);
+        assert_eq!(reward.ex_units().steps, 160000000);
+    }
+
+    #[test]
+    fn spend_redeemer_matches_input() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        for (order, input) in tx.inputs_sorted_set().iter().enumerate() {
+            let redeemer = tx.spend_redeemer_for_input(input).unwrap();
+            assert_eq!(redeemer.tag(), conway::RedeemerTag::Spend);
+            assert_eq!(redeemer.index(), order as u32);
+        }
+
+        let cbor = hex::decode(include_str!("../../test_data/conway2.tx")).unwrap();
+        let other = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        for input in other.inputs() {
+            assert!(tx.spend_redeemer_for_input(&input).is_none());
+        }
+    }
+
+    #[test]
+    fn find_redeemer_by_purpose() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let mut owned: conway::Tx = tx.as_conway().unwrap().clone().into();
+
+        let value = conway::RedeemersValue {
+            data: tx.redeemers()[0].data().clone(),
+            ex_units: conway::ExUnits { mem: 1, steps: 2 },
+        };
+
+        let pairs = [conway::RedeemerTag::Vote, conway::RedeemerTag::Propose]
+            .into_iter()
+            .map(|tag| (conway::RedeemersKey { tag, index: 1 }, value.clone()))
+            .collect();
+
+        owned.transaction_witness_set.redeemer =
+            Some(conway::Redeemers::Map(NonEmptyKeyValuePairs::Def(pairs)));
+
+        let cbor = minicbor::to_vec(&owned).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let vote = tx.find_vote_redeemer(1).unwrap();
+        assert_eq!(vote.tag(), conway::RedeemerTag::Vote);
+
+        let propose = tx.find_propose_redeemer(1).unwrap();
+        assert_eq!(propose.tag(), conway::RedeemerTag::Propose);
+
+        assert!(tx.find_vote_redeemer(0).is_none());
+        assert!(tx.find_propose_redeemer(0).is_none());
+        assert!(tx.find_spend_redeemer(1).is_none());
+    }
+}
@@ -0,0 +1,127 @@
+use std::borrow::Cow;
+
+use pallas_crypto::hash::Hash;
+use pallas_primitives::{alonzo, conway};
+
+use crate::{ComputeHash, MultiEraScript, OriginalHash, ScriptKind};
+
+impl<'b> From<conway::MintedScriptRef<'b>> for MultiEraScript<'b> {
+    fn from(value: conway::MintedScriptRef<'b>) -> Self {
+        match value {
+            conway::PseudoScript::NativeScript(x) => Self::Native(Box::new(Cow::Owned(x))),
+            conway::PseudoScript::PlutusV1Script(x) => Self::PlutusV1(Cow::Owned(x)),
+            conway::PseudoScript::PlutusV2Script(x) => Self::PlutusV2(Cow::Owned(x)),
+            conway::PseudoScript::PlutusV3Script(x) => Self::PlutusV3(Cow::Owned(x)),
+        }
+    }
+}
+
+impl MultiEraScript<'_> {
+    pub fn kind(&self) -> ScriptKind {
+        match self {
+            Self::Native(_) => ScriptKind::Native,
+            Self::PlutusV1(_) => ScriptKind::PlutusV1,
+            Self::PlutusV2(_) => ScriptKind::PlutusV2,
+            Self::PlutusV3(_) => ScriptKind::PlutusV3,
+        }
+    }
+
+    /// The script hash, as used in addresses, policy ids and credentials
+    ///
+    /// Native scripts are hashed over their original CBOR bytes, so the result
+    /// matches the on-chain hash even for non-canonical encodings.
+    pub fn hash(&self) -> Hash<28> {
+        match self {
+            Self::Native(x) => x.original_hash(),
+            Self::PlutusV1(x) => x.compute_hash(),
+            Self::PlutusV2(x) => x.compute_hash(),
+            Self::PlutusV3(x) => x.compute_hash(),
+        }
+    }
+
+    /// Size in bytes of the script, as accounted for by reference script fees
+    pub fn size(&self) -> usize {
+        match self {
+            Self::Native(x) => x.raw_cbor().len(),
+            Self::PlutusV1(x) => x.0.len(),
+            Self::PlutusV2(x) => x.0.len(),
+            Self::PlutusV3(x) => x.0.len(),
+        }
+    }
+
+    pub fn as_native(&self) -> Option<&alonzo::NativeScript> {
+        match self {
+            Self::Native(x) => Some(x),
+            _ => None,
+        }
+    }
+
+    /// The serialized (flat-encoded) program of a Plutus script
+    pub fn as_plutus(&self) -> Option<&[u8]> {
+        match self {
+            Self::Native(_) => None,
+            Self::PlutusV1(x) => Some(x.0.as_slice()),
+            Self::PlutusV2(x) => Some(x.0.as_slice()),
+            Self::PlutusV3(x) => Some(x.0.as_slice()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Deref;
+
+    use crate::{MultiEraBlock, ScriptKind};
+
+    use super::*;
+
+    #[test]
+    fn witness_scripts_are_unified() {
+        let cbor = hex::decode(include_str!("../../test_data/alonzo27.block")).unwrap();
+        let block = MultiEraBlock::decode(&cbor).unwrap();
+
+        let mut seen = 0;
+
+        for tx in block.txs() {
+            let scripts = tx.scripts();
+
+            assert_eq!(
+                scripts.len(),
+                tx.native_scripts().len()
+                    + tx.plutus_v1_scripts().len()
+                    + tx.plutus_v2_scripts().len()
+                    + tx.plutus_v3_scripts().len()
+            );
+
+            for script in scripts {
+                match script.kind() {
+                    ScriptKind::Native => {
+                        let native = tx
+                            .native_scripts()
+                            .iter()
+                            .find(|x| x.original_hash() == script.hash())
+                            .unwrap();
+
+                        assert_eq!(script.as_native(), Some(native.deref()));
+                        assert!(script.as_plutus().is_none());
+                    }
+                    ScriptKind::PlutusV1 => {
+                        let plutus = tx
+                            .plutus_v1_scripts()
+                            .iter()
+                            .find(|x| x.compute_hash() == script.hash())
+                            .unwrap();
+
+                        assert_eq!(script.as_plutus(), Some(plutus.0.as_slice()));
+                        assert!(script.as_native().is_none());
+                    }
+                    x => panic!("unexpected script kind {x:?}"),
+                }
+
+                seen += 1;
+            }
+        }
+
+        assert!(seen > 0);
+    }
+}
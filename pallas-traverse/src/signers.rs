@@ -1,7 +1,9 @@
+use std::collections::BTreeSet;
+
 use pallas_crypto::hash::Hash;
 use pallas_primitives::alonzo;
 
-use crate::MultiEraSigners;
+use crate::{MultiEraSigners, SignerRequirements};
 
 impl Default for MultiEraSigners<'_> {
     fn default() -> Self {
@@ -35,3 +37,33 @@ impl MultiEraSigners<'_> {
         }
     }
 }
+
+impl SignerRequirements {
+    /// The union of all the groups, the set of keys a wallet must satisfy
+    pub fn all(&self) -> BTreeSet<Hash<28>> {
+        self.explicit
+            .iter()
+            .chain(self.inputs.iter())
+            .chain(self.certificates.iter())
+            .chain(self.withdrawals.iter())
+            .chain(self.voters.iter())
+            .copied()
+            .collect()
+    }
+
+    pub fn contains(&self, key: &Hash<28>) -> bool {
+        self.explicit.contains(key)
+            || self.inputs.contains(key)
+            || self.certificates.contains(key)
+            || self.withdrawals.contains(key)
+            || self.voters.contains(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.explicit.is_empty()
+            && self.inputs.is_empty()
+            && self.certificates.is_empty()
+            && self.withdrawals.is_empty()
+            && self.voters.is_empty()
+    }
+}
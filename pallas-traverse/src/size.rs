@@ -1,6 +1,46 @@
 use pallas_codec::utils::Nullable;
+use pallas_primitives::RationalNumber;
 
-use crate::{MultiEraBlock, MultiEraTx};
+use crate::{MultiEraBlock, MultiEraTx, UtxoResolver};
+
+/// Size of each tier used to price reference scripts (25 KiB)
+const REF_SCRIPT_TIER_SIZE: u128 = 25_600;
+
+/// Price of the reference scripts bytes following the Conway tiered schedule
+///
+/// The first 25 KiB are charged at `cost_per_byte`, and the price per byte is
+/// multiplied by 1.2 for each subsequent 25 KiB chunk. Rational arithmetic is
+/// used all the way through to match the ledger's rounding.
+///
+/// Returns `None` if the denominator of the cost is zero, or if the exact
+/// computation doesn't fit the integers used for it, which only happens for
+/// sizes (a bit over 1 MiB at mainnet prices) beyond what the protocol allows
+/// per tx.
+pub fn tiered_ref_script_fee(cost_per_byte: &RationalNumber, size: u64) -> Option<u64> {
+    let mut num = cost_per_byte.numerator as u128;
+    let mut den = cost_per_byte.denominator as u128;
+
+    if den == 0 {
+        return None;
+    }
+
+    // accumulated fee, expressed over the current denominator
+    let mut acc = 0u128;
+    let mut remaining = size as u128;
+
+    while remaining >= REF_SCRIPT_TIER_SIZE {
+        acc = acc.checked_add(REF_SCRIPT_TIER_SIZE.checked_mul(num)?)?;
+        remaining -= REF_SCRIPT_TIER_SIZE;
+
+        num = num.checked_mul(6)?;
+        den = den.checked_mul(5)?;
+        acc = acc.checked_mul(5)?;
+    }
+
+    let total = acc.checked_add(remaining.checked_mul(num)?)? / den;
+
+    u64::try_from(total).ok()
+}
 
 impl MultiEraTx<'_> {
     fn aux_data_size(&self) -> usize {
@@ -42,6 +82,81 @@ impl MultiEraTx<'_> {
     pub fn size(&self) -> usize {
         self.body_size() + self.witness_set_size() + self.aux_data_size()
     }
+
+    /// Size of the tx as measured by the ledger fee policy
+    ///
+    /// The ledger measures `[body, witness_set, aux_data / null]`, using the
+    /// original bytes of each component. The `is_valid` flag of Alonzo
+    /// onwards is left out of the computation, since blocks don't carry it
+    /// inside each tx. Byron txs measure `[tx, witnesses]`.
+    fn fee_size(&self) -> usize {
+        let aux_data_size = match self {
+            MultiEraTx::AlonzoCompatible(x, _) => match &x.auxiliary_data {
+                Nullable::Some(x) => x.raw_cbor().len(),
+                _ => 1,
+            },
+            MultiEraTx::Babbage(x) => match &x.auxiliary_data {
+                Nullable::Some(x) => x.raw_cbor().len(),
+                _ => 1,
+            },
+            MultiEraTx::Byron(_) => 0,
+            MultiEraTx::Conway(x) => match &x.auxiliary_data {
+                Nullable::Some(x) => x.raw_cbor().len(),
+                _ => 1,
+            },
+        };
+
+        // the outer array header, a single byte for 2 to 4 items
+        1 + self.body_size() + self.witness_set_size() + aux_data_size
+    }
+
+    /// Minimum fee required by the linear fee policy
+    ///
+    /// Computes `minfee_a * size + minfee_b` using the size the ledger
+    /// measures for the tx, see [`MultiEraTx::size`] for the size of its
+    /// components instead.
+    pub fn min_fee(&self, minfee_a: u64, minfee_b: u64) -> u64 {
+        let size = self.fee_size() as u64;
+
+        minfee_a * size + minfee_b
+    }
+
+    /// Minimum fee including the Conway cost of reference scripts
+    ///
+    /// Adds to `min_fee` the tiered price of every script held by the outputs
+    /// this tx spends or references. The resolver provides those outputs;
+    /// inputs that can't be resolved are assumed to carry no script.
+    ///
+    /// Returns `None` if the fee can't be computed, see
+    /// [`tiered_ref_script_fee`].
+    pub fn min_fee_with_ref_scripts<'r, R>(
+        &self,
+        minfee_a: u64,
+        minfee_b: u64,
+        ref_script_cost_per_byte: &RationalNumber,
+        resolver: &R,
+    ) -> Option<u64>
+    where
+        R: UtxoResolver<'r> + ?Sized,
+    {
+        let mut inputs = self.inputs();
+        inputs.extend(self.reference_inputs());
+        inputs.sort_by_key(|x| (*x.hash(), x.index()));
+        inputs.dedup_by_key(|x| (*x.hash(), x.index()));
+
+        let ref_scripts_size = inputs
+            .iter()
+            .filter_map(|x| resolver.resolve(x))
+            .filter_map(|x| x.script_ref())
+            .map(|x| crate::MultiEraScript::from(x).size() as u64)
+            .try_fold(0u64, u64::checked_add)?;
+
+        self.min_fee(minfee_a, minfee_b)
+            .checked_add(tiered_ref_script_fee(
+                ref_script_cost_per_byte,
+                ref_scripts_size,
+            )?)
+    }
 }
 
 impl MultiEraBlock<'_> {
@@ -57,3 +172,127 @@ impl MultiEraBlock<'_> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas_codec::{minicbor, utils::CborWrap};
+    use pallas_primitives::{conway, PlutusScript};
+
+    use super::*;
+    use crate::{Era, MultiEraInput, MultiEraOutput};
+
+    const COST_PER_BYTE: RationalNumber = RationalNumber {
+        numerator: 15,
+        denominator: 1,
+    };
+
+    #[test]
+    fn ref_script_fee_is_tiered() {
+        assert_eq!(tiered_ref_script_fee(&COST_PER_BYTE, 0), Some(0));
+        assert_eq!(tiered_ref_script_fee(&COST_PER_BYTE, 100), Some(1_500));
+        assert_eq!(tiered_ref_script_fee(&COST_PER_BYTE, 25_600), Some(384_000));
+        assert_eq!(tiered_ref_script_fee(&COST_PER_BYTE, 30_000), Some(463_200));
+        assert_eq!(tiered_ref_script_fee(&COST_PER_BYTE, 51_200), Some(844_800));
+    }
+
+    #[test]
+    fn ref_script_fee_doesnt_overflow() {
+        // the protocol max of ref scripts per block is still computed
+        assert!(tiered_ref_script_fee(&COST_PER_BYTE, 1 << 20).is_some());
+
+        assert_eq!(tiered_ref_script_fee(&COST_PER_BYTE, 5_000_000), None);
+        assert_eq!(tiered_ref_script_fee(&COST_PER_BYTE, u64::MAX), None);
+
+        let zero = RationalNumber {
+            numerator: 15,
+            denominator: 0,
+        };
+
+        assert_eq!(tiered_ref_script_fee(&zero, 100), None);
+    }
+
+    #[test]
+    fn declared_fee_covers_min_fee() {
+        for (era, tx) in [
+            (Era::Alonzo, include_str!("../../test_data/alonzo1.tx")),
+            (Era::Babbage, include_str!("../../test_data/babbage1.tx")),
+            (Era::Babbage, include_str!("../../test_data/babbage7.tx")),
+            (Era::Conway, include_str!("../../test_data/conway1.tx")),
+            (Era::Conway, include_str!("../../test_data/conway2.tx")),
+        ] {
+            let cbor = hex::decode(tx).unwrap();
+            let tx = MultiEraTx::decode_for_era(era, &cbor).unwrap();
+
+            let min_fee = tx.min_fee(44, 155_381);
+            assert!(min_fee > 155_381);
+            assert!(tx.fee().unwrap() >= min_fee);
+        }
+    }
+
+    #[test]
+    fn min_fee_matches_fees_paid_on_chain() {
+        // smallest gap between the declared fee and the min fee among the txs
+        // without scripts of each block. Most wallets pay the exact min fee,
+        // older ones padded the size by 4 bytes (176 lovelace).
+        for (block, slack) in [
+            (include_str!("../../test_data/shelley1.block"), 176),
+            (include_str!("../../test_data/allegra1.block"), 352),
+            (include_str!("../../test_data/mary1.block"), 176),
+            (include_str!("../../test_data/alonzo13.block"), 0),
+            (include_str!("../../test_data/alonzo2.block"), 0),
+            (include_str!("../../test_data/alonzo12.block"), 0),
+            (include_str!("../../test_data/babbage4.block"), 0),
+            (include_str!("../../test_data/babbage7.block"), 0),
+            (include_str!("../../test_data/conway2.block"), 0),
+        ] {
+            let cbor = hex::decode(block).unwrap();
+            let block = MultiEraBlock::decode(&cbor).unwrap();
+
+            let min_slack = block
+                .txs()
+                .iter()
+                .filter(|tx| tx.redeemers().is_empty())
+                .map(|tx| tx.fee().unwrap() as i64 - tx.min_fee(44, 155_381) as i64)
+                .min();
+
+            assert_eq!(min_slack, Some(slack), "era {}", block.era());
+        }
+    }
+
+    #[test]
+    fn ref_scripts_add_to_min_fee() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let script = PlutusScript::<3>(vec![0u8; 1_000].into());
+
+        let output = conway::TransactionOutput::PostAlonzo(conway::PostAlonzoTransactionOutput {
+            address: hex::decode("71".to_owned() + &"00".repeat(28))
+                .unwrap()
+                .into(),
+            value: conway::Value::Coin(1_000_000),
+            datum_option: None,
+            script_ref: Some(CborWrap(conway::ScriptRef::PlutusV3Script(script))),
+        });
+
+        let output_cbor = minicbor::to_vec(&output).unwrap();
+        let output = MultiEraOutput::decode(Era::Conway, &output_cbor).unwrap();
+
+        let base = tx.min_fee(44, 155_381);
+
+        assert_eq!(
+            tx.min_fee_with_ref_scripts(44, 155_381, &COST_PER_BYTE, &|_: &MultiEraInput| None),
+            Some(base)
+        );
+
+        // every spent and referenced input resolves to the same output
+        let count = tx.inputs().len() + tx.reference_inputs().len();
+
+        assert_eq!(
+            tx.min_fee_with_ref_scripts(44, 155_381, &COST_PER_BYTE, &|_: &MultiEraInput| Some(
+                output.clone()
+            )),
+            Some(base + 15_000 * count as u64)
+        );
+    }
+}
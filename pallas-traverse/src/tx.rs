@@ -11,10 +11,28 @@ use pallas_primitives::{
 
 use crate::{
     Era, Error, MultiEraCert, MultiEraInput, MultiEraMeta, MultiEraOutput, MultiEraPolicyAssets,
-    MultiEraProposal, MultiEraSigners, MultiEraTx, MultiEraUpdate, MultiEraWithdrawals,
-    OriginalHash,
+    MultiEraProposal, MultiEraScript, MultiEraSigners, MultiEraTx, MultiEraUpdate,
+    MultiEraWithdrawals, OriginalHash, UtxoResolver,
 };
 
+/// Sort key matching the ledger's `Ord` instance for reward accounts
+///
+/// The header byte of a reward account holds the network id in its low nibble
+/// and flags a script credential with bit 4.
+fn reward_account_order(account: &[u8]) -> (u8, bool, &[u8]) {
+    match account.split_first() {
+        Some((header, hash)) => (header & 0x0f, header & 0x10 == 0, hash),
+        None => (0, false, account),
+    }
+}
+
+fn sorted_set(mut raw: Vec<MultiEraInput>) -> Vec<MultiEraInput> {
+    raw.sort_by_key(|x| (*x.hash(), x.index()));
+    raw.dedup_by_key(|x| (*x.hash(), x.index()));
+
+    raw
+}
+
 impl<'b> MultiEraTx<'b> {
     pub fn from_byron(tx: &'b byron::MintedTxPayload<'b>) -> Self {
         Self::Byron(Box::new(Cow::Borrowed(tx)))
@@ -28,6 +46,17 @@ impl<'b> MultiEraTx<'b> {
         Self::Babbage(Box::new(Cow::Borrowed(tx)))
     }
 
+    /// Serialize the tx back to CBOR
+    ///
+    /// Body, witness set and auxiliary data are written from their retained
+    /// bytes, so for a tx decoded from a standalone payload this reproduces
+    /// the original bytes.
+    ///
+    /// Txs taken from a block never existed as a single payload on chain,
+    /// since blocks store bodies, witness sets and auxiliary data in separate
+    /// lists. For those, the output is the tx as it would be submitted, made
+    /// of the exact on-chain bytes of each part. Either way, the tx id of
+    /// the output matches [Self::hash].
     pub fn encode(&self) -> Vec<u8> {
         // to_vec is infallible
         match self {
@@ -103,6 +132,27 @@ impl<'b> MultiEraTx<'b> {
         }
     }
 
+    /// Original CBOR bytes of the tx body
+    ///
+    /// These are the exact bytes found on-chain (or in the payload provided to
+    /// `decode`), retained during decoding. A full tx doesn't have a single
+    /// contiguous encoding inside a block (body, witnesses and auxiliary data
+    /// live in separate block fields), so the body is the largest piece that
+    /// can be returned without copying.
+    pub fn raw_body_cbor(&self) -> &[u8] {
+        match self {
+            MultiEraTx::AlonzoCompatible(x, _) => x.transaction_body.raw_cbor(),
+            MultiEraTx::Babbage(x) => x.transaction_body.raw_cbor(),
+            MultiEraTx::Byron(x) => x.transaction.raw_cbor(),
+            MultiEraTx::Conway(x) => x.transaction_body.raw_cbor(),
+        }
+    }
+
+    /// The tx id, computed over the retained body bytes
+    ///
+    /// The hash is computed over `raw_body_cbor` instead of a re-encoding of
+    /// the decoded body, so it always matches the on-chain tx id, even for
+    /// bodies that use non-canonical CBOR. No re-encoding takes place.
     pub fn hash(&self) -> Hash<32> {
         match self {
             MultiEraTx::AlonzoCompatible(x, _) => x.transaction_body.original_hash(),
@@ -200,17 +250,34 @@ impl<'b> MultiEraTx<'b> {
 
     /// Return inputs as expected for processing
     ///
-    /// To process inputs we need a set (no duplicates) and lexicographical
-    /// order (hash#idx). This function will take the raw inputs and apply a
-    /// sort following these requirements.
+    /// To process inputs we need a set (no duplicates) and the same order used
+    /// by the ledger, which sorts by tx hash bytes first and then numerically
+    /// by output index. Spend redeemer indices point into this order.
     pub fn inputs_sorted_set(&self) -> Vec<MultiEraInput> {
-        let mut raw = self.inputs();
-        raw.sort_by_key(|x| x.lexicographical_key());
-        raw.dedup_by_key(|x| x.lexicographical_key());
+        sorted_set(self.inputs())
+    }
 
-        raw
+    /// Return collateral inputs as expected for processing
+    ///
+    /// Same ordering as [`MultiEraTx::inputs_sorted_set`]. No redeemer points
+    /// into the collateral, but the ledger consumes it in this order when
+    /// phase-2 validation fails.
+    pub fn collateral_sorted_set(&self) -> Vec<MultiEraInput> {
+        sorted_set(self.collateral())
     }
 
+    /// Return reference inputs as expected for processing
+    ///
+    /// Same ordering as [`MultiEraTx::inputs_sorted_set`]. This is the order in
+    /// which reference inputs are presented to scripts in the script context.
+    pub fn reference_inputs_sorted_set(&self) -> Vec<MultiEraInput> {
+        sorted_set(self.reference_inputs())
+    }
+
+    /// Return mints as expected for processing
+    ///
+    /// The ledger sorts minted assets by policy id bytes. Mint redeemer
+    /// indices point into this order.
     pub fn mints_sorted_set(&self) -> Vec<MultiEraPolicyAssets> {
         let mut raw = self.mints();
 
@@ -219,6 +286,31 @@ impl<'b> MultiEraTx<'b> {
         raw
     }
 
+    /// Return the signed quantity of every minted or burnt asset
+    ///
+    /// Positive quantities are mints and negative ones are burns. This unifies
+    /// the pre-Conway `i64` and the Conway `NonZeroInt` mint encodings.
+    pub fn mint_burn(&self) -> Vec<(alonzo::PolicyId, alonzo::AssetName, i64)> {
+        let mut out = vec![];
+
+        for policy in self.mints() {
+            for asset in policy.assets() {
+                if let Some(quantity) = asset.mint_coin() {
+                    out.push((*asset.policy(), asset.name().to_vec().into(), quantity));
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Return withdrawals as expected for processing
+    ///
+    /// The ledger sorts reward accounts by network, then by credential kind
+    /// (script credentials before key credentials) and finally by credential
+    /// hash. This differs from plain byte order of the encoded account, which
+    /// would place key credentials first. Withdrawal redeemer indices point
+    /// into this order.
     pub fn withdrawals_sorted_set(&self) -> Vec<(&[u8], u64)> {
         match self.withdrawals() {
             MultiEraWithdrawals::NotApplicable | MultiEraWithdrawals::Empty => {
@@ -227,16 +319,80 @@ impl<'b> MultiEraTx<'b> {
             MultiEraWithdrawals::AlonzoCompatible(x) => x
                 .iter()
                 .map(|(k, v)| (k.as_slice(), *v))
-                .sorted_by_key(|(k, _)| *k)
+                .sorted_by_key(|(k, _)| reward_account_order(k))
                 .collect(),
             MultiEraWithdrawals::Conway(x) => x
                 .iter()
                 .map(|(k, v)| (k.as_slice(), *v))
-                .sorted_by_key(|(k, _)| *k)
+                .sorted_by_key(|(k, _)| reward_account_order(k))
                 .collect(),
         }
     }
 
+    /// Return the inputs being spent paired with the outputs they point to
+    ///
+    /// Inputs that the resolver doesn't know about are paired with `None`.
+    pub fn resolve_inputs<'r, R>(
+        &self,
+        resolver: &R,
+    ) -> Vec<(MultiEraInput, Option<MultiEraOutput<'r>>)>
+    where
+        R: UtxoResolver<'r> + ?Sized,
+    {
+        self.inputs()
+            .into_iter()
+            .map(|input| {
+                let output = resolver.resolve(&input);
+                (input, output)
+            })
+            .collect()
+    }
+
+    /// Return every input of the tx paired with the outputs they point to
+    ///
+    /// Besides the inputs being spent, this includes the collateral and the
+    /// reference inputs. Inputs appearing more than once are resolved only
+    /// once.
+    pub fn resolve_all<'r, R>(
+        &self,
+        resolver: &R,
+    ) -> Vec<(MultiEraInput, Option<MultiEraOutput<'r>>)>
+    where
+        R: UtxoResolver<'r> + ?Sized,
+    {
+        let mut seen = HashSet::new();
+
+        self.inputs()
+            .into_iter()
+            .chain(self.collateral())
+            .chain(self.reference_inputs())
+            .filter(|input| seen.insert(input.output_ref()))
+            .map(|input| {
+                let output = resolver.resolve(&input);
+                (input, output)
+            })
+            .collect()
+    }
+
+    /// Return the scripts made available through reference inputs
+    ///
+    /// Babbage and later txs can use scripts held in the `script_ref` field of
+    /// the outputs pointed to by their reference inputs instead of carrying
+    /// them in the witness set. Since those outputs aren't part of the tx, the
+    /// caller needs to provide a resolver; reference inputs that can't be
+    /// resolved or that don't hold a script are skipped.
+    pub fn resolved_reference_scripts<'r, R>(&self, resolver: &R) -> Vec<MultiEraScript<'r>>
+    where
+        R: UtxoResolver<'r> + ?Sized,
+    {
+        self.reference_inputs()
+            .iter()
+            .filter_map(|x| resolver.resolve(x))
+            .filter_map(|output| output.script_ref())
+            .map(MultiEraScript::from)
+            .collect()
+    }
+
     /// Return the transaction reference inputs
     ///
     /// NOTE: It is possible for this to return duplicates. See
@@ -524,22 +680,10 @@ impl<'b> MultiEraTx<'b> {
 
     pub(crate) fn aux_data(&self) -> Option<&KeepRaw<'_, alonzo::AuxiliaryData>> {
         match self {
-            MultiEraTx::AlonzoCompatible(x, _) => match &x.auxiliary_data {
-                pallas_codec::utils::Nullable::Some(x) => Some(x),
-                pallas_codec::utils::Nullable::Null => None,
-                pallas_codec::utils::Nullable::Undefined => None,
-            },
-            MultiEraTx::Babbage(x) => match &x.auxiliary_data {
-                pallas_codec::utils::Nullable::Some(x) => Some(x),
-                pallas_codec::utils::Nullable::Null => None,
-                pallas_codec::utils::Nullable::Undefined => None,
-            },
+            MultiEraTx::AlonzoCompatible(x, _) => x.auxiliary_data.as_option(),
+            MultiEraTx::Babbage(x) => x.auxiliary_data.as_option(),
             MultiEraTx::Byron(_) => None,
-            MultiEraTx::Conway(x) => match &x.auxiliary_data {
-                pallas_codec::utils::Nullable::Some(x) => Some(x),
-                pallas_codec::utils::Nullable::Null => None,
-                pallas_codec::utils::Nullable::Undefined => None,
-            },
+            MultiEraTx::Conway(x) => x.auxiliary_data.as_option(),
         }
     }
 
@@ -560,6 +704,16 @@ impl<'b> MultiEraTx<'b> {
         }
     }
 
+    /// Finds the metadatum for a given label, if the tx has one
+    pub fn metadatum(&self, label: alonzo::MetadatumLabel) -> Option<&alonzo::Metadatum> {
+        self.metadata().find(label)
+    }
+
+    /// The labels of the metadata of the tx, in their original order
+    pub fn metadata_labels(&self) -> Vec<alonzo::MetadatumLabel> {
+        self.metadata().labels()
+    }
+
     pub fn required_signers(&self) -> MultiEraSigners {
         match self {
             MultiEraTx::AlonzoCompatible(x, _) => x
@@ -639,3 +793,324 @@ impl<'b> MultiEraTx<'b> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas_codec::utils::{CborWrap, NonEmptyKeyValuePairs, NonEmptySet, Set};
+    use pallas_primitives::PlutusScript;
+
+    use super::*;
+
+    fn conway1() -> conway::Tx {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+        tx.as_conway().unwrap().clone().into()
+    }
+
+    #[test]
+    fn inputs_sort_by_hash_then_numeric_index() {
+        let cbor = hex::decode(include_str!("../../test_data/conway2.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let sorted = tx.inputs_sorted_set();
+        assert_eq!(sorted.len(), tx.inputs().len());
+        assert!(sorted
+            .windows(2)
+            .all(|w| (w[0].hash(), w[0].index()) < (w[1].hash(), w[1].index())));
+
+        let mut owned = conway1();
+        let hash = owned.transaction_body.inputs[0].transaction_id;
+
+        owned.transaction_body.inputs = Set::from(
+            [10, 2, 10]
+                .into_iter()
+                .map(|index| conway::TransactionInput {
+                    transaction_id: hash,
+                    index,
+                })
+                .collect::<Vec<_>>(),
+        );
+
+        let cbor = minicbor::to_vec(&owned).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let indexes: Vec<_> = tx.inputs_sorted_set().iter().map(|x| x.index()).collect();
+        assert_eq!(indexes, vec![2, 10]);
+    }
+
+    #[test]
+    fn collateral_and_reference_inputs_sort_like_inputs() {
+        let mut owned = conway1();
+        let hash = owned.transaction_body.inputs[0].transaction_id;
+
+        let inputs = |indexes: [u64; 3]| {
+            NonEmptySet::from_vec(
+                indexes
+                    .into_iter()
+                    .map(|index| conway::TransactionInput {
+                        transaction_id: hash,
+                        index,
+                    })
+                    .collect(),
+            )
+        };
+
+        owned.transaction_body.collateral = inputs([7, 1, 7]);
+        owned.transaction_body.reference_inputs = inputs([3, 12, 0]);
+
+        let cbor = minicbor::to_vec(&owned).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let indexes =
+            |inputs: Vec<MultiEraInput>| -> Vec<_> { inputs.iter().map(|x| x.index()).collect() };
+
+        assert_eq!(indexes(tx.collateral_sorted_set()), vec![1, 7]);
+        assert_eq!(indexes(tx.reference_inputs_sorted_set()), vec![0, 3, 12]);
+    }
+
+    #[test]
+    fn withdrawals_sort_script_credentials_first() {
+        let mut owned = conway1();
+
+        let key_low = hex::decode("e1".to_owned() + &"00".repeat(28)).unwrap();
+        let key_high = hex::decode("e1".to_owned() + &"ff".repeat(28)).unwrap();
+        let script = hex::decode("f1".to_owned() + &"88".repeat(28)).unwrap();
+        let testnet = hex::decode("f0".to_owned() + &"ff".repeat(28)).unwrap();
+
+        let pairs = [&key_high, &script, &key_low, &testnet]
+            .into_iter()
+            .map(|k| (k.clone().into(), 1))
+            .collect();
+
+        owned.transaction_body.withdrawals = Some(NonEmptyKeyValuePairs::Def(pairs));
+
+        let cbor = minicbor::to_vec(&owned).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let accounts: Vec<_> = tx
+            .withdrawals_sorted_set()
+            .into_iter()
+            .map(|(k, _)| k.to_vec())
+            .collect();
+
+        assert_eq!(accounts, vec![testnet, script, key_low, key_high]);
+    }
+
+    #[test]
+    fn mint_burn_is_signed() {
+        let mut owned = conway1();
+
+        let policy: Hash<28> = "a0028f350aaabe0545fdcb56b039bfb08e4bb4d8c4d7c3c7d481c235"
+            .parse()
+            .unwrap();
+
+        let assets = vec![
+            (
+                b"minted".to_vec().into(),
+                conway::NonZeroInt::try_from(5).unwrap(),
+            ),
+            (
+                b"burnt".to_vec().into(),
+                conway::NonZeroInt::try_from(-3).unwrap(),
+            ),
+        ];
+
+        owned.transaction_body.mint = Some(NonEmptyKeyValuePairs::Def(vec![(
+            policy,
+            NonEmptyKeyValuePairs::Def(assets),
+        )]));
+
+        let cbor = minicbor::to_vec(&owned).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        assert_eq!(
+            tx.mint_burn(),
+            vec![
+                (policy, b"minted".to_vec().into(), 5),
+                (policy, b"burnt".to_vec().into(), -3),
+            ]
+        );
+
+        let cbor = hex::decode(include_str!("../../test_data/babbage5.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Babbage, &cbor).unwrap();
+
+        let flat: i64 = tx.mint_burn().iter().map(|(_, _, q)| q).sum();
+        let grouped: i128 = tx
+            .mints()
+            .iter()
+            .flat_map(|p| p.assets())
+            .map(|a| a.any_coin())
+            .sum();
+
+        assert_eq!(flat as i128, grouped);
+        assert!(!tx.mint_burn().is_empty());
+    }
+
+    #[test]
+    fn inputs_are_resolved_from_a_store() {
+        struct Store<'a>(std::collections::HashMap<crate::OutputRef, MultiEraOutput<'a>>);
+
+        impl<'a> UtxoResolver<'a> for Store<'a> {
+            fn resolve(&self, input: &MultiEraInput) -> Option<MultiEraOutput<'a>> {
+                self.0.get(&input.output_ref()).cloned()
+            }
+        }
+
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let output = conway::TransactionOutput::PostAlonzo(conway::PostAlonzoTransactionOutput {
+            address: hex::decode("61".to_owned() + &"00".repeat(28))
+                .unwrap()
+                .into(),
+            value: conway::Value::Coin(1_000_000),
+            datum_option: None,
+            script_ref: None,
+        });
+
+        let output_cbor = minicbor::to_vec(&output).unwrap();
+        let output = MultiEraOutput::decode(Era::Conway, &output_cbor).unwrap();
+
+        let inputs = tx.inputs();
+        let store = Store([(inputs[0].output_ref(), output.clone())].into());
+
+        let resolved = tx.resolve_inputs(&store);
+        assert_eq!(resolved.len(), inputs.len());
+        assert_eq!(resolved[0].1, Some(output));
+        assert!(resolved[1..].iter().all(|(_, x)| x.is_none()));
+
+        let all = tx.resolve_all(&store);
+        assert_eq!(all.iter().filter(|(_, x)| x.is_some()).count(), 1);
+
+        let unique: HashSet<_> = all.iter().map(|(x, _)| x.output_ref()).collect();
+        assert_eq!(unique.len(), all.len());
+        assert!(all.len() >= inputs.len() + tx.collateral().len());
+    }
+
+    #[test]
+    fn reference_scripts_are_resolved() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        let script = PlutusScript::<2>(
+            hex::decode("4e4d01000033222220051200120011")
+                .unwrap()
+                .into(),
+        );
+
+        let output = conway::TransactionOutput::PostAlonzo(conway::PostAlonzoTransactionOutput {
+            address: hex::decode("71".to_owned() + &"00".repeat(28))
+                .unwrap()
+                .into(),
+            value: conway::Value::Coin(1_000_000),
+            datum_option: None,
+            script_ref: Some(CborWrap(conway::ScriptRef::PlutusV2Script(script.clone()))),
+        });
+
+        let output_cbor = minicbor::to_vec(&output).unwrap();
+        let output = MultiEraOutput::decode(Era::Conway, &output_cbor).unwrap();
+
+        let refs = tx.reference_inputs();
+        assert!(refs.len() > 1);
+
+        let scripts = tx.resolved_reference_scripts(&|input: &MultiEraInput| {
+            (input.output_ref() == refs[0].output_ref()).then(|| output.clone())
+        });
+
+        assert_eq!(scripts.len(), 1);
+
+        match &scripts[0] {
+            MultiEraScript::PlutusV2(x) => assert_eq!(x.as_ref(), &script),
+            x => panic!("unexpected script {x:?}"),
+        }
+
+        assert!(tx
+            .resolved_reference_scripts(&|_: &MultiEraInput| None)
+            .is_empty());
+    }
+
+    #[test]
+    fn hash_uses_retained_bytes() {
+        for (era, tx) in [
+            (Era::Byron, include_str!("../../test_data/byron1.tx")),
+            (Era::Alonzo, include_str!("../../test_data/alonzo1.tx")),
+            (Era::Babbage, include_str!("../../test_data/babbage1.tx")),
+            (Era::Conway, include_str!("../../test_data/conway1.tx")),
+        ] {
+            let cbor = hex::decode(tx).unwrap();
+            let tx = MultiEraTx::decode_for_era(era, &cbor).unwrap();
+
+            let expected = pallas_crypto::hash::Hasher::<256>::hash(tx.raw_body_cbor());
+            assert_eq!(tx.hash(), expected);
+
+            assert_eq!(tx.encode(), cbor);
+        }
+    }
+
+    #[test]
+    fn block_txs_encode_from_on_chain_parts() {
+        let cbor = hex::decode(include_str!("../../test_data/alonzo27.block")).unwrap();
+        let block = crate::MultiEraBlock::decode(&cbor).unwrap();
+
+        for tx in block.txs() {
+            let encoded = tx.encode();
+            let decoded = MultiEraTx::decode_for_era(tx.era(), &encoded).unwrap();
+
+            assert_eq!(decoded.hash(), tx.hash());
+            assert_eq!(decoded.raw_body_cbor(), tx.raw_body_cbor());
+            assert_eq!(decoded.encode(), encoded);
+        }
+    }
+
+    #[test]
+    fn mints_sort_by_policy() {
+        let cbor = hex::decode(include_str!("../../test_data/alonzo27.block")).unwrap();
+        let block = crate::MultiEraBlock::decode(&cbor).unwrap();
+
+        for tx in block.txs() {
+            let sorted = tx.mints_sorted_set();
+            assert_eq!(sorted.len(), tx.mints().len());
+            assert!(sorted.windows(2).all(|w| w[0].policy() < w[1].policy()));
+
+            for redeemer in tx.redeemers() {
+                if redeemer.tag() == conway::RedeemerTag::Mint {
+                    assert!((redeemer.index() as usize) < sorted.len());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn metadatum_lookup_by_label() {
+        let blocks = [
+            include_str!("../../test_data/mary1.block"),
+            include_str!("../../test_data/alonzo1.block"),
+            include_str!("../../test_data/babbage9.block"),
+            include_str!("../../test_data/conway1.block"),
+        ];
+
+        let mut found = 0;
+
+        for block_str in blocks {
+            let cbor = hex::decode(block_str).unwrap();
+            let block = crate::MultiEraBlock::decode(&cbor).unwrap();
+
+            for tx in block.txs() {
+                let metadata = tx.metadata();
+                let all: Vec<(u64, &alonzo::Metadatum)> = metadata.collect();
+                let labels: Vec<_> = all.iter().map(|(k, _)| *k).collect();
+
+                assert_eq!(tx.metadata_labels(), labels);
+
+                for (label, value) in all {
+                    assert_eq!(tx.metadatum(label), Some(value));
+                    found += 1;
+                }
+
+                assert!(tx.metadatum(u64::MAX).is_none());
+            }
+        }
+
+        assert!(found > 0);
+    }
+}
@@ -54,7 +54,188 @@ pub type ProtocolVersion = alonzo::ProtocolVersion;
 pub type PoolVotingThresholds = conway::PoolVotingThresholds;
 pub type DRepVotingThresholds = conway::DRepVotingThresholds;
 
-use crate::{Era, MultiEraUpdate};
+use crate::{Era, Error, MultiEraUpdate};
+
+macro_rules! protocol_params {
+    (
+        $(#[$meta:meta])*
+        $name:ident: $era:ident,
+        [$($field:ident: $type_:ty),* $(,)?],
+        optional [$($opt_field:ident: $opt_type:ty),* $(,)?]
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name {
+            $(pub $field: $type_,)*
+            $(pub $opt_field: Option<$opt_type>,)*
+        }
+
+        impl $name {
+            /// Replaces each parameter present in the proposal
+            fn overlay(&self, proposal: &$era::ProtocolParamUpdate) -> Self {
+                Self {
+                    $(
+                        $field: proposal
+                            .$field
+                            .clone()
+                            .unwrap_or_else(|| self.$field.clone()),
+                    )*
+                    $(
+                        $opt_field: proposal
+                            .$opt_field
+                            .clone()
+                            .or_else(|| self.$opt_field.clone()),
+                    )*
+                }
+            }
+        }
+    };
+}
+
+protocol_params!(
+    /// Protocol parameters from Shelley to Alonzo
+    ///
+    /// Parameters introduced by Alonzo are `None` on earlier eras. The Shelley
+    /// `min_utxo_value` is not part of the Alonzo-compatible update model and
+    /// so it's not tracked here.
+    AlonzoCompatibleProtocolParams: alonzo,
+    [
+        minfee_a: u32,
+        minfee_b: u32,
+        max_block_body_size: u32,
+        max_transaction_size: u32,
+        max_block_header_size: u32,
+        key_deposit: u64,
+        pool_deposit: u64,
+        maximum_epoch: u64,
+        desired_number_of_stake_pools: u32,
+        pool_pledge_influence: RationalNumber,
+        expansion_rate: UnitInterval,
+        treasury_growth_rate: UnitInterval,
+        decentralization_constant: UnitInterval,
+        extra_entropy: Nonce,
+        protocol_version: ProtocolVersion,
+        min_pool_cost: u64,
+    ],
+    optional [
+        ada_per_utxo_byte: u64,
+        cost_models_for_script_languages: AlonzoCostModels,
+        execution_costs: ExUnitPrices,
+        max_tx_ex_units: ExUnits,
+        max_block_ex_units: ExUnits,
+        max_value_size: u32,
+        collateral_percentage: u32,
+        max_collateral_inputs: u32,
+    ]
+);
+
+protocol_params!(
+    /// Protocol parameters of the Babbage era
+    BabbageProtocolParams: babbage,
+    [
+        minfee_a: u32,
+        minfee_b: u32,
+        max_block_body_size: u32,
+        max_transaction_size: u32,
+        max_block_header_size: u32,
+        key_deposit: u64,
+        pool_deposit: u64,
+        maximum_epoch: u64,
+        desired_number_of_stake_pools: u32,
+        pool_pledge_influence: RationalNumber,
+        expansion_rate: UnitInterval,
+        treasury_growth_rate: UnitInterval,
+        protocol_version: ProtocolVersion,
+        min_pool_cost: u64,
+        ada_per_utxo_byte: u64,
+        cost_models_for_script_languages: BabbageCostModels,
+        execution_costs: ExUnitPrices,
+        max_tx_ex_units: ExUnits,
+        max_block_ex_units: ExUnits,
+        max_value_size: u32,
+        collateral_percentage: u32,
+        max_collateral_inputs: u32,
+    ],
+    optional []
+);
+
+protocol_params!(
+    /// Protocol parameters of the Conway era
+    ///
+    /// The protocol version isn't part of Conway proposals, hard forks are
+    /// enacted through governance actions instead.
+    ConwayProtocolParams: conway,
+    [
+        minfee_a: u64,
+        minfee_b: u64,
+        max_block_body_size: u64,
+        max_transaction_size: u64,
+        max_block_header_size: u64,
+        key_deposit: u64,
+        pool_deposit: u64,
+        maximum_epoch: u64,
+        desired_number_of_stake_pools: u64,
+        pool_pledge_influence: RationalNumber,
+        expansion_rate: UnitInterval,
+        treasury_growth_rate: UnitInterval,
+        min_pool_cost: u64,
+        ada_per_utxo_byte: u64,
+        cost_models_for_script_languages: ConwayCostModels,
+        execution_costs: conway::ExUnitPrices,
+        max_tx_ex_units: ExUnits,
+        max_block_ex_units: ExUnits,
+        max_value_size: u64,
+        collateral_percentage: u64,
+        max_collateral_inputs: u64,
+        pool_voting_thresholds: PoolVotingThresholds,
+        drep_voting_thresholds: DRepVotingThresholds,
+        min_committee_size: u64,
+        committee_term_limit: u64,
+        governance_action_validity_period: u64,
+        governance_action_deposit: u64,
+        drep_deposit: u64,
+        drep_inactivity_period: u64,
+        minfee_refscript_cost_per_byte: UnitInterval,
+    ],
+    optional []
+);
+
+/// The full set of protocol parameters of a given era
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MultiEraProtocolParam {
+    AlonzoCompatible(Box<AlonzoCompatibleProtocolParams>),
+    Babbage(Box<BabbageProtocolParams>),
+    Conway(Box<ConwayProtocolParams>),
+}
+
+/// The proposal submitted by at least `quorum` genesis delegates, if any
+///
+/// Mirrors the ledger: only the latest proposal of each delegate counts, and
+/// the update is discarded unless exactly one distinct proposal reaches the
+/// quorum.
+fn voted_proposal<K: PartialEq, P: PartialEq>(proposals: &[(K, P)], quorum: usize) -> Option<&P> {
+    let latest = proposals
+        .iter()
+        .enumerate()
+        .filter(|(i, (key, _))| !proposals[i + 1..].iter().any(|(x, _)| x == key))
+        .map(|(_, (_, proposal))| proposal);
+
+    let mut tally: Vec<(&P, usize)> = vec![];
+
+    for proposal in latest {
+        match tally.iter_mut().find(|(x, _)| *x == proposal) {
+            Some((_, votes)) => *votes += 1,
+            None => tally.push((proposal, 1)),
+        }
+    }
+
+    let mut consensus = tally.into_iter().filter(|(_, votes)| *votes >= quorum);
+
+    match (consensus.next(), consensus.next()) {
+        (Some((proposal, _)), None) => Some(proposal),
+        _ => None,
+    }
+}
 
 impl<'b> MultiEraUpdate<'b> {
     pub fn decode_for_era(era: Era, cbor: &[u8]) -> Result<Self, minicbor::decode::Error> {
@@ -138,6 +319,54 @@ impl<'b> MultiEraUpdate<'b> {
         }
     }
 
+    /// Applies the proposed parameters on top of the current ones
+    ///
+    /// Follows the ledger rules for adopting an update: the proposal must be
+    /// submitted, identical, by at least `quorum` genesis delegates (the
+    /// `updateQuorum` of the Shelley genesis). If no proposal reaches the
+    /// quorum, the current params are returned unchanged. Each parameter
+    /// present in the adopted proposal replaces the current value, the rest
+    /// are kept as-is.
+    ///
+    /// The update should hold every proposal submitted during its epoch, and
+    /// the resulting params only take effect at the start of the following
+    /// epoch; deciding when to apply them is up to the caller. Byron updates,
+    /// or updates for an era different from the one of the current params,
+    /// return an error.
+    pub fn apply_to(
+        &self,
+        current: &MultiEraProtocolParam,
+        quorum: usize,
+    ) -> Result<MultiEraProtocolParam, Error> {
+        match (self, current) {
+            (MultiEraUpdate::AlonzoCompatible(x), MultiEraProtocolParam::AlonzoCompatible(c)) => {
+                let params = match voted_proposal(&x.proposed_protocol_parameter_updates, quorum) {
+                    Some(proposal) => c.overlay(proposal),
+                    None => c.as_ref().clone(),
+                };
+
+                Ok(MultiEraProtocolParam::AlonzoCompatible(Box::new(params)))
+            }
+            (MultiEraUpdate::Babbage(x), MultiEraProtocolParam::Babbage(c)) => {
+                let params = match voted_proposal(&x.proposed_protocol_parameter_updates, quorum) {
+                    Some(proposal) => c.overlay(proposal),
+                    None => c.as_ref().clone(),
+                };
+
+                Ok(MultiEraProtocolParam::Babbage(Box::new(params)))
+            }
+            (MultiEraUpdate::Conway(x), MultiEraProtocolParam::Conway(c)) => {
+                let params = match voted_proposal(&x.proposed_protocol_parameter_updates, quorum) {
+                    Some(proposal) => c.overlay(proposal),
+                    None => c.as_ref().clone(),
+                };
+
+                Ok(MultiEraProtocolParam::Conway(Box::new(params)))
+            }
+            _ => Err(Error::UpdateEraMismatch),
+        }
+    }
+
     pub fn byron_proposed_fee_policy(&self) -> Option<byron::TxFeePol> {
         match self {
             MultiEraUpdate::Byron(_, x) => {
@@ -263,3 +492,142 @@ impl<'b> MultiEraUpdate<'b> {
 
     param_boilerplate!(minfee_refscript_cost_per_byte: UnitInterval, [Conway]);
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas_codec::utils::KeyValuePairs;
+
+    use super::*;
+
+    fn alonzo_params() -> AlonzoCompatibleProtocolParams {
+        AlonzoCompatibleProtocolParams {
+            minfee_a: 44,
+            minfee_b: 155381,
+            max_block_body_size: 65536,
+            max_transaction_size: 16384,
+            max_block_header_size: 1100,
+            key_deposit: 2000000,
+            pool_deposit: 500000000,
+            maximum_epoch: 18,
+            desired_number_of_stake_pools: 500,
+            pool_pledge_influence: RationalNumber {
+                numerator: 3,
+                denominator: 10,
+            },
+            expansion_rate: RationalNumber {
+                numerator: 3,
+                denominator: 1000,
+            },
+            treasury_growth_rate: RationalNumber {
+                numerator: 2,
+                denominator: 10,
+            },
+            decentralization_constant: RationalNumber {
+                numerator: 0,
+                denominator: 1,
+            },
+            extra_entropy: Nonce {
+                variant: pallas_primitives::NonceVariant::NeutralNonce,
+                hash: None,
+            },
+            protocol_version: (6, 0),
+            min_pool_cost: 340000000,
+            ada_per_utxo_byte: Some(34482),
+            cost_models_for_script_languages: None,
+            execution_costs: None,
+            max_tx_ex_units: None,
+            max_block_ex_units: None,
+            max_value_size: Some(5000),
+            collateral_percentage: Some(150),
+            max_collateral_inputs: Some(3),
+        }
+    }
+
+    fn alonzo_update(proposals: Vec<(u8, &str)>) -> alonzo::Update {
+        let proposals = proposals
+            .into_iter()
+            .map(|(delegate, cbor)| {
+                let cbor = hex::decode(cbor).unwrap();
+                (vec![delegate; 28].into(), minicbor::decode(&cbor).unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        alonzo::Update {
+            proposed_protocol_parameter_updates: KeyValuePairs::from(proposals),
+            epoch: 290,
+        }
+    }
+
+    // {0: 45, 14: [7, 0]}
+    const PROPOSAL: &str = "a200182d0e820700";
+
+    // {0: 46}
+    const OTHER_PROPOSAL: &str = "a100182e";
+
+    #[test]
+    fn proposals_reaching_quorum_are_applied() {
+        let current = MultiEraProtocolParam::AlonzoCompatible(Box::new(alonzo_params()));
+
+        let update = alonzo_update((0..5).map(|x| (x, PROPOSAL)).collect());
+        let update = MultiEraUpdate::from_alonzo_compatible(&update);
+
+        let MultiEraProtocolParam::AlonzoCompatible(next) = update.apply_to(&current, 5).unwrap()
+        else {
+            panic!("unexpected era");
+        };
+
+        assert_eq!(next.minfee_a, 45);
+        assert_eq!(next.minfee_b, 155381);
+        assert_eq!(next.protocol_version, (7, 0));
+        assert_eq!(next.max_tx_ex_units, None);
+
+        // a single delegate short of the quorum leaves the params unchanged
+        assert_eq!(update.apply_to(&current, 6).unwrap(), current);
+    }
+
+    #[test]
+    fn proposals_below_quorum_are_ignored() {
+        let current = MultiEraProtocolParam::AlonzoCompatible(Box::new(alonzo_params()));
+
+        // four identical proposals, a different one and a delegate that
+        // replaced its proposal with the other one
+        let update = alonzo_update(vec![
+            (0, PROPOSAL),
+            (1, PROPOSAL),
+            (2, PROPOSAL),
+            (3, PROPOSAL),
+            (4, OTHER_PROPOSAL),
+            (5, PROPOSAL),
+            (5, OTHER_PROPOSAL),
+        ]);
+        let update = MultiEraUpdate::from_alonzo_compatible(&update);
+
+        assert_eq!(update.apply_to(&current, 5).unwrap(), current);
+
+        // with a low quorum, both proposals qualify and none is adopted
+        assert_eq!(update.apply_to(&current, 2).unwrap(), current);
+
+        let MultiEraProtocolParam::AlonzoCompatible(next) = update.apply_to(&current, 4).unwrap()
+        else {
+            panic!("unexpected era");
+        };
+
+        assert_eq!(next.minfee_a, 45);
+    }
+
+    #[test]
+    fn updates_for_other_eras_are_rejected() {
+        let current = MultiEraProtocolParam::AlonzoCompatible(Box::new(alonzo_params()));
+
+        let update = babbage::Update {
+            proposed_protocol_parameter_updates: KeyValuePairs::from(vec![]),
+            epoch: 400,
+        };
+        let update = MultiEraUpdate::from_babbage(&update);
+
+        assert!(matches!(
+            update.apply_to(&current, 5),
+            Err(Error::UpdateEraMismatch)
+        ));
+    }
+}
@@ -98,4 +98,86 @@ impl MultiEraValue<'_> {
             },
         }
     }
+
+    /// Native assets in the output as a flat list
+    ///
+    /// Same as `assets` but without the grouping by policy; each item is a
+    /// tuple of policy id, asset name and quantity.
+    pub fn assets_flat(&self) -> impl Iterator<Item = (alonzo::PolicyId, alonzo::AssetName, u64)> {
+        let flat: Vec<_> = match self {
+            Self::Byron(_) => vec![],
+            Self::AlonzoCompatible(x) => match x.deref() {
+                alonzo::Value::Coin(_) => vec![],
+                alonzo::Value::Multiasset(_, x) => x
+                    .iter()
+                    .flat_map(|(p, v)| v.iter().map(|(k, v)| (*p, k.clone(), *v)))
+                    .collect(),
+            },
+            Self::Conway(x) => match x.deref() {
+                conway::Value::Coin(_) => vec![],
+                conway::Value::Multiasset(_, x) => x
+                    .iter()
+                    .flat_map(|(p, v)| v.iter().map(|(k, v)| (*p, k.clone(), u64::from(v))))
+                    .collect(),
+            },
+        };
+
+        flat.into_iter()
+    }
+
+    /// Number of distinct native assets in the output
+    pub fn asset_count(&self) -> usize {
+        match self {
+            Self::Byron(_) => 0,
+            Self::AlonzoCompatible(x) => match x.deref() {
+                alonzo::Value::Coin(_) => 0,
+                alonzo::Value::Multiasset(_, x) => x.iter().map(|(_, v)| v.len()).sum(),
+            },
+            Self::Conway(x) => match x.deref() {
+                conway::Value::Coin(_) => 0,
+                conway::Value::Multiasset(_, x) => x.iter().map(|(_, v)| v.len()).sum(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Era, MultiEraTx};
+
+    #[test]
+    fn flat_assets_match_grouped_assets() {
+        let mut total = 0;
+
+        for (era, tx) in [
+            (Era::Alonzo, include_str!("../../test_data/alonzo1.tx")),
+            (Era::Babbage, include_str!("../../test_data/babbage1.tx")),
+            (Era::Conway, include_str!("../../test_data/conway1.tx")),
+        ] {
+            let cbor = hex::decode(tx).unwrap();
+            let tx = MultiEraTx::decode_for_era(era, &cbor).unwrap();
+
+            for output in tx.outputs() {
+                let value = output.value();
+
+                let grouped: Vec<_> = value
+                    .assets()
+                    .iter()
+                    .flat_map(|p| p.assets())
+                    .map(|a| {
+                        let name = a.name().to_vec().into();
+                        (*a.policy(), name, a.output_coin().unwrap())
+                    })
+                    .collect();
+
+                let flat: Vec<_> = value.assets_flat().collect();
+
+                assert_eq!(flat, grouped);
+                assert_eq!(value.asset_count(), flat.len());
+                total += flat.len();
+            }
+        }
+
+        assert!(total > 0);
+    }
 }
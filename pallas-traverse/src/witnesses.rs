@@ -4,7 +4,15 @@ use pallas_primitives::{
     conway, Hash, PlutusData, PlutusScript,
 };
 
-use crate::{MultiEraRedeemer, MultiEraTx, OriginalHash as _};
+use std::borrow::Cow;
+
+use pallas_addresses::{Address, ShelleyPaymentPart};
+use pallas_crypto::hash::Hasher;
+
+use crate::{
+    MultiEraInput, MultiEraRedeemer, MultiEraScript, MultiEraTx, MultiEraWithdrawals,
+    OriginalHash as _, SignerRequirements, UtxoResolver,
+};
 
 impl<'b> MultiEraTx<'b> {
     pub fn vkey_witnesses(&self) -> &[VKeyWitness] {
@@ -133,6 +141,11 @@ impl<'b> MultiEraTx<'b> {
             .find(|x| x.original_hash() == *hash)
     }
 
+    /// All the redeemers of the tx, regardless of era
+    ///
+    /// Conway txs can carry redeemers either in the legacy list form or in the
+    /// new map form, both are returned in the order they appear in the
+    /// witness set.
     pub fn redeemers(&self) -> Vec<MultiEraRedeemer> {
         match self {
             Self::Byron(_) => vec![],
@@ -164,12 +177,139 @@ impl<'b> MultiEraTx<'b> {
         }
     }
 
+    /// Return every script in the witness set, regardless of its kind
+    ///
+    /// Scripts are listed by kind: native first, followed by Plutus V1, V2
+    /// and V3. Scripts provided through reference inputs are not included,
+    /// see `resolved_reference_scripts` for those.
+    pub fn scripts(&self) -> Vec<MultiEraScript> {
+        let native = self
+            .native_scripts()
+            .iter()
+            .map(|x| MultiEraScript::Native(Box::new(Cow::Borrowed(x))));
+
+        let v1 = self
+            .plutus_v1_scripts()
+            .iter()
+            .map(|x| MultiEraScript::PlutusV1(Cow::Borrowed(x)));
+
+        let v2 = self
+            .plutus_v2_scripts()
+            .iter()
+            .map(|x| MultiEraScript::PlutusV2(Cow::Borrowed(x)));
+
+        let v3 = self
+            .plutus_v3_scripts()
+            .iter()
+            .map(|x| MultiEraScript::PlutusV3(Cow::Borrowed(x)));
+
+        native.chain(v1).chain(v2).chain(v3).collect()
+    }
+
+    /// Return the key hashes that must sign this tx, grouped by what requires
+    /// them
+    ///
+    /// Required keys come from the payment credentials of the spent and
+    /// collateral outputs, the key-based reward accounts being withdrawn, the
+    /// certificates, the key-based voters and the explicit required signers.
+    /// The resolver provides the outputs being spent; inputs that can't be
+    /// resolved or that are locked by a Byron address (which are witnessed
+    /// through bootstrap witnesses) are skipped. Keys required by native
+    /// scripts aren't included, since which of them must sign depends on how
+    /// the script is satisfied.
+    pub fn all_signers<'r, R>(&self, resolver: &R) -> SignerRequirements
+    where
+        R: UtxoResolver<'r> + ?Sized,
+    {
+        let mut required = SignerRequirements::default();
+
+        for input in self.inputs().iter().chain(self.collateral().iter()) {
+            let address = resolver.resolve(input).and_then(|x| x.address().ok());
+
+            if let Some(Address::Shelley(x)) = address {
+                if let ShelleyPaymentPart::Key(hash) = x.payment() {
+                    required.inputs.insert(*hash);
+                }
+            }
+        }
+
+        let accounts: Vec<&[u8]> = match self.withdrawals() {
+            MultiEraWithdrawals::AlonzoCompatible(x) => x.iter().map(|(k, _)| &k[..]).collect(),
+            MultiEraWithdrawals::Conway(x) => x.iter().map(|(k, _)| &k[..]).collect(),
+            _ => vec![],
+        };
+
+        for account in accounts {
+            // bit 4 of the header flags a script credential
+            if account.len() == 29 && account[0] & 0x10 == 0 {
+                required.withdrawals.insert(Hash::from(&account[1..]));
+            }
+        }
+
+        for cert in self.certs() {
+            required.certificates.extend(cert.required_key_hashes());
+        }
+
+        if let Some(procedures) = self
+            .as_conway()
+            .and_then(|x| x.transaction_body.voting_procedures.as_ref())
+        {
+            for (voter, _) in procedures.iter() {
+                match voter {
+                    conway::Voter::ConstitutionalCommitteeKey(x)
+                    | conway::Voter::DRepKey(x)
+                    | conway::Voter::StakePoolKey(x) => {
+                        required.voters.insert(*x);
+                    }
+                    _ => (),
+                }
+            }
+        }
+
+        required
+            .explicit
+            .extend(self.required_signers().collect::<Vec<_>>());
+
+        required
+    }
+
+    /// Return the key hashes that must sign this tx but have no vkey witness
+    ///
+    /// The required keys are the ones reported by [`MultiEraTx::all_signers`].
+    pub fn missing_vkey_witnesses<'r, R>(&self, resolver: &R) -> Vec<Hash<28>>
+    where
+        R: UtxoResolver<'r> + ?Sized,
+    {
+        let mut required = self.all_signers(resolver).all();
+
+        for witness in self.vkey_witnesses() {
+            required.remove(&Hasher::<224>::hash(&witness.vkey));
+        }
+
+        required.into_iter().collect()
+    }
+
     pub fn find_spend_redeemer(&self, input_order: u32) -> Option<MultiEraRedeemer> {
         self.redeemers().into_iter().find(|r| {
             r.tag() == pallas_primitives::conway::RedeemerTag::Spend && r.index() == input_order
         })
     }
 
+    /// Return the spend redeemer that applies to a given input
+    ///
+    /// Spend redeemers are indexed by the position of the input within the
+    /// sorted set of inputs (see `inputs_sorted_set`). This function resolves
+    /// that position internally, returning `None` if the input isn't part of
+    /// this tx or if it isn't locked by a script.
+    pub fn spend_redeemer_for_input(&self, input: &MultiEraInput) -> Option<MultiEraRedeemer> {
+        let order = self
+            .inputs_sorted_set()
+            .iter()
+            .position(|x| x.hash() == input.hash() && x.index() == input.index())?;
+
+        self.find_spend_redeemer(order as u32)
+    }
+
     pub fn find_mint_redeemer(&self, mint_order: u32) -> Option<MultiEraRedeemer> {
         self.redeemers().into_iter().find(|r| {
             r.tag() == pallas_primitives::conway::RedeemerTag::Mint && r.index() == mint_order
@@ -190,6 +330,19 @@ impl<'b> MultiEraTx<'b> {
         })
     }
 
+    pub fn find_vote_redeemer(&self, voter_order: u32) -> Option<MultiEraRedeemer<'_>> {
+        self.redeemers().into_iter().find(|r| {
+            r.tag() == pallas_primitives::conway::RedeemerTag::Vote && r.index() == voter_order
+        })
+    }
+
+    pub fn find_propose_redeemer(&self, proposal_order: u32) -> Option<MultiEraRedeemer<'_>> {
+        self.redeemers().into_iter().find(|r| {
+            r.tag() == pallas_primitives::conway::RedeemerTag::Propose
+                && r.index() == proposal_order
+        })
+    }
+
     pub fn plutus_v2_scripts(&self) -> &[PlutusScript<2>] {
         match self {
             Self::Byron(_) => &[],
@@ -223,3 +376,86 @@ impl<'b> MultiEraTx<'b> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas_codec::minicbor;
+
+    use std::collections::BTreeSet;
+
+    use super::*;
+    use crate::{Era, MultiEraOutput};
+
+    fn key_output(hash: &Hash<28>) -> Vec<u8> {
+        let mut address = vec![0x61];
+        address.extend_from_slice(hash.as_ref());
+
+        let output = conway::TransactionOutput::PostAlonzo(conway::PostAlonzoTransactionOutput {
+            address: address.into(),
+            value: conway::Value::Coin(1_000_000),
+            datum_option: None,
+            script_ref: None,
+        });
+
+        minicbor::to_vec(&output).unwrap()
+    }
+
+    #[test]
+    fn missing_vkey_witnesses_are_reported() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        assert!(tx
+            .missing_vkey_witnesses(&|_: &MultiEraInput| None)
+            .is_empty());
+
+        let signer = Hasher::<224>::hash(&tx.vkey_witnesses()[0].vkey);
+        let signed = key_output(&signer);
+        let signed = MultiEraOutput::decode(Era::Conway, &signed).unwrap();
+
+        assert!(tx
+            .missing_vkey_witnesses(&|_: &MultiEraInput| Some(signed.clone()))
+            .is_empty());
+
+        let stranger = Hash::<28>::from([7u8; 28]);
+        let unsigned = key_output(&stranger);
+        let unsigned = MultiEraOutput::decode(Era::Conway, &unsigned).unwrap();
+
+        assert_eq!(
+            tx.missing_vkey_witnesses(&|_: &MultiEraInput| Some(unsigned.clone())),
+            vec![stranger]
+        );
+    }
+
+    #[test]
+    fn all_signers_groups_requirements() {
+        let cbor = hex::decode(include_str!("../../test_data/mary2.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Mary, &cbor).unwrap();
+
+        let signers = tx.all_signers(&|_: &MultiEraInput| None);
+
+        // pool registration requires the operator and the owners
+        assert!(!signers.certificates.is_empty());
+        assert!(signers.inputs.is_empty());
+        assert!(signers.explicit.is_empty());
+        assert_eq!(signers.all(), signers.certificates);
+
+        let witnessed: BTreeSet<_> = tx
+            .vkey_witnesses()
+            .iter()
+            .map(|x| Hasher::<224>::hash(&x.vkey))
+            .collect();
+
+        assert!(signers.all().is_subset(&witnessed));
+
+        let stranger = Hash::<28>::from([7u8; 28]);
+        let unsigned = key_output(&stranger);
+        let unsigned = MultiEraOutput::decode(Era::Conway, &unsigned).unwrap();
+
+        let signers = tx.all_signers(&|_: &MultiEraInput| Some(unsigned.clone()));
+
+        assert!(signers.inputs.contains(&stranger));
+        assert!(signers.contains(&stranger));
+        assert!(!signers.certificates.contains(&stranger));
+    }
+}
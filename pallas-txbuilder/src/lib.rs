@@ -1,11 +1,13 @@
 mod conway;
 mod scriptdata;
 mod transaction;
+mod witnesses;
 
 pub use conway::BuildConway;
 pub use transaction::model::{
     BuiltTransaction, ExUnits, Input, Output, ScriptKind, StagingTransaction,
 };
+pub use witnesses::WitnessSetBuilder;
 
 #[derive(Debug, Clone, PartialEq, thiserror::Error)]
 pub enum TxBuilderError {
@@ -3,14 +3,14 @@ use pallas_crypto::{
     hash::{Hash, Hasher},
     key::ed25519,
 };
-use pallas_primitives::{conway, Fragment, NonEmptySet};
+use pallas_primitives::{conway, Fragment};
 use pallas_wallet::PrivateKey;
 
 use std::{collections::HashMap, ops::Deref};
 
 use serde::{Deserialize, Serialize};
 
-use crate::{scriptdata, TxBuilderError};
+use crate::{scriptdata, TxBuilderError, WitnessSetBuilder};
 
 use super::{
     AssetName, Bytes, Bytes32, Bytes64, DatumBytes, DatumHash, Hash28, PolicyId, PubKeyHash,
@@ -638,19 +638,13 @@ impl BuiltTransaction {
                 let mut tx = conway::Tx::decode_fragment(&self.tx_bytes.0)
                     .map_err(|_| TxBuilderError::CorruptedTxBytes)?;
 
-                let mut vkey_witnesses = tx
-                    .transaction_witness_set
-                    .vkeywitness
-                    .map(|x| x.to_vec())
-                    .unwrap_or_default();
-
-                vkey_witnesses.push(conway::VKeyWitness {
-                    vkey: Vec::from(pubkey.as_ref()).into(),
-                    signature: Vec::from(signature.as_ref()).into(),
-                });
-
-                tx.transaction_witness_set.vkeywitness =
-                    Some(NonEmptySet::from_vec(vkey_witnesses).unwrap());
+                tx.transaction_witness_set =
+                    WitnessSetBuilder::from_witness_set(tx.transaction_witness_set)
+                        .vkey_witness(conway::VKeyWitness {
+                            vkey: Vec::from(pubkey.as_ref()).into(),
+                            signature: Vec::from(signature.as_ref()).into(),
+                        })
+                        .build();
 
                 self.tx_bytes = tx.encode_fragment().unwrap().into();
             }
@@ -685,19 +679,13 @@ impl BuiltTransaction {
                 let mut tx = conway::Tx::decode_fragment(&self.tx_bytes.0)
                     .map_err(|_| TxBuilderError::CorruptedTxBytes)?;
 
-                let mut vkey_witnesses = tx
-                    .transaction_witness_set
-                    .vkeywitness
-                    .map(|x| x.to_vec())
-                    .unwrap_or_default();
-
-                vkey_witnesses.push(conway::VKeyWitness {
-                    vkey: Vec::from(pub_key.as_ref()).into(),
-                    signature: Vec::from(signature.as_ref()).into(),
-                });
-
-                tx.transaction_witness_set.vkeywitness =
-                    Some(NonEmptySet::from_vec(vkey_witnesses).unwrap());
+                tx.transaction_witness_set =
+                    WitnessSetBuilder::from_witness_set(tx.transaction_witness_set)
+                        .vkey_witness(conway::VKeyWitness {
+                            vkey: Vec::from(pub_key.as_ref()).into(),
+                            signature: Vec::from(signature.as_ref()).into(),
+                        })
+                        .build();
 
                 self.tx_bytes = tx.encode_fragment().unwrap().into();
             }
@@ -727,16 +715,10 @@ impl BuiltTransaction {
                 let mut tx = conway::Tx::decode_fragment(&self.tx_bytes.0)
                     .map_err(|_| TxBuilderError::CorruptedTxBytes)?;
 
-                let mut vkey_witnesses = tx
-                    .transaction_witness_set
-                    .vkeywitness
-                    .map(|x| x.to_vec())
-                    .unwrap_or_default();
-
-                vkey_witnesses.retain(|x| *x.vkey != pk.0.to_vec());
-
-                tx.transaction_witness_set.vkeywitness =
-                    Some(NonEmptySet::from_vec(vkey_witnesses).unwrap());
+                tx.transaction_witness_set =
+                    WitnessSetBuilder::from_witness_set(tx.transaction_witness_set)
+                        .remove_vkey_witness(&pk.0)
+                        .build();
 
                 self.tx_bytes = tx.encode_fragment().unwrap().into();
             }
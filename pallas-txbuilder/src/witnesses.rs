@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use pallas_codec::minicbor;
+use pallas_primitives::{
+    conway::{BootstrapWitness, VKeyWitness, WitnessSet},
+    NonEmptySet,
+};
+
+/// Assembles a Conway witness set with deduplicated, canonically ordered key
+/// witnesses
+///
+/// Vkey and bootstrap witnesses are keyed by their public key, so adding a
+/// signature for a key that already has one replaces it instead of adding a
+/// duplicate. When building, the witnesses are sorted by their CBOR encoding,
+/// which makes the resulting bytes independent of the order in which the
+/// signatures were collected. The remaining fields of the witness set (scripts,
+/// datums, redeemers) are kept as they were provided.
+#[derive(Debug, Clone)]
+pub struct WitnessSetBuilder {
+    base: WitnessSet,
+    vkey_witnesses: BTreeMap<Vec<u8>, VKeyWitness>,
+    bootstrap_witnesses: BTreeMap<Vec<u8>, BootstrapWitness>,
+}
+
+fn canonical<T: minicbor::Encode<()>>(items: impl Iterator<Item = T>) -> Option<NonEmptySet<T>> {
+    // to_vec is infallible
+    let mut items: Vec<_> = items.map(|x| (minicbor::to_vec(&x).unwrap(), x)).collect();
+    items.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    NonEmptySet::from_vec(items.into_iter().map(|(_, x)| x).collect())
+}
+
+impl Default for WitnessSetBuilder {
+    fn default() -> Self {
+        Self::from_witness_set(empty())
+    }
+}
+
+fn empty() -> WitnessSet {
+    WitnessSet {
+        vkeywitness: None,
+        native_script: None,
+        bootstrap_witness: None,
+        plutus_v1_script: None,
+        plutus_data: None,
+        redeemer: None,
+        plutus_v2_script: None,
+        plutus_v3_script: None,
+    }
+}
+
+impl WitnessSetBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start from an existing witness set, deduplicating its key witnesses
+    pub fn from_witness_set(mut set: WitnessSet) -> Self {
+        let vkeys = set.vkeywitness.take().map(|x| x.to_vec());
+        let bootstraps = set.bootstrap_witness.take().map(|x| x.to_vec());
+
+        let mut builder = Self {
+            base: set,
+            vkey_witnesses: BTreeMap::new(),
+            bootstrap_witnesses: BTreeMap::new(),
+        };
+
+        for witness in vkeys.into_iter().flatten() {
+            builder = builder.vkey_witness(witness);
+        }
+
+        for witness in bootstraps.into_iter().flatten() {
+            builder = builder.bootstrap_witness(witness);
+        }
+
+        builder
+    }
+
+    /// Add a vkey witness, replacing any previous one for the same vkey
+    pub fn vkey_witness(mut self, witness: VKeyWitness) -> Self {
+        self.vkey_witnesses.insert(witness.vkey.to_vec(), witness);
+        self
+    }
+
+    /// Add a bootstrap witness, replacing any previous one for the same key
+    pub fn bootstrap_witness(mut self, witness: BootstrapWitness) -> Self {
+        self.bootstrap_witnesses
+            .insert(witness.public_key.to_vec(), witness);
+        self
+    }
+
+    pub fn remove_vkey_witness(mut self, vkey: &[u8]) -> Self {
+        self.vkey_witnesses.remove(vkey);
+        self
+    }
+
+    pub fn remove_bootstrap_witness(mut self, public_key: &[u8]) -> Self {
+        self.bootstrap_witnesses.remove(public_key);
+        self
+    }
+
+    pub fn build(self) -> WitnessSet {
+        WitnessSet {
+            vkeywitness: canonical(self.vkey_witnesses.into_values()),
+            bootstrap_witness: canonical(self.bootstrap_witnesses.into_values()),
+            ..self.base
+        }
+    }
+
+    pub fn encode(self) -> Vec<u8> {
+        // to_vec is infallible
+        minicbor::to_vec(self.build()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn witness(vkey: u8, signature: u8) -> VKeyWitness {
+        VKeyWitness {
+            vkey: vec![vkey; 32].into(),
+            signature: vec![signature; 64].into(),
+        }
+    }
+
+    #[test]
+    fn witnesses_are_deduplicated_and_sorted() {
+        let a = WitnessSetBuilder::new()
+            .vkey_witness(witness(2, 0))
+            .vkey_witness(witness(1, 0))
+            .vkey_witness(witness(2, 1))
+            .build();
+
+        assert_eq!(
+            a.vkeywitness.clone().unwrap().to_vec(),
+            vec![witness(1, 0), witness(2, 1)]
+        );
+
+        let b = WitnessSetBuilder::new()
+            .vkey_witness(witness(2, 1))
+            .vkey_witness(witness(1, 0))
+            .build();
+
+        assert_eq!(minicbor::to_vec(&a).unwrap(), minicbor::to_vec(&b).unwrap());
+    }
+
+    #[test]
+    fn removing_last_witness_leaves_field_empty() {
+        let set = WitnessSetBuilder::from_witness_set(WitnessSet {
+            vkeywitness: NonEmptySet::from_vec(vec![witness(1, 0), witness(1, 0)]),
+            ..empty()
+        })
+        .remove_vkey_witness(&[1; 32])
+        .build();
+
+        assert!(set.vkeywitness.is_none());
+    }
+}
@@ -0,0 +1,138 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+
+use crate::{EraCbor, LedgerContext, TxoRef, UtxoMap};
+
+/// A [LedgerContext] decorator that memoizes the UTxOs returned by another
+/// context
+///
+/// Inputs tend to repeat across the txs of a block (reference inputs in
+/// particular), so wrapping the ledger context of a [crate::Mapper] with this
+/// cache reduces the lookups issued to the backend. Only the least recently
+/// used UTxOs are kept, up to the configured capacity. Clones share the same
+/// cache.
+#[derive(Clone)]
+pub struct CachingLedgerContext<C: LedgerContext> {
+    inner: C,
+    cache: Arc<Mutex<LruCache<TxoRef, EraCbor>>>,
+}
+
+impl<C: LedgerContext> CachingLedgerContext<C> {
+    pub const DEFAULT_CAPACITY: NonZeroUsize = match NonZeroUsize::new(10_000) {
+        Some(x) => x,
+        None => unreachable!(),
+    };
+
+    pub fn new(inner: C) -> Self {
+        Self::with_capacity(inner, Self::DEFAULT_CAPACITY)
+    }
+
+    pub fn with_capacity(inner: C, capacity: NonZeroUsize) -> Self {
+        Self {
+            inner,
+            cache: Arc::new(Mutex::new(LruCache::new(capacity))),
+        }
+    }
+
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+impl<C: LedgerContext> LedgerContext for CachingLedgerContext<C> {
+    fn get_utxos(&self, refs: &[TxoRef]) -> Option<UtxoMap> {
+        let mut found = UtxoMap::new();
+        let mut missing = vec![];
+
+        {
+            let mut cache = self.cache.lock().unwrap();
+
+            for txo in refs {
+                match cache.get(txo) {
+                    Some(utxo) => {
+                        found.insert(*txo, utxo.clone());
+                    }
+                    None => missing.push(*txo),
+                }
+            }
+        }
+
+        if missing.is_empty() {
+            return Some(found);
+        }
+
+        // the lock is not held while querying the inner context, concurrent
+        // misses for the same UTxO might query it twice, which is harmless
+        let Some(resolved) = self.inner.get_utxos(&missing) else {
+            return (!found.is_empty()).then_some(found);
+        };
+
+        let mut cache = self.cache.lock().unwrap();
+
+        for (txo, utxo) in resolved {
+            cache.put(txo, utxo.clone());
+            found.insert(txo, utxo);
+        }
+
+        Some(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use pallas_traverse::Era;
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct CountingLedger {
+        queried: Rc<RefCell<Vec<TxoRef>>>,
+    }
+
+    impl LedgerContext for CountingLedger {
+        fn get_utxos(&self, refs: &[TxoRef]) -> Option<UtxoMap> {
+            self.queried.borrow_mut().extend_from_slice(refs);
+
+            let utxos = refs
+                .iter()
+                .filter(|(_, index)| index % 2 == 0)
+                .map(|txo| (*txo, (Era::Conway, vec![txo.1 as u8])))
+                .collect();
+
+            Some(utxos)
+        }
+    }
+
+    fn txo(index: u32) -> TxoRef {
+        ([1; 32].into(), index)
+    }
+
+    #[test]
+    fn only_misses_reach_inner_context() {
+        let inner = CountingLedger::default();
+        let ctx = CachingLedgerContext::with_capacity(inner.clone(), NonZeroUsize::new(2).unwrap());
+
+        let first = ctx.get_utxos(&[txo(0), txo(1), txo(2)]).unwrap();
+        assert_eq!(first.len(), 2);
+        assert_eq!(inner.queried.borrow().len(), 3);
+
+        // clones share the cache, unknown UTxOs are queried again
+        let second = ctx.clone().get_utxos(&[txo(0), txo(1), txo(2)]).unwrap();
+        assert_eq!(first, second);
+        assert_eq!(inner.queried.borrow()[3..], [txo(1)]);
+
+        // capacity is 2, caching txo 4 evicts the least recently used (txo 0)
+        ctx.get_utxos(&[txo(2), txo(4)]).unwrap();
+        inner.queried.borrow_mut().clear();
+
+        ctx.get_utxos(&[txo(0), txo(2), txo(4)]).unwrap();
+        assert_eq!(*inner.queried.borrow(), [txo(0)]);
+    }
+}
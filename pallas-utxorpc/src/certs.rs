@@ -47,13 +47,10 @@ impl<C: LedgerContext> Mapper<C> {
                 reward_account: reward_account.to_vec().into(),
                 pool_owners: pool_owners.iter().map(|x| x.to_vec().into()).collect(),
                 relays: relays.iter().map(|x| self.map_relay(x)).collect(),
-                pool_metadata: pool_metadata
-                    .clone()
-                    .map(|x| u5c::PoolMetadata {
-                        url: x.url.clone(),
-                        hash: x.hash.to_vec().into(),
-                    })
-                    .into(),
+                pool_metadata: pool_metadata.as_option().map(|x| u5c::PoolMetadata {
+                    url: x.url.clone(),
+                    hash: x.hash.to_vec().into(),
+                }),
             }),
             alonzo::Certificate::PoolRetirement(a, b) => {
                 u5c::certificate::Certificate::PoolRetirement(u5c::PoolRetirementCert {
@@ -115,6 +112,17 @@ impl<C: LedgerContext> Mapper<C> {
         }
     }
 
+    pub fn map_anchor(&self, x: &conway::Anchor) -> u5c::Anchor {
+        u5c::Anchor {
+            url: x.url.clone(),
+            content_hash: x.content_hash.to_vec().into(),
+        }
+    }
+
+    fn map_nullable_anchor(&self, x: &conway::Nullable<conway::Anchor>) -> Option<u5c::Anchor> {
+        x.as_option().map(|x| self.map_anchor(x))
+    }
+
     pub fn map_conway_cert(
         &self,
         x: &conway::Certificate,
@@ -157,13 +165,10 @@ impl<C: LedgerContext> Mapper<C> {
                 reward_account: reward_account.to_vec().into(),
                 pool_owners: pool_owners.iter().map(|x| x.to_vec().into()).collect(),
                 relays: relays.iter().map(|x| self.map_relay(x)).collect(),
-                pool_metadata: pool_metadata
-                    .clone()
-                    .map(|x| u5c::PoolMetadata {
-                        url: x.url.clone(),
-                        hash: x.hash.to_vec().into(),
-                    })
-                    .into(),
+                pool_metadata: pool_metadata.as_option().map(|x| u5c::PoolMetadata {
+                    url: x.url.clone(),
+                    hash: x.hash.to_vec().into(),
+                }),
             }),
             conway::Certificate::PoolRetirement(a, b) => {
                 u5c::certificate::Certificate::PoolRetirement(u5c::PoolRetirementCert {
@@ -228,13 +233,7 @@ impl<C: LedgerContext> Mapper<C> {
                 u5c::certificate::Certificate::ResignCommitteeColdCert(
                     u5c::ResignCommitteeColdCert {
                         committee_cold_credential: self.map_stake_credential(cold_cred).into(),
-                        anchor: anchor
-                            .clone()
-                            .map(|a| u5c::Anchor {
-                                url: a.url,
-                                content_hash: a.content_hash.to_vec().into(),
-                            })
-                            .into(),
+                        anchor: self.map_nullable_anchor(anchor),
                     },
                 )
             }
@@ -242,13 +241,7 @@ impl<C: LedgerContext> Mapper<C> {
                 u5c::certificate::Certificate::RegDrepCert(u5c::RegDRepCert {
                     drep_credential: self.map_stake_credential(cred).into(),
                     coin: *coin,
-                    anchor: anchor
-                        .clone()
-                        .map(|a| u5c::Anchor {
-                            url: a.url,
-                            content_hash: a.content_hash.to_vec().into(),
-                        })
-                        .into(),
+                    anchor: self.map_nullable_anchor(anchor),
                 })
             }
             conway::Certificate::UnRegDRepCert(cred, coin) => {
@@ -260,13 +253,7 @@ impl<C: LedgerContext> Mapper<C> {
             conway::Certificate::UpdateDRepCert(cred, anchor) => {
                 u5c::certificate::Certificate::UpdateDrepCert(u5c::UpdateDRepCert {
                     drep_credential: self.map_stake_credential(cred).into(),
-                    anchor: anchor
-                        .clone()
-                        .map(|a| u5c::Anchor {
-                            url: a.url,
-                            content_hash: a.content_hash.to_vec().into(),
-                        })
-                        .into(),
+                    anchor: self.map_nullable_anchor(anchor),
                 })
             }
         };
@@ -294,3 +281,121 @@ impl<C: LedgerContext> Mapper<C> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use pallas_crypto::hash::Hash;
+
+    use super::*;
+    use crate::{TxoRef, UtxoMap};
+
+    #[derive(Clone)]
+    struct NoLedger;
+
+    impl LedgerContext for NoLedger {
+        fn get_utxos(&self, _refs: &[TxoRef]) -> Option<UtxoMap> {
+            None
+        }
+    }
+
+    fn anchor() -> conway::Anchor {
+        conway::Anchor {
+            url: "https://example.com/drep.json".into(),
+            content_hash: Hash::new([7; 32]),
+        }
+    }
+
+    fn cred(byte: u8) -> conway::StakeCredential {
+        conway::StakeCredential::AddrKeyhash(Hash::new([byte; 28]))
+    }
+
+    #[test]
+    fn governance_certs_are_mapped() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = trv::MultiEraTx::decode_for_era(trv::Era::Conway, &cbor).unwrap();
+        let mapper = Mapper::new(NoLedger);
+
+        let map = |cert: conway::Certificate| mapper.map_conway_cert(&cert, &tx, 0).certificate;
+
+        let expected_anchor = Some(u5c::Anchor {
+            url: "https://example.com/drep.json".into(),
+            content_hash: vec![7; 32].into(),
+        });
+
+        match map(conway::Certificate::RegDRepCert(
+            cred(1),
+            500_000_000,
+            conway::Nullable::Some(anchor()),
+        )) {
+            Some(u5c::certificate::Certificate::RegDrepCert(x)) => {
+                assert_eq!(
+                    x.drep_credential,
+                    Some(mapper.map_stake_credential(&cred(1)))
+                );
+                assert_eq!(x.coin, 500_000_000);
+                assert_eq!(x.anchor, expected_anchor);
+            }
+            x => panic!("unexpected mapping {x:?}"),
+        }
+
+        match map(conway::Certificate::RegDRepCert(
+            cred(1),
+            500_000_000,
+            conway::Nullable::Null,
+        )) {
+            Some(u5c::certificate::Certificate::RegDrepCert(x)) => assert_eq!(x.anchor, None),
+            x => panic!("unexpected mapping {x:?}"),
+        }
+
+        match map(conway::Certificate::UnRegDRepCert(cred(1), 500_000_000)) {
+            Some(u5c::certificate::Certificate::UnregDrepCert(x)) => {
+                assert_eq!(x.coin, 500_000_000);
+            }
+            x => panic!("unexpected mapping {x:?}"),
+        }
+
+        match map(conway::Certificate::UpdateDRepCert(
+            cred(1),
+            conway::Nullable::Some(anchor()),
+        )) {
+            Some(u5c::certificate::Certificate::UpdateDrepCert(x)) => {
+                assert_eq!(x.anchor, expected_anchor);
+            }
+            x => panic!("unexpected mapping {x:?}"),
+        }
+
+        match map(conway::Certificate::AuthCommitteeHot(cred(2), cred(3))) {
+            Some(u5c::certificate::Certificate::AuthCommitteeHotCert(x)) => {
+                assert_eq!(
+                    x.committee_cold_credential,
+                    Some(mapper.map_stake_credential(&cred(2)))
+                );
+                assert_eq!(
+                    x.committee_hot_credential,
+                    Some(mapper.map_stake_credential(&cred(3)))
+                );
+            }
+            x => panic!("unexpected mapping {x:?}"),
+        }
+
+        match map(conway::Certificate::ResignCommitteeCold(
+            cred(2),
+            conway::Nullable::Some(anchor()),
+        )) {
+            Some(u5c::certificate::Certificate::ResignCommitteeColdCert(x)) => {
+                assert_eq!(x.anchor, expected_anchor);
+            }
+            x => panic!("unexpected mapping {x:?}"),
+        }
+
+        match map(conway::Certificate::VoteDeleg(
+            cred(4),
+            conway::DRep::Abstain,
+        )) {
+            Some(u5c::certificate::Certificate::VoteDelegCert(x)) => {
+                assert_eq!(x.drep, Some(mapper.map_drep(&conway::DRep::Abstain)));
+            }
+            x => panic!("unexpected mapping {x:?}"),
+        }
+    }
+}
@@ -12,8 +12,12 @@ pub use utxorpc_spec::utxorpc::v1alpha as spec;
 
 use utxorpc_spec::utxorpc::v1alpha::cardano as u5c;
 
+mod cache;
 mod certs;
 mod params;
+pub mod reverse;
+
+pub use cache::CachingLedgerContext;
 
 pub type TxHash = Hash<32>;
 pub type TxoIndex = u32;
@@ -37,14 +41,14 @@ pub trait LedgerContext: Clone {
 #[derive(Default, Clone)]
 pub struct Mapper<C: LedgerContext> {
     ledger: Option<C>,
-    _mask: FieldMask,
+    mask: FieldMask,
 }
 
 impl<C: LedgerContext> Mapper<C> {
     pub fn new(ledger: C) -> Self {
         Self {
             ledger: Some(ledger),
-            _mask: FieldMask { paths: vec![] },
+            mask: FieldMask { paths: vec![] },
         }
     }
 
@@ -52,7 +56,53 @@ impl<C: LedgerContext> Mapper<C> {
     pub fn masked(&self, mask: FieldMask) -> Self {
         Self {
             ledger: self.ledger.clone(),
-            _mask: mask,
+            mask,
+        }
+    }
+
+    /// Matches a (dot-separated) field path against the field mask
+    ///
+    /// Returns `None` if the field is not selected, otherwise returns the mask
+    /// that applies to the sub-fields of the selected field. An empty mask
+    /// selects every field.
+    fn select(&self, field: &str) -> Option<FieldMask> {
+        if self.mask.paths.is_empty() {
+            return Some(FieldMask::default());
+        }
+
+        let mut paths = vec![];
+
+        for path in self.mask.paths.iter() {
+            let covers_field = path == field
+                || field
+                    .strip_prefix(path.as_str())
+                    .is_some_and(|x| x.starts_with('.'));
+
+            if covers_field {
+                return Some(FieldMask::default());
+            }
+
+            if let Some(rest) = path.strip_prefix(field).and_then(|x| x.strip_prefix('.')) {
+                paths.push(rest.to_owned());
+            }
+        }
+
+        match paths.is_empty() {
+            true => None,
+            false => Some(FieldMask { paths }),
+        }
+    }
+
+    fn is_selected(&self, field: &str) -> bool {
+        self.select(field).is_some()
+    }
+
+    /// Computes the value of a field only if selected by the mask, falling
+    /// back to the default value
+    fn when<T: Default>(&self, field: &str, f: impl FnOnce() -> T) -> T {
+        match self.is_selected(field) {
+            true => f(),
+            false => T::default(),
         }
     }
 }
@@ -225,17 +275,22 @@ impl<C: LedgerContext> Mapper<C> {
     pub fn map_relay(&self, x: &alonzo::Relay) -> u5c::Relay {
         match x {
             babbage::Relay::SingleHostAddr(port, v4, v6) => u5c::Relay {
-                // ip_v4: v4.map(|x| x.to_vec().into()).into().unwrap_or_default(),
-                ip_v4: Option::from(v4.clone().map(|x| x.to_vec().into())).unwrap_or_default(),
-                ip_v6: Option::from(v6.clone().map(|x| x.to_vec().into())).unwrap_or_default(),
+                ip_v4: v4
+                    .as_option()
+                    .map(|x| x.to_vec().into())
+                    .unwrap_or_default(),
+                ip_v6: v6
+                    .as_option()
+                    .map(|x| x.to_vec().into())
+                    .unwrap_or_default(),
                 dns_name: String::default(),
-                port: Option::from(port.clone()).unwrap_or_default(),
+                port: port.as_option().copied().unwrap_or_default(),
             },
             babbage::Relay::SingleHostName(port, name) => u5c::Relay {
                 ip_v4: Default::default(),
                 ip_v6: Default::default(),
                 dns_name: name.clone(),
-                port: Option::from(port.clone()).unwrap_or_default(),
+                port: port.as_option().copied().unwrap_or_default(),
             },
             babbage::Relay::MultiHostName(name) => u5c::Relay {
                 ip_v4: Default::default(),
@@ -295,7 +350,7 @@ impl<C: LedgerContext> Mapper<C> {
                 })
             }
             babbage::NativeScript::ScriptAny(x) => {
-                u5c::native_script::NativeScript::ScriptAll(u5c::NativeScriptList {
+                u5c::native_script::NativeScript::ScriptAny(u5c::NativeScriptList {
                     items: x.iter().map(|x| Self::map_native_script(x)).collect(),
                 })
             }
@@ -318,32 +373,28 @@ impl<C: LedgerContext> Mapper<C> {
         }
     }
 
-    fn collect_all_scripts(&self, tx: &trv::MultiEraTx) -> Vec<u5c::Script> {
-        let ns = tx
-            .native_scripts()
-            .iter()
-            .map(|x| Self::map_native_script(x.deref()))
-            .map(|x| u5c::Script {
-                script: u5c::script::Script::Native(x).into(),
-            });
-
-        let p1 = tx
-            .plutus_v1_scripts()
-            .iter()
-            .map(|x| x.0.to_vec().into())
-            .map(|x| u5c::Script {
-                script: u5c::script::Script::PlutusV1(x).into(),
-            });
+    pub fn map_script(&self, x: &trv::MultiEraScript) -> u5c::Script {
+        let script = match x {
+            trv::MultiEraScript::Native(x) => {
+                u5c::script::Script::Native(Self::map_native_script(x)).into()
+            }
+            trv::MultiEraScript::PlutusV1(x) => {
+                u5c::script::Script::PlutusV1(x.0.to_vec().into()).into()
+            }
+            trv::MultiEraScript::PlutusV2(x) => {
+                u5c::script::Script::PlutusV2(x.0.to_vec().into()).into()
+            }
+            trv::MultiEraScript::PlutusV3(x) => {
+                u5c::script::Script::PlutusV3(x.0.to_vec().into()).into()
+            }
+            _ => None,
+        };
 
-        let p2 = tx
-            .plutus_v2_scripts()
-            .iter()
-            .map(|x| x.0.to_vec().into())
-            .map(|x| u5c::Script {
-                script: u5c::script::Script::PlutusV2(x).into(),
-            });
+        u5c::Script { script }
+    }
 
-        ns.chain(p1).chain(p2).collect()
+    fn collect_all_scripts(&self, tx: &trv::MultiEraTx) -> Vec<u5c::Script> {
+        tx.scripts().iter().map(|x| self.map_script(x)).collect()
     }
 
     pub fn map_plutus_constr(&self, x: &alonzo::Constr<alonzo::PlutusData>) -> u5c::Constr {
@@ -419,109 +470,98 @@ impl<C: LedgerContext> Mapper<C> {
         &self,
         x: &conway::Nullable<conway::GovActionId>,
     ) -> Option<u5c::GovernanceActionId> {
-        match x {
-            conway::Nullable::Some(x) => Some(u5c::GovernanceActionId {
-                transaction_id: x.transaction_id.to_vec().into(),
-                governance_action_index: x.action_index,
-            }),
-            _ => None,
-        }
+        x.as_option().map(|x| u5c::GovernanceActionId {
+            transaction_id: x.transaction_id.to_vec().into(),
+            governance_action_index: x.action_index,
+        })
     }
 
     pub fn map_conway_gov_action(&self, x: &conway::GovAction) -> u5c::GovernanceAction {
-        let inner =
-            match x {
-                conway::GovAction::ParameterChange(gov_id, params, script) => {
-                    u5c::governance_action::GovernanceAction::ParameterChangeAction(
-                        u5c::ParameterChangeAction {
-                            gov_action_id: self.map_gov_action_id(gov_id),
-                            protocol_param_update: Some(self.map_conway_pparams_update(&params)),
-                            policy_hash: match script {
-                                conway::Nullable::Some(x) => x.to_vec().into(),
-                                _ => Default::default(),
-                            },
+        let inner = match x {
+            conway::GovAction::ParameterChange(gov_id, params, script) => {
+                u5c::governance_action::GovernanceAction::ParameterChangeAction(
+                    u5c::ParameterChangeAction {
+                        gov_action_id: self.map_gov_action_id(gov_id),
+                        protocol_param_update: Some(self.map_conway_pparams_update(&params)),
+                        policy_hash: match script {
+                            conway::Nullable::Some(x) => x.to_vec().into(),
+                            _ => Default::default(),
                         },
-                    )
-                }
-                conway::GovAction::HardForkInitiation(gov_id, version) => {
-                    u5c::governance_action::GovernanceAction::HardForkInitiationAction(
-                        u5c::HardForkInitiationAction {
-                            gov_action_id: self.map_gov_action_id(gov_id),
-                            protocol_version: Some(u5c::ProtocolVersion {
-                                major: version.0 as u32,
-                                minor: version.1 as u32,
-                            }),
+                    },
+                )
+            }
+            conway::GovAction::HardForkInitiation(gov_id, version) => {
+                u5c::governance_action::GovernanceAction::HardForkInitiationAction(
+                    u5c::HardForkInitiationAction {
+                        gov_action_id: self.map_gov_action_id(gov_id),
+                        protocol_version: Some(u5c::ProtocolVersion {
+                            major: version.0 as u32,
+                            minor: version.1 as u32,
+                        }),
+                    },
+                )
+            }
+            conway::GovAction::TreasuryWithdrawals(withdrawals, script) => {
+                u5c::governance_action::GovernanceAction::TreasuryWithdrawalsAction(
+                    u5c::TreasuryWithdrawalsAction {
+                        withdrawals: withdrawals
+                            .iter()
+                            .map(|(k, v)| u5c::WithdrawalAmount {
+                                reward_account: k.to_vec().into(),
+                                coin: *v,
+                            })
+                            .collect(),
+                        policy_hash: match script {
+                            conway::Nullable::Some(x) => x.to_vec().into(),
+                            _ => Default::default(),
                         },
-                    )
-                }
-                conway::GovAction::TreasuryWithdrawals(withdrawals, script) => {
-                    u5c::governance_action::GovernanceAction::TreasuryWithdrawalsAction(
-                        u5c::TreasuryWithdrawalsAction {
-                            withdrawals: withdrawals
-                                .iter()
-                                .map(|(k, v)| u5c::WithdrawalAmount {
-                                    reward_account: k.to_vec().into(),
-                                    coin: *v,
-                                })
-                                .collect(),
-                            policy_hash: match script {
+                    },
+                )
+            }
+            conway::GovAction::NoConfidence(gov_id) => {
+                u5c::governance_action::GovernanceAction::NoConfidenceAction(
+                    u5c::NoConfidenceAction {
+                        gov_action_id: self.map_gov_action_id(gov_id),
+                    },
+                )
+            }
+            conway::GovAction::UpdateCommittee(gov_id, remove, add, threshold) => {
+                u5c::governance_action::GovernanceAction::UpdateCommitteeAction(
+                    u5c::UpdateCommitteeAction {
+                        gov_action_id: self.map_gov_action_id(gov_id),
+                        remove_committee_credentials: remove
+                            .iter()
+                            .map(|x| self.map_stake_credential(x))
+                            .collect(),
+                        new_committee_credentials: add
+                            .iter()
+                            .map(|(cred, epoch)| u5c::NewCommitteeCredentials {
+                                committee_cold_credential: Some(self.map_stake_credential(cred)),
+                                expires_epoch: *epoch as u32,
+                            })
+                            .collect(),
+                        new_committee_threshold: Some(rational_number_to_u5c(threshold.clone())),
+                    },
+                )
+            }
+            conway::GovAction::NewConstitution(gov_id, constitution) => {
+                u5c::governance_action::GovernanceAction::NewConstitutionAction(
+                    u5c::NewConstitutionAction {
+                        gov_action_id: self.map_gov_action_id(gov_id),
+                        constitution: Some(u5c::Constitution {
+                            anchor: Some(self.map_anchor(&constitution.anchor)),
+                            hash: match constitution.guardrail_script {
                                 conway::Nullable::Some(x) => x.to_vec().into(),
                                 _ => Default::default(),
                             },
-                        },
-                    )
-                }
-                conway::GovAction::NoConfidence(gov_id) => {
-                    u5c::governance_action::GovernanceAction::NoConfidenceAction(
-                        u5c::NoConfidenceAction {
-                            gov_action_id: self.map_gov_action_id(gov_id),
-                        },
-                    )
-                }
-                conway::GovAction::UpdateCommittee(gov_id, remove, add, threshold) => {
-                    u5c::governance_action::GovernanceAction::UpdateCommitteeAction(
-                        u5c::UpdateCommitteeAction {
-                            gov_action_id: self.map_gov_action_id(gov_id),
-                            remove_committee_credentials: remove
-                                .iter()
-                                .map(|x| self.map_stake_credential(x))
-                                .collect(),
-                            new_committee_credentials: add
-                                .iter()
-                                .map(|(cred, epoch)| u5c::NewCommitteeCredentials {
-                                    committee_cold_credential: Some(
-                                        self.map_stake_credential(cred),
-                                    ),
-                                    expires_epoch: *epoch as u32,
-                                })
-                                .collect(),
-                            new_committee_threshold: Some(rational_number_to_u5c(
-                                threshold.clone(),
-                            )),
-                        },
-                    )
-                }
-                conway::GovAction::NewConstitution(gov_id, constitution) => {
-                    u5c::governance_action::GovernanceAction::NewConstitutionAction(
-                        u5c::NewConstitutionAction {
-                            gov_action_id: self.map_gov_action_id(gov_id),
-                            constitution: Some(u5c::Constitution {
-                                anchor: Some(u5c::Anchor {
-                                    url: constitution.anchor.url.clone(),
-                                    content_hash: constitution.anchor.content_hash.to_vec().into(),
-                                }),
-                                hash: match constitution.guardrail_script {
-                                    conway::Nullable::Some(x) => x.to_vec().into(),
-                                    _ => Default::default(),
-                                },
-                            }),
-                        },
-                    )
-                }
-                conway::GovAction::Information => {
-                    u5c::governance_action::GovernanceAction::InfoAction(6) // The 6 is just a placeholder, we don't need to use it
-                }
-            };
+                        }),
+                    },
+                )
+            }
+            conway::GovAction::Information => {
+                u5c::governance_action::GovernanceAction::InfoAction(6) // The 6 is just a placeholder, we don't need to use it
+            }
+        };
 
         u5c::GovernanceAction {
             governance_action: Some(inner),
@@ -535,10 +575,7 @@ impl<C: LedgerContext> Mapper<C> {
             gov_action: x
                 .as_conway()
                 .map(|x| self.map_conway_gov_action(&x.gov_action)),
-            anchor: Some(u5c::Anchor {
-                url: x.anchor().url.clone(),
-                content_hash: x.anchor().content_hash.to_vec().into(),
-            }),
+            anchor: Some(self.map_anchor(x.anchor())),
         }
     }
 
@@ -616,126 +653,221 @@ impl<C: LedgerContext> Mapper<C> {
         inputs.chain(collateral).chain(reference_inputs).collect()
     }
 
+    /// Maps a transaction, including only the fields selected by the field
+    /// mask
+    ///
+    /// Mask paths are relative to the transaction (eg: `hash`, `outputs`).
+    /// Fields are pruned at the top level, a selected field is mapped in full.
     pub fn map_tx(&self, tx: &trv::MultiEraTx) -> u5c::Tx {
-        let resolved = self.ledger.as_ref().and_then(|ctx| {
-            let to_resolve = self.find_related_inputs(tx);
-            ctx.get_utxos(to_resolve.as_slice())
-        });
+        let needs_resolve = ["inputs", "reference_inputs", "collateral"]
+            .iter()
+            .any(|x| self.is_selected(x));
 
-        u5c::Tx {
-            hash: tx.hash().to_vec().into(),
-            inputs: tx
-                .inputs_sorted_set()
-                .iter()
-                .enumerate()
-                .map(|(order, i)| self.map_tx_input(i, tx, order as u32, &resolved))
-                .collect(),
-            outputs: tx
-                .outputs()
-                .iter()
-                .map(|x| self.map_tx_output(x, Some(tx)))
-                .collect(),
-            certificates: tx
-                .certs()
-                .iter()
-                .enumerate()
-                .filter_map(|(order, x)| self.map_cert(x, tx, order as u32))
-                .collect(),
-            proposals: tx
-                .gov_proposals()
-                .iter()
-                .map(|x| self.map_gov_proposal(x))
-                .collect(),
-            withdrawals: tx
-                .withdrawals_sorted_set()
-                .iter()
-                .enumerate()
-                .map(|(order, x)| self.map_withdrawals(x, tx, order as u32))
-                .collect(),
-            mint: tx
-                .mints_sorted_set()
-                .iter()
-                .enumerate()
-                .map(|(order, x)| {
-                    let mut ma = self.map_policy_assets(x);
+        let resolved = match needs_resolve {
+            true => self.ledger.as_ref().and_then(|ctx| {
+                let to_resolve = self.find_related_inputs(tx);
+                ctx.get_utxos(to_resolve.as_slice())
+            }),
+            false => None,
+        };
 
-                    ma.redeemer = tx
-                        .find_mint_redeemer(order as u32)
-                        .map(|r| self.map_redeemer(&r));
+        self.map_resolved_tx(tx, &resolved)
+    }
 
-                    ma
-                })
-                .collect(),
-            reference_inputs: tx
-                .reference_inputs()
-                .iter()
-                .map(|x| self.map_tx_reference_input(x, &resolved, tx))
-                .collect(),
-            witnesses: u5c::WitnessSet {
-                vkeywitness: tx
-                    .vkey_witnesses()
+    /// Maps a transaction using the provided UTxOs to resolve its inputs,
+    /// without querying the ledger context
+    ///
+    /// Useful for transactions that are not on-chain yet (eg: from a mempool),
+    /// whose inputs can't be resolved by the ledger context.
+    pub fn map_tx_with_utxos(&self, tx: &trv::MultiEraTx, utxos: &UtxoMap) -> u5c::Tx {
+        self.map_resolved_tx(tx, &Some(utxos.clone()))
+    }
+
+    fn map_resolved_tx(&self, tx: &trv::MultiEraTx, resolved: &Option<UtxoMap>) -> u5c::Tx {
+        u5c::Tx {
+            hash: self.when("hash", || tx.hash().to_vec().into()),
+            inputs: self.when("inputs", || {
+                tx.inputs_sorted_set()
                     .iter()
-                    .map(|x| self.map_vkey_witness(x))
-                    .collect(),
-                script: self.collect_all_scripts(tx),
-                plutus_datums: tx
-                    .plutus_data()
+                    .enumerate()
+                    .map(|(order, i)| self.map_tx_input(i, tx, order as u32, resolved))
+                    .collect()
+            }),
+            outputs: self.when("outputs", || {
+                tx.outputs()
                     .iter()
-                    .map(|x| self.map_plutus_datum(x.deref()))
-                    .collect(),
-            }
-            .into(),
-            collateral: u5c::Collateral {
-                collateral: tx
-                    .collateral()
+                    .map(|x| self.map_tx_output(x, Some(tx)))
+                    .collect()
+            }),
+            certificates: self.when("certificates", || {
+                tx.certs()
                     .iter()
-                    .map(|x| self.map_tx_collateral(x, &resolved, tx))
-                    .collect(),
-                collateral_return: tx
-                    .collateral_return()
-                    .map(|x| self.map_tx_output(&x, Some(tx))),
-                total_collateral: tx.total_collateral().unwrap_or_default(),
-            }
-            .into(),
-            fee: tx.fee().unwrap_or_default(),
-            validity: u5c::TxValidity {
-                start: tx.validity_start().unwrap_or_default(),
-                ttl: tx.ttl().unwrap_or_default(),
-            }
-            .into(),
-            successful: tx.is_valid(),
-            auxiliary: u5c::AuxData {
-                metadata: tx
-                    .metadata()
-                    .collect::<Vec<_>>()
-                    .into_iter()
-                    .map(|(l, d)| self.map_metadata(l, d))
-                    .collect(),
-                scripts: self.collect_all_aux_scripts(tx),
-            }
-            .into(),
+                    .enumerate()
+                    .filter_map(|(order, x)| self.map_cert(x, tx, order as u32))
+                    .collect()
+            }),
+            proposals: self.when("proposals", || {
+                tx.gov_proposals()
+                    .iter()
+                    .map(|x| self.map_gov_proposal(x))
+                    .collect()
+            }),
+            withdrawals: self.when("withdrawals", || {
+                tx.withdrawals_sorted_set()
+                    .iter()
+                    .enumerate()
+                    .map(|(order, x)| self.map_withdrawals(x, tx, order as u32))
+                    .collect()
+            }),
+            mint: self.when("mint", || {
+                tx.mints_sorted_set()
+                    .iter()
+                    .enumerate()
+                    .map(|(order, x)| {
+                        let mut ma = self.map_policy_assets(x);
+
+                        ma.redeemer = tx
+                            .find_mint_redeemer(order as u32)
+                            .map(|r| self.map_redeemer(&r));
+
+                        ma
+                    })
+                    .collect()
+            }),
+            reference_inputs: self.when("reference_inputs", || {
+                tx.reference_inputs()
+                    .iter()
+                    .map(|x| self.map_tx_reference_input(x, resolved, tx))
+                    .collect()
+            }),
+            witnesses: self.when("witnesses", || {
+                u5c::WitnessSet {
+                    vkeywitness: tx
+                        .vkey_witnesses()
+                        .iter()
+                        .map(|x| self.map_vkey_witness(x))
+                        .collect(),
+                    script: self.collect_all_scripts(tx),
+                    plutus_datums: tx
+                        .plutus_data()
+                        .iter()
+                        .map(|x| self.map_plutus_datum(x.deref()))
+                        .collect(),
+                }
+                .into()
+            }),
+            collateral: self.when("collateral", || {
+                u5c::Collateral {
+                    collateral: tx
+                        .collateral()
+                        .iter()
+                        .map(|x| self.map_tx_collateral(x, resolved, tx))
+                        .collect(),
+                    collateral_return: tx
+                        .collateral_return()
+                        .map(|x| self.map_tx_output(&x, Some(tx))),
+                    total_collateral: tx.total_collateral().unwrap_or_default(),
+                }
+                .into()
+            }),
+            fee: self.when("fee", || tx.fee().unwrap_or_default()),
+            validity: self.when("validity", || {
+                u5c::TxValidity {
+                    start: tx.validity_start().unwrap_or_default(),
+                    ttl: tx.ttl().unwrap_or_default(),
+                }
+                .into()
+            }),
+            successful: self.when("successful", || tx.is_valid()),
+            auxiliary: self.when("auxiliary", || {
+                u5c::AuxData {
+                    metadata: tx
+                        .metadata()
+                        .collect::<Vec<_>>()
+                        .into_iter()
+                        .map(|(l, d)| self.map_metadata(l, d))
+                        .collect(),
+                    scripts: self.collect_all_aux_scripts(tx),
+                }
+                .into()
+            }),
         }
     }
 
+    /// Maps a block, including only the fields selected by the field mask
+    ///
+    /// Mask paths are relative to the block (eg: `header.slot`). Transaction
+    /// fields are selected via the `body.tx` prefix (eg: `body.tx.hash`).
     pub fn map_block(&self, block: &trv::MultiEraBlock) -> u5c::Block {
+        self.map_block_with(block, |mapper, txs| {
+            txs.iter().map(|x| mapper.map_tx(x)).collect()
+        })
+    }
+
+    /// Maps a block delegating the mapping of its transactions, which
+    /// receives the mapper masked for the `body.tx` field
+    fn map_block_with(
+        &self,
+        block: &trv::MultiEraBlock,
+        map_txs: impl FnOnce(&Self, &[trv::MultiEraTx]) -> Vec<u5c::Tx>,
+    ) -> u5c::Block {
         u5c::Block {
-            header: u5c::BlockHeader {
-                slot: block.slot(),
-                hash: block.hash().to_vec().into(),
-                height: block.number(),
-            }
-            .into(),
-            body: u5c::BlockBody {
-                tx: block.txs().iter().map(|x| self.map_tx(x)).collect(),
-            }
-            .into(),
+            header: self.when("header", || {
+                u5c::BlockHeader {
+                    slot: self.when("header.slot", || block.slot()),
+                    hash: self.when("header.hash", || block.hash().to_vec().into()),
+                    height: self.when("header.height", || block.number()),
+                }
+                .into()
+            }),
+            body: self.when("body", || {
+                let tx = match self.select("body.tx") {
+                    Some(mask) => {
+                        let mapper = self.masked(mask);
+                        map_txs(&mapper, &block.txs())
+                    }
+                    None => vec![],
+                };
+
+                u5c::BlockBody { tx }.into()
+            }),
         }
     }
 
+    /// Decodes and maps a block from its CBOR bytes
+    ///
+    /// # Panics
+    ///
+    /// Panics if the block can't be decoded. Meant as a shortcut for tests and
+    /// trusted data, use [`Mapper::try_map_block_cbor`] for anything else.
     pub fn map_block_cbor(&self, raw: &[u8]) -> u5c::Block {
         let block = trv::MultiEraBlock::decode(raw).unwrap();
         self.map_block(&block)
     }
+
+    /// Decodes and maps a block from its CBOR bytes, propagating any decoding
+    /// error
+    pub fn try_map_block_cbor(&self, raw: &[u8]) -> Result<u5c::Block, trv::Error> {
+        let block = trv::MultiEraBlock::decode(raw)?;
+        Ok(self.map_block(&block))
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<C: LedgerContext + Send + Sync> Mapper<C> {
+    /// Maps a block like [`Mapper::map_block`], mapping its transactions in
+    /// parallel
+    ///
+    /// Each transaction resolves its inputs through the ledger context on its
+    /// own, so this pays off mostly for large blocks or slow contexts. The
+    /// order of the transactions is preserved.
+    pub fn map_block_par(&self, block: &trv::MultiEraBlock) -> u5c::Block {
+        use rayon::prelude::*;
+
+        self.map_block_with(block, |mapper, txs| {
+            txs.par_iter().map(|x| mapper.map_tx(x)).collect()
+        })
+    }
 }
 
 #[cfg(test)]
@@ -777,4 +909,163 @@ mod tests {
             assert_eq!(expected, current)
         }
     }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn parallel_mapping_preserves_order() {
+        let cbor = hex::decode(include_str!("../../test_data/u5c1.block")).unwrap();
+        let block = pallas_traverse::MultiEraBlock::decode(&cbor).unwrap();
+
+        let mapper = Mapper::new(NoLedger);
+
+        assert_eq!(mapper.map_block(&block), mapper.map_block_par(&block));
+    }
+
+    #[test]
+    fn field_mask_prunes_unselected_fields() {
+        let cbor = hex::decode(include_str!("../../test_data/u5c1.block")).unwrap();
+        let block = pallas_traverse::MultiEraBlock::decode(&cbor).unwrap();
+
+        let full = Mapper::new(NoLedger).map_block(&block);
+
+        let mask = FieldMask {
+            paths: vec!["header.slot".into(), "body.tx.hash".into()],
+        };
+
+        let masked = Mapper::new(NoLedger).masked(mask).map_block(&block);
+
+        let header = masked.header.unwrap();
+        assert_eq!(header.slot, full.header.as_ref().unwrap().slot);
+        assert!(header.hash.is_empty());
+        assert_eq!(header.height, 0);
+
+        let txs = masked.body.unwrap().tx;
+        let full_txs = full.body.unwrap().tx;
+        assert_eq!(txs.len(), full_txs.len());
+
+        for (tx, full_tx) in txs.iter().zip(full_txs.iter()) {
+            assert_eq!(tx.hash, full_tx.hash);
+            assert!(tx.inputs.is_empty());
+            assert!(tx.outputs.is_empty());
+            assert!(tx.witnesses.is_none());
+            assert_eq!(tx.fee, 0);
+        }
+
+        let mask = FieldMask {
+            paths: vec!["header".into()],
+        };
+
+        let masked = Mapper::new(NoLedger).masked(mask).map_block(&block);
+        assert_eq!(masked.header, full.header);
+        assert!(masked.body.is_none());
+    }
+
+    #[test]
+    fn map_tx_with_external_utxos() {
+        let cbor = hex::decode(include_str!("../../test_data/babbage1.tx")).unwrap();
+        let tx = trv::MultiEraTx::decode_for_era(trv::Era::Babbage, &cbor).unwrap();
+
+        let output = babbage::TransactionOutput::Legacy(alonzo::TransactionOutput {
+            address: hex::decode("61e44ba50d9e01fd0f3e7ac1ba4f0468a56fb0f5eeef1bf2da4b0c5b1d")
+                .unwrap()
+                .into(),
+            amount: alonzo::Value::Coin(5_000_000),
+            datum_hash: None,
+        });
+
+        let output = pallas_codec::minicbor::to_vec(&output).unwrap();
+
+        let utxos: UtxoMap = tx
+            .inputs()
+            .iter()
+            .map(|x| {
+                (
+                    (*x.hash(), x.index() as u32),
+                    (trv::Era::Babbage, output.clone()),
+                )
+            })
+            .collect();
+
+        let mapper = Mapper::new(NoLedger);
+
+        let unresolved = mapper.map_tx(&tx);
+        assert!(unresolved.inputs.iter().all(|x| x.as_output.is_none()));
+
+        let resolved = mapper.map_tx_with_utxos(&tx, &utxos);
+        assert_eq!(resolved.inputs.len(), tx.inputs().len());
+
+        for input in resolved.inputs.iter() {
+            assert_eq!(input.as_output.as_ref().unwrap().coin, 5_000_000);
+        }
+    }
+
+    #[test]
+    fn native_script_any_keeps_its_kind() {
+        let key = alonzo::NativeScript::ScriptPubkey([1; 28].into());
+
+        let all = Mapper::<NoLedger>::map_native_script(&alonzo::NativeScript::ScriptAll(vec![
+            key.clone(),
+        ]));
+
+        let any = Mapper::<NoLedger>::map_native_script(&alonzo::NativeScript::ScriptAny(vec![
+            key.clone(),
+        ]));
+
+        let items = vec![Mapper::<NoLedger>::map_native_script(&key)];
+
+        assert_eq!(
+            all.native_script,
+            Some(u5c::native_script::NativeScript::ScriptAll(
+                u5c::NativeScriptList {
+                    items: items.clone()
+                }
+            ))
+        );
+
+        assert_eq!(
+            any.native_script,
+            Some(u5c::native_script::NativeScript::ScriptAny(
+                u5c::NativeScriptList { items }
+            ))
+        );
+    }
+
+    #[test]
+    fn plutus_v3_witness_scripts_are_mapped() {
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let mut tx: conway::Tx = pallas_codec::minicbor::decode(&cbor).unwrap();
+
+        let script = conway::PlutusScript::<3>(
+            hex::decode("4e4d01000033222220051200120011")
+                .unwrap()
+                .into(),
+        );
+
+        tx.transaction_witness_set.plutus_v3_script =
+            Some(vec![script.clone()].try_into().unwrap());
+
+        let cbor = pallas_codec::minicbor::to_vec(&tx).unwrap();
+        let tx = trv::MultiEraTx::decode_for_era(trv::Era::Conway, &cbor).unwrap();
+
+        let mapped = Mapper::new(NoLedger).map_tx(&tx);
+        let scripts = mapped.witnesses.unwrap().script;
+
+        assert_eq!(
+            scripts,
+            vec![u5c::Script {
+                script: Some(u5c::script::Script::PlutusV3(script.0.to_vec().into())),
+            }]
+        );
+    }
+
+    #[test]
+    fn try_map_block_cbor_fails_on_bad_input() {
+        let mapper = Mapper::new(NoLedger);
+
+        let cbor = hex::decode(include_str!("../../test_data/u5c1.block")).unwrap();
+        assert!(mapper.try_map_block_cbor(&cbor).is_ok());
+
+        assert!(mapper.try_map_block_cbor(&cbor[..cbor.len() / 2]).is_err());
+        assert!(mapper.try_map_block_cbor(&[0xff, 0x00]).is_err());
+    }
 }
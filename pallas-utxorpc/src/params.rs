@@ -1,5 +1,5 @@
 use pallas_applying::MultiEraProtocolParameters;
-use pallas_primitives::conway;
+use pallas_primitives::{alonzo, conway};
 use utxorpc_spec::utxorpc::v1alpha::cardano as u5c;
 
 use crate::{rational_number_to_u5c, LedgerContext, Mapper};
@@ -30,6 +30,7 @@ impl<C: LedgerContext> Mapper<C> {
                 coins_per_utxo_byte: params.ada_per_utxo_byte,
                 stake_key_deposit: params.key_deposit,
                 pool_deposit: params.pool_deposit,
+                pool_retirement_epoch_bound: params.maximum_epoch,
                 desired_number_of_pools: params.desired_number_of_stake_pools.into(),
                 pool_influence: Some(rational_number_to_u5c(params.pool_pledge_influence)),
                 monetary_expansion: Some(rational_number_to_u5c(params.expansion_rate)),
@@ -50,10 +51,11 @@ impl<C: LedgerContext> Mapper<C> {
                     params.max_block_ex_units,
                 )),
                 cost_models: u5c::CostModels {
-                    // Only plutusv1.
+                    // Alonzo only knows about plutusv1.
                     plutus_v1: params
                         .cost_models_for_script_languages
-                        .first()
+                        .iter()
+                        .find(|(lang, _)| *lang == alonzo::Language::PlutusV1)
                         .map(|(_, data)| u5c::CostModel {
                             values: data.to_vec(),
                         }),
@@ -70,6 +72,7 @@ impl<C: LedgerContext> Mapper<C> {
                 min_fee_constant: params.minfee_b.into(),
                 stake_key_deposit: params.key_deposit,
                 pool_deposit: params.pool_deposit,
+                pool_retirement_epoch_bound: params.maximum_epoch,
                 desired_number_of_pools: params.desired_number_of_stake_pools.into(),
                 pool_influence: Some(rational_number_to_u5c(params.pool_pledge_influence)),
                 monetary_expansion: Some(rational_number_to_u5c(params.expansion_rate)),
@@ -90,6 +93,7 @@ impl<C: LedgerContext> Mapper<C> {
                 coins_per_utxo_byte: params.ada_per_utxo_byte,
                 stake_key_deposit: params.key_deposit,
                 pool_deposit: params.pool_deposit,
+                pool_retirement_epoch_bound: params.maximum_epoch,
                 desired_number_of_pools: params.desired_number_of_stake_pools.into(),
                 pool_influence: Some(rational_number_to_u5c(params.pool_pledge_influence)),
                 monetary_expansion: Some(rational_number_to_u5c(params.expansion_rate)),
@@ -139,6 +143,7 @@ impl<C: LedgerContext> Mapper<C> {
                 coins_per_utxo_byte: params.ada_per_utxo_byte,
                 stake_key_deposit: params.key_deposit,
                 pool_deposit: params.pool_deposit,
+                pool_retirement_epoch_bound: params.maximum_epoch,
                 desired_number_of_pools: params.desired_number_of_stake_pools.into(),
                 pool_influence: Some(rational_number_to_u5c(params.pool_pledge_influence)),
                 monetary_expansion: Some(rational_number_to_u5c(params.expansion_rate)),
@@ -212,12 +217,18 @@ impl<C: LedgerContext> Mapper<C> {
                         .map(|values| u5c::CostModel { values }),
                 }
                 .into(),
-                ..Default::default()
             },
             _ => unimplemented!(),
         }
     }
 
+    /// Maps a Conway protocol parameter update (as found in `ParameterChange`
+    /// governance actions)
+    ///
+    /// u5c has no way to represent an unset scalar param, params not present
+    /// in the update are mapped to their default value. The protocol version
+    /// is not part of Conway updates (it changes via `HardForkInitiation`) and
+    /// is always `None`.
     pub fn map_conway_pparams_update(&self, x: &conway::ProtocolParamUpdate) -> u5c::PParams {
         u5c::PParams {
             coins_per_utxo_byte: x.ada_per_utxo_byte.unwrap_or_default(),
@@ -300,3 +311,92 @@ impl<C: LedgerContext> Mapper<C> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use pallas_traverse as trv;
+
+    use super::*;
+    use crate::{TxoRef, UtxoMap};
+
+    #[derive(Clone)]
+    struct NoLedger;
+
+    impl LedgerContext for NoLedger {
+        fn get_utxos(&self, _refs: &[TxoRef]) -> Option<UtxoMap> {
+            None
+        }
+    }
+
+    #[test]
+    fn conway_pparams_update_maps_every_field() {
+        // block with a parameter-change proposal that sets every updatable
+        // param
+        let cbor = hex::decode(include_str!("../../test_data/conway4.block")).unwrap();
+        let block = trv::MultiEraBlock::decode(&cbor).unwrap();
+
+        let update = block
+            .txs()
+            .iter()
+            .flat_map(|tx| tx.gov_proposals())
+            .find_map(|x| match &x.as_conway()?.gov_action {
+                conway::GovAction::ParameterChange(_, update, _) => Some(update.clone()),
+                _ => None,
+            })
+            .unwrap();
+
+        let mapped = Mapper::new(NoLedger).map_conway_pparams_update(&update);
+
+        // default values are omitted from the json output, any field missing
+        // from it is a param that didn't make it through the mapping
+        let json = serde_json::to_value(&mapped).unwrap();
+        let present: BTreeSet<_> = json.as_object().unwrap().keys().cloned().collect();
+
+        let expected: BTreeSet<_> = [
+            "coinsPerUtxoByte",
+            "maxTxSize",
+            "minFeeCoefficient",
+            "minFeeConstant",
+            "maxBlockBodySize",
+            "maxBlockHeaderSize",
+            "stakeKeyDeposit",
+            "poolDeposit",
+            "poolRetirementEpochBound",
+            "desiredNumberOfPools",
+            "poolInfluence",
+            "monetaryExpansion",
+            "treasuryExpansion",
+            "minPoolCost",
+            "maxValueSize",
+            "collateralPercentage",
+            "maxCollateralInputs",
+            "costModels",
+            "prices",
+            "maxExecutionUnitsPerTransaction",
+            "maxExecutionUnitsPerBlock",
+            "minFeeScriptRefCostPerByte",
+            "poolVotingThresholds",
+            "drepVotingThresholds",
+            "minCommitteeSize",
+            "committeeTermLimit",
+            "governanceActionValidityPeriod",
+            "governanceActionDeposit",
+            "drepDeposit",
+            "drepInactivityPeriod",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        assert_eq!(present, expected);
+
+        assert_eq!(mapped.pool_voting_thresholds.unwrap().thresholds.len(), 5);
+        assert_eq!(mapped.drep_voting_thresholds.unwrap().thresholds.len(), 10);
+        assert_eq!(
+            mapped.cost_models.unwrap().plutus_v1.unwrap().values,
+            vec![1, 2]
+        );
+    }
+}
@@ -0,0 +1,266 @@
+//! Mapping from u5c structures back into Pallas primitives
+//!
+//! Only the parts of a tx that carry enough information to rebuild the
+//! original primitive are supported. Outputs are always rebuilt in their
+//! post-Alonzo (map) form, since the u5c representation doesn't distinguish
+//! legacy outputs.
+
+use pallas_codec::minicbor;
+use pallas_codec::utils::{CborWrap, NonEmptyKeyValuePairs, PositiveCoin};
+use pallas_crypto::hash::Hash;
+use pallas_primitives::{alonzo, babbage, conway, PlutusScript, TransactionInput};
+use thiserror::Error;
+use utxorpc_spec::utxorpc::v1alpha::cardano as u5c;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("missing required field {0}")]
+    MissingField(&'static str),
+
+    #[error("invalid hash size {0}")]
+    InvalidHashSize(usize),
+
+    #[error("invalid asset amount, outputs can't hold zero tokens")]
+    InvalidAssetAmount,
+
+    #[error("policy {0} has no assets")]
+    EmptyPolicy(Hash<28>),
+
+    #[error("invalid datum cbor {0}")]
+    InvalidDatum(minicbor::decode::Error),
+}
+
+fn hash<const BYTES: usize>(x: &[u8]) -> Result<Hash<BYTES>, Error> {
+    let bytes: [u8; BYTES] = x.try_into().map_err(|_| Error::InvalidHashSize(x.len()))?;
+    Ok(Hash::new(bytes))
+}
+
+pub fn map_tx_input(x: &u5c::TxInput) -> Result<TransactionInput, Error> {
+    Ok(TransactionInput {
+        transaction_id: hash(&x.tx_hash)?,
+        index: x.output_index as u64,
+    })
+}
+
+pub fn map_native_script(x: &u5c::NativeScript) -> Result<alonzo::NativeScript, Error> {
+    use u5c::native_script::NativeScript as Inner;
+
+    let items = |x: &[u5c::NativeScript]| {
+        x.iter()
+            .map(map_native_script)
+            .collect::<Result<Vec<_>, _>>()
+    };
+
+    let script = match x.native_script.as_ref() {
+        Some(Inner::ScriptPubkey(x)) => alonzo::NativeScript::ScriptPubkey(hash(x)?),
+        Some(Inner::ScriptAll(x)) => alonzo::NativeScript::ScriptAll(items(&x.items)?),
+        Some(Inner::ScriptAny(x)) => alonzo::NativeScript::ScriptAny(items(&x.items)?),
+        Some(Inner::ScriptNOfK(x)) => alonzo::NativeScript::ScriptNOfK(x.k, items(&x.scripts)?),
+        Some(Inner::InvalidBefore(x)) => alonzo::NativeScript::InvalidBefore(*x),
+        Some(Inner::InvalidHereafter(x)) => alonzo::NativeScript::InvalidHereafter(*x),
+        None => return Err(Error::MissingField("native_script")),
+    };
+
+    Ok(script)
+}
+
+pub fn map_script(x: &u5c::Script) -> Result<conway::ScriptRef, Error> {
+    use u5c::script::Script as Inner;
+
+    let script = match x.script.as_ref() {
+        Some(Inner::Native(x)) => conway::ScriptRef::NativeScript(map_native_script(x)?),
+        Some(Inner::PlutusV1(x)) => {
+            conway::ScriptRef::PlutusV1Script(PlutusScript(x.to_vec().into()))
+        }
+        Some(Inner::PlutusV2(x)) => {
+            conway::ScriptRef::PlutusV2Script(PlutusScript(x.to_vec().into()))
+        }
+        Some(Inner::PlutusV3(x)) => {
+            conway::ScriptRef::PlutusV3Script(PlutusScript(x.to_vec().into()))
+        }
+        None => return Err(Error::MissingField("script")),
+    };
+
+    Ok(script)
+}
+
+/// Rebuilds the datum option of an output
+///
+/// Inline datums are decoded from their original CBOR, which the forward
+/// mapping always includes. Datums with only a hash are referenced by hash.
+pub fn map_datum(x: &u5c::Datum) -> Result<Option<conway::DatumOption>, Error> {
+    if !x.original_cbor.is_empty() {
+        let data = minicbor::decode(&x.original_cbor).map_err(Error::InvalidDatum)?;
+        return Ok(Some(babbage::PseudoDatumOption::Data(CborWrap(data))));
+    }
+
+    if !x.hash.is_empty() {
+        return Ok(Some(babbage::PseudoDatumOption::Hash(hash(&x.hash)?)));
+    }
+
+    Ok(None)
+}
+
+pub fn map_value(coin: u64, assets: &[u5c::Multiasset]) -> Result<conway::Value, Error> {
+    if assets.is_empty() {
+        return Ok(conway::Value::Coin(coin));
+    }
+
+    // group by policy, the forward mapping might have split a policy in many
+    // entries
+    let mut policies: Vec<(Hash<28>, Vec<(pallas_primitives::Bytes, PositiveCoin)>)> = vec![];
+
+    for multiasset in assets {
+        let policy = hash(&multiasset.policy_id)?;
+
+        let index = match policies.iter().position(|(x, _)| *x == policy) {
+            Some(index) => index,
+            None => {
+                policies.push((policy, vec![]));
+                policies.len() - 1
+            }
+        };
+
+        for asset in multiasset.assets.iter() {
+            let amount =
+                PositiveCoin::try_from(asset.output_coin).map_err(|_| Error::InvalidAssetAmount)?;
+
+            policies[index].1.push((asset.name.to_vec().into(), amount));
+        }
+    }
+
+    let multiasset = policies
+        .into_iter()
+        .map(|(policy, assets)| {
+            NonEmptyKeyValuePairs::new(assets)
+                .map(|x| (policy, x))
+                .map_err(|_| Error::EmptyPolicy(policy))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let multiasset =
+        NonEmptyKeyValuePairs::new(multiasset).map_err(|_| Error::MissingField("assets"))?;
+
+    Ok(conway::Value::Multiasset(coin, multiasset))
+}
+
+pub fn map_tx_output(x: &u5c::TxOutput) -> Result<conway::TransactionOutput, Error> {
+    let datum_option = match x.datum.as_ref() {
+        Some(x) => map_datum(x)?,
+        None => None,
+    };
+
+    let script_ref = x.script.as_ref().map(map_script).transpose()?.map(CborWrap);
+
+    Ok(conway::TransactionOutput::PostAlonzo(
+        conway::PostAlonzoTransactionOutput {
+            address: x.address.to_vec().into(),
+            value: map_value(x.coin, &x.assets)?,
+            datum_option,
+            script_ref,
+        },
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas_traverse::{Era, MultiEraOutput, MultiEraTx};
+
+    use super::*;
+    use crate::{LedgerContext, Mapper, TxoRef, UtxoMap};
+
+    #[derive(Clone)]
+    struct NoLedger;
+
+    impl LedgerContext for NoLedger {
+        fn get_utxos(&self, _refs: &[TxoRef]) -> Option<UtxoMap> {
+            None
+        }
+    }
+
+    #[test]
+    fn inputs_and_outputs_roundtrip() {
+        let mapper = Mapper::new(NoLedger);
+
+        let cbor = hex::decode(include_str!("../../test_data/conway1.tx")).unwrap();
+        let tx = MultiEraTx::decode_for_era(Era::Conway, &cbor).unwrap();
+
+        for input in tx.inputs() {
+            let mapped = mapper.map_tx_input(&input, &tx, 0, &None);
+            let reversed = map_tx_input(&mapped).unwrap();
+
+            assert_eq!(reversed.transaction_id, *input.hash());
+            assert_eq!(reversed.index, input.index());
+        }
+
+        let owned: conway::Tx = tx.as_conway().unwrap().clone().into();
+
+        // include an output with an inline datum, a script ref and assets
+        let mut outputs = owned.transaction_body.outputs.clone();
+
+        let mut reference = owned.transaction_body.outputs[0].clone();
+        if let conway::TransactionOutput::PostAlonzo(x) = &mut reference {
+            let data = tx.redeemers()[0].data().clone();
+
+            let script = alonzo::NativeScript::ScriptAny(vec![
+                alonzo::NativeScript::InvalidBefore(10),
+                alonzo::NativeScript::ScriptNOfK(
+                    1,
+                    vec![alonzo::NativeScript::InvalidHereafter(20)],
+                ),
+            ]);
+
+            x.datum_option = Some(babbage::PseudoDatumOption::Data(CborWrap(data)));
+            x.script_ref = Some(CborWrap(conway::ScriptRef::NativeScript(script)));
+        }
+        outputs.push(reference);
+
+        for output in outputs.iter() {
+            let cbor = minicbor::to_vec(output).unwrap();
+            let decoded = MultiEraOutput::decode(Era::Conway, &cbor).unwrap();
+
+            let mapped = mapper.map_tx_output(&decoded, Some(&tx));
+            let reversed = map_tx_output(&mapped).unwrap();
+
+            let reversed_cbor = minicbor::to_vec(&reversed).unwrap();
+            let remapped = MultiEraOutput::decode(Era::Conway, &reversed_cbor).unwrap();
+            assert_eq!(mapper.map_tx_output(&remapped, Some(&tx)), mapped);
+
+            // legacy outputs are rebuilt in post-alonzo form, the rest give back the
+            // original bytes
+            if let conway::TransactionOutput::PostAlonzo(_) = output {
+                assert_eq!(reversed_cbor, cbor);
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_parts_are_rejected() {
+        let input = u5c::TxInput {
+            tx_hash: vec![0; 31].into(),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            map_tx_input(&input),
+            Err(Error::InvalidHashSize(31))
+        ));
+
+        let asset = u5c::Asset {
+            name: vec![1].into(),
+            output_coin: 0,
+            mint_coin: 0,
+        };
+
+        let multiasset = u5c::Multiasset {
+            policy_id: vec![0; 28].into(),
+            assets: vec![asset],
+            redeemer: None,
+        };
+
+        assert!(matches!(
+            map_value(1, &[multiasset]),
+            Err(Error::InvalidAssetAmount)
+        ));
+    }
+}
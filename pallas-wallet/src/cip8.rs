@@ -0,0 +1,63 @@
+//! Signing of arbitrary data following CIP-8 / CIP-30 `signData`
+
+use pallas_crypto::cip8::{public_key_to_cose_key, CoseSign1};
+
+use crate::PrivateKey;
+
+/// The result of signing data, equivalent to the CIP-30 `DataSignature`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataSignature {
+    /// CBOR of the COSE_Sign1 structure
+    pub signature: Vec<u8>,
+    /// CBOR of the COSE_Key of the signer
+    pub key: Vec<u8>,
+}
+
+/// Signs a payload on behalf of an address, as a browser wallet would do
+/// through the CIP-30 `signData` endpoint
+///
+/// The address is the raw bytes of the address controlled by the key, it's
+/// embedded in the protected headers so that verifiers can check the key
+/// against it.
+pub fn sign_data(private_key: &PrivateKey, address: &[u8], payload: &[u8]) -> DataSignature {
+    let mut message = CoseSign1::new(address, payload);
+
+    let signature = private_key.sign(message.sig_structure(payload));
+    message.set_signature(&signature);
+
+    DataSignature {
+        signature: message.to_bytes(),
+        key: public_key_to_cose_key(&private_key.public_key()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pallas_crypto::{
+        cip8::verify_data_signature,
+        hash::Hasher,
+        key::ed25519::{SecretKey, SecretKeyExtended},
+    };
+    use rand::rngs::OsRng;
+
+    use super::*;
+
+    #[test]
+    fn signed_data_verifies() {
+        let keys: [PrivateKey; 2] = [
+            SecretKey::new(OsRng).into(),
+            SecretKeyExtended::new(OsRng).into(),
+        ];
+
+        for private_key in keys {
+            let key_hash = Hasher::<224>::hash(private_key.public_key().as_ref());
+            let address = [&[0b0110_0001], key_hash.as_ref()].concat();
+
+            let signed = sign_data(&private_key, &address, b"login to dapp");
+
+            let (payload, signer) = verify_data_signature(&signed.signature, &signed.key).unwrap();
+            assert_eq!(payload, b"login to dapp");
+            assert_eq!(signer, address);
+        }
+    }
+}
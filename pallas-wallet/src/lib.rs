@@ -3,6 +3,7 @@ use pallas_crypto::key::ed25519::{
 };
 use thiserror::Error;
 
+pub mod cip8;
 pub mod hd;
 pub mod wrapper;
 